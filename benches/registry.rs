@@ -0,0 +1,114 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bridge registry benchmarks.
+//!
+//! Measures `get_enabled` throughput (the exchange hot path), both idle and
+//! while a background admin thread keeps updating bridges.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use permguard_trustplane::bridge::{BridgeConfig, BridgeConfigBuilder, BridgeRegistry};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BRIDGES: usize = 64;
+const READERS: usize = 4;
+
+fn bridge(id: String) -> BridgeConfig {
+    BridgeConfigBuilder::jwt(
+        "https://auth.example.com/.well-known/openid-configuration",
+        "https://auth.example.com",
+    )
+    .id(id)
+    .audience("api")
+    .build()
+    .unwrap()
+}
+
+fn ids() -> Vec<String> {
+    (0..BRIDGES).map(|i| format!("bridge-{}", i)).collect()
+}
+
+fn populated_registry() -> Arc<BridgeRegistry> {
+    let registry = Arc::new(BridgeRegistry::new());
+    for id in ids() {
+        registry.add(bridge(id)).unwrap();
+    }
+    registry
+}
+
+fn bench_get_enabled(c: &mut Criterion) {
+    let registry = populated_registry();
+    let ids = ids();
+
+    c.bench_function("get_enabled", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % BRIDGES;
+            black_box(registry.get_enabled(&ids[i]))
+        })
+    });
+}
+
+fn bench_get_enabled_under_churn(c: &mut Criterion) {
+    let registry = populated_registry();
+    let ids = ids();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Admin churn: keep rewriting bridges while reads are measured
+    let writer = {
+        let registry = registry.clone();
+        let ids = ids.clone();
+        let stop = stop.clone();
+        thread::spawn(move || {
+            let mut i = 0;
+            while !stop.load(Ordering::Relaxed) {
+                i = (i + 1) % BRIDGES;
+                registry.update(bridge(ids[i].clone()), None).unwrap();
+                thread::sleep(Duration::from_micros(50));
+            }
+        })
+    };
+
+    // Concurrent readers, as on the exchange path under load
+    c.bench_function("get_enabled_under_churn", |b| {
+        b.iter_custom(|iters| {
+            let per_reader = iters / READERS as u64 + 1;
+            let start = Instant::now();
+            thread::scope(|scope| {
+                for r in 0..READERS {
+                    let registry = &registry;
+                    let ids = &ids;
+                    scope.spawn(move || {
+                        for n in 0..per_reader {
+                            let i = (n as usize + r) % BRIDGES;
+                            black_box(registry.get_enabled(&ids[i]));
+                        }
+                    });
+                }
+            });
+            start.elapsed()
+        })
+    });
+
+    stop.store(true, Ordering::Relaxed);
+    writer.join().unwrap();
+}
+
+criterion_group!(benches, bench_get_enabled, bench_get_enabled_under_churn);
+criterion_main!(benches);
@@ -16,6 +16,8 @@
 
  use std::path::Path;
 use std::fs;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let out_dir = "src/proto";
@@ -24,9 +26,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::fs::create_dir_all(out_dir)?;
     }
 
+    // Clients too: the CAT client forwards transitions to a peer Trust Plane
     tonic_build::configure()
         .build_server(true)
-        .build_client(false)
+        .build_client(true)
         .file_descriptor_set_path(format!("{}/descriptor.bin", out_dir))
         .out_dir(out_dir)
         .compile_protos(
@@ -34,6 +37,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/cat.proto",
                 "proto/bridge.proto",
                 "proto/bridge_admin.proto",
+                "proto/info.proto",
             ],
             &["proto/"],
         )?;
@@ -51,8 +55,25 @@ pub mod bridge;
 
 #[path = "permguard.trustplane.bridge_admin.v1.rs"]
 pub mod bridge_admin;
+
+#[path = "permguard.trustplane.info.v1.rs"]
+pub mod info;
 "#,
     )?;
 
+    // Build info, exposed by the version endpoints
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=PERMGUARD_GIT_COMMIT={}", git_commit);
+
+    let build_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    println!("cargo:rustc-env=PERMGUARD_BUILD_TIMESTAMP={}", build_timestamp);
+
     Ok(())
 }
\ No newline at end of file
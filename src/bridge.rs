@@ -19,36 +19,78 @@
 //! The Bridge service exchanges external credentials (JWT, SPIFFE, etc.)
 //! for an initial PCA₀ (PIC Causal Authority).
 
-use crate::credentials::CredentialsManager;
+mod dir;
+mod jwt;
+mod pop;
+
+pub use dir::BridgeDir;
+pub use jwt::{jwk_thumbprint, Jwt};
+
+use crate::cache::TtlCache;
+use crate::concurrency::ConcurrencyLimit;
+use crate::credentials::{
+    CredentialsManager, SignedPca, TenantCredentials, DEFAULT_PCA_SIGNING_CONTEXT,
+};
+use crate::deadline;
 use crate::error::{Error, Result};
+use crate::metrics::{LabeledCounter, LatencyHistogram};
+use crate::redact::redact;
+use crate::revocation::RevocationStore;
 use crate::proto::bridge::{
     bridge_service_server::{BridgeService, BridgeServiceServer},
     ExchangeRequest, ExchangeResponse,
 };
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tonic::{Request, Response, Status};
-use tracing::{info, warn};
+use tracing::{debug, info, trace_span, warn};
+use utoipa::ToSchema;
 
 // ============================================================================
 // Bridge Configuration Types
 // ============================================================================
 
 /// Bridge configuration
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BridgeConfig {
     /// Unique identifier
+    #[serde(default)]
     pub id: String,
     /// Bridge type
+    #[serde(rename = "type")]
     pub bridge_type: BridgeType,
     /// Whether the bridge is enabled
     pub enabled: bool,
+    /// Issuer DID for minted PCA₀s (defaults to the Trust Plane DID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pca_issuer: Option<String>,
+    /// Audience minted PCA₀s are bound to when the exchange request names none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pca_audience: Option<String>,
+    /// Why the bridge was disabled, if an operator said so
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// When a removed bridge is deleted for good; set by the registry while
+    /// the deletion grace runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_deletion: Option<DateTime<Utc>>,
+    /// Revision, set by the registry and incremented on every write
+    /// (0: not yet stored)
+    #[serde(default)]
+    pub version: u64,
     /// Type-specific configuration
     pub config: BridgeTypeConfig,
 }
 
 /// Supported bridge types
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum BridgeType {
     /// JWT/OIDC token bridge
     Jwt,
@@ -56,35 +98,924 @@ pub enum BridgeType {
     // Vc,     // Future: Verifiable Credential bridge
 }
 
+impl BridgeType {
+    /// Every bridge type this build supports
+    pub fn all() -> &'static [BridgeType] {
+        // Exhaustive on purpose: a new variant does not compile until listed
+        match BridgeType::Jwt {
+            BridgeType::Jwt => {}
+        }
+        &[BridgeType::Jwt]
+    }
+
+    /// Name of the type, as in configurations
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeType::Jwt => "jwt",
+        }
+    }
+
+    /// JSON Schema of the type-specific configuration (`config.<type>`)
+    pub fn config_schema(&self) -> Value {
+        match self {
+            BridgeType::Jwt => jwt_config_schema(),
+        }
+    }
+}
+
 /// Type-specific bridge configuration
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BridgeTypeConfig {
     /// JWT bridge configuration
     Jwt(JwtBridgeConfig),
 }
 
+/// Default tolerated clock difference with an IdP, in seconds
+pub const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 60;
+
 /// JWT bridge configuration
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JwtBridgeConfig {
     /// OIDC well-known URL for JWKS discovery
     pub wellknown_url: String,
-    /// Expected issuer claim
-    pub issuer: String,
+    /// Accepted issuer claims (`issuer` with a single string is also accepted)
+    #[serde(alias = "issuer", deserialize_with = "one_or_many")]
+    pub issuers: Vec<String>,
     /// Allowed audiences
+    #[serde(default)]
     pub audiences: Vec<String>,
+    /// Reject tokens without an `aud` claim, which otherwise skip the
+    /// audience check
+    #[serde(default)]
+    pub require_audience: bool,
+    /// Require the client to present a nonce matching the token's `nonce`
+    /// claim, binding OIDC ID tokens to the flow they were issued for
+    #[serde(default)]
+    pub require_nonce: bool,
+    /// Require a DPoP-style proof signed by the key in the token's
+    /// `cnf.jwk`. Refused by validation until token signatures are verified
+    /// against the JWKS: until then the key is whatever the token names
+    #[serde(default)]
+    pub require_pop: bool,
     /// Claim mapping configuration
+    #[serde(default = "MappingConfig::with_defaults")]
     pub mapping: MappingConfig,
+    /// PCA₀ attributes an exchange must produce, named as they appear in
+    /// the PCA₀ (with their namespace prefix when `namespace_attributes` is
+    /// on); an exchange missing one is refused
+    #[serde(default)]
+    pub required_pca_attributes: Vec<String>,
+    /// Accepted JWS algorithms (empty: any asymmetric algorithm the JWKS offers)
+    #[serde(default)]
+    pub allowed_algorithms: Vec<String>,
+    /// Tolerated clock difference with the IdP, in seconds.
+    ///
+    /// Applies symmetrically: a token is accepted up to this long after its
+    /// `exp`, and from this long before its `nbf`.
+    #[serde(default = "default_max_clock_skew_secs")]
+    pub max_clock_skew_secs: u64,
+    /// Reject tokens issued longer ago than this, whatever their `exp`.
+    /// When set, tokens must carry `iat`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_token_age_secs: Option<u64>,
+    /// RFC 7638 thumbprints of the only JWKS keys to trust (empty: every
+    /// key the IdP serves). Refused by validation until JWKS keys are
+    /// fetched, as pins could not be enforced
+    #[serde(default)]
+    pub pinned_thumbprints: Vec<String>,
 }
 
-/// Claim to PCA field mapping configuration
-#[derive(Clone, Debug, Default)]
+/// Prefix of claim-derived PCA₀ attributes when namespacing is on
+pub const CLAIM_ATTRIBUTE_PREFIX: &str = "claims.";
+
+/// Prefix of static PCA₀ attributes when namespacing is on
+pub const CONFIG_ATTRIBUTE_PREFIX: &str = "config.";
+
+/// Claims never copied by `copy_all_claims`: token lifetime and replay
+/// bookkeeping that means nothing in a PCA₀
+pub const UNCOPIED_CLAIMS: &[&str] = &["exp", "nbf", "iat", "jti"];
+
+/// Claim to PCA field mapping configuration.
+///
+/// PCA₀ attributes come from two sources: `custom` (copied from claims)
+/// and `initial_attributes` (static). `copy_all_claims` adds every other
+/// permitted claim under its own name, below both: an attribute either
+/// source sets is never replaced by a copied claim. With `namespace_attributes` they land
+/// under [`CLAIM_ATTRIBUTE_PREFIX`] and [`CONFIG_ATTRIBUTE_PREFIX`], so the
+/// sources can never collide. Without it both share one flat namespace, and
+/// a name configured in both is rejected when the bridge is stored rather
+/// than one silently overwriting the other. Attribute names may not start
+/// with either prefix in flat mode, so a prefixed name always means the
+/// attribute was namespaced.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MappingConfig {
     /// Claim to use for subject (default: "sub")
+    #[serde(default = "default_subject_claim")]
     pub subject_claim: String,
-    /// Claim to use for organization (default: "org")
-    pub organization_claim: String,
-    /// Custom claim mappings
+    /// Candidate claims for organization, first present wins (default: ["org"])
+    #[serde(
+        alias = "organization_claim",
+        default = "default_organization_claims",
+        deserialize_with = "one_or_many"
+    )]
+    pub organization_claims: Vec<String>,
+    /// Organization when none of the candidate claims is present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_organization: Option<String>,
+    /// Custom claim mappings (PCA attribute -> claim)
+    #[serde(default)]
     pub custom: HashMap<String, String>,
+    /// Static attributes set on every PCA₀ from this bridge
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub initial_attributes: HashMap<String, String>,
+    /// Prefix claim-derived attributes with `claims.` and static ones with
+    /// `config.`
+    #[serde(default)]
+    pub namespace_attributes: bool,
+    /// Claims that may be mapped (empty: any claim not denied)
+    #[serde(default)]
+    pub allowed_claims: Vec<String>,
+    /// Claims that are never mapped, even if allowed
+    #[serde(default)]
+    pub denied_claims: Vec<String>,
+    /// Copy every permitted claim except [`UNCOPIED_CLAIMS`] into the
+    /// attributes, without listing each in `custom`
+    #[serde(default)]
+    pub copy_all_claims: bool,
+}
+
+/// PCA₀ fields extracted from credential claims
+#[derive(Clone, Debug, Default)]
+pub struct MappedClaims {
+    /// Issuer the credential was accepted from
+    pub issuer: String,
+    /// Subject
+    pub subject: String,
+    /// Organization, if the claim is present
+    pub organization: Option<String>,
+    /// Custom attributes
+    pub attributes: Map<String, Value>,
+}
+
+/// How one PCA₀ field was (or was not) filled from a claim
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct MappingTrace {
+    /// PCA₀ field (`subject`, `organization` or `attributes.<name>`)
+    pub field: String,
+    /// Source claim
+    pub claim: String,
+    /// `copied`, `absent` (claim missing), `not_permitted` (allow/deny lists),
+    /// `default` (configured default organization) or `static` (configured
+    /// initial attribute)
+    pub transform: &'static str,
+}
+
+impl MappingConfig {
+    /// Mapping using the default subject and organization claims
+    pub fn with_defaults() -> Self {
+        Self {
+            subject_claim: default_subject_claim(),
+            organization_claims: default_organization_claims(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether a claim may flow into a PCA₀ under the allow/deny lists
+    pub fn is_claim_permitted(&self, claim: &str) -> bool {
+        if self.denied_claims.iter().any(|c| c == claim) {
+            return false;
+        }
+        self.allowed_claims.is_empty() || self.allowed_claims.iter().any(|c| c == claim)
+    }
+
+    /// Map credential claims to PCA₀ fields
+    pub fn map_claims(&self, claims: &Map<String, Value>) -> Result<MappedClaims> {
+        let subject = self
+            .claim(claims, &self.subject_claim)
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                Error::Invalid(format!("missing subject claim: {}", self.subject_claim))
+            })?
+            .to_string();
+
+        let organization = self
+            .organization_claims
+            .iter()
+            .find_map(|claim| self.claim(claims, claim).and_then(Value::as_str))
+            .map(str::to_string)
+            .or_else(|| self.default_organization.clone());
+
+        let mut attributes = Map::new();
+        for (claim, value) in self.copy_candidates(claims) {
+            if self.is_claim_permitted(claim) {
+                attributes.insert(self.claim_attribute(claim), value.clone());
+            }
+        }
+        for (attribute, value) in &self.initial_attributes {
+            attributes.insert(self.config_attribute(attribute), Value::from(value.as_str()));
+        }
+        for (attribute, claim) in &self.custom {
+            if let Some(value) = self.claim(claims, claim) {
+                attributes.insert(self.claim_attribute(attribute), value.clone());
+            }
+        }
+
+        Ok(MappedClaims {
+            issuer: String::new(),
+            subject,
+            organization,
+            attributes,
+        })
+    }
+
+    /// Explain, field by field, how `claims` would be mapped
+    pub fn trace(&self, claims: &Map<String, Value>) -> Vec<MappingTrace> {
+        let mut custom: Vec<_> = self.custom.iter().collect();
+        custom.sort();
+
+        let transform = |claim: &str| {
+            if !claims.contains_key(claim) {
+                "absent"
+            } else if !self.is_claim_permitted(claim) {
+                "not_permitted"
+            } else {
+                "copied"
+            }
+        };
+
+        let organization = self
+            .organization_claims
+            .iter()
+            .find(|claim| transform(claim) == "copied")
+            .map(|claim| MappingTrace {
+                field: "organization".to_string(),
+                claim: claim.clone(),
+                transform: "copied",
+            })
+            .unwrap_or_else(|| MappingTrace {
+                field: "organization".to_string(),
+                claim: self.organization_claims.join(", "),
+                transform: if self.default_organization.is_some() {
+                    "default"
+                } else if self.organization_claims.iter().any(|c| transform(c) == "not_permitted") {
+                    "not_permitted"
+                } else {
+                    "absent"
+                },
+            });
+
+        let mut trace = vec![
+            MappingTrace {
+                field: "subject".to_string(),
+                claim: self.subject_claim.clone(),
+                transform: transform(&self.subject_claim),
+            },
+            organization,
+        ];
+        trace.extend(custom.into_iter().map(|(attr, claim)| MappingTrace {
+            field: format!("attributes.{}", self.claim_attribute(attr)),
+            claim: claim.clone(),
+            transform: transform(claim),
+        }));
+        let mut initial: Vec<_> = self.initial_attributes.keys().collect();
+        initial.sort();
+        trace.extend(initial.into_iter().map(|attr| MappingTrace {
+            field: format!("attributes.{}", self.config_attribute(attr)),
+            claim: String::new(),
+            transform: "static",
+        }));
+        let explicit: HashSet<String> = trace.iter().map(|t| t.field.clone()).collect();
+        trace.extend(
+            self.copy_candidates(claims)
+                .map(|(claim, _)| MappingTrace {
+                    field: format!("attributes.{}", self.claim_attribute(claim)),
+                    claim: claim.clone(),
+                    transform: transform(claim),
+                })
+                .filter(|t| !explicit.contains(&t.field)),
+        );
+        trace
+    }
+
+    /// Claims `copy_all_claims` would copy, before the allow/deny lists.
+    /// In flat mode, claims named with a reserved prefix are left out.
+    fn copy_candidates<'a>(
+        &'a self,
+        claims: &'a Map<String, Value>,
+    ) -> impl Iterator<Item = (&'a String, &'a Value)> + 'a {
+        claims.iter().filter(move |(claim, _)| {
+            self.copy_all_claims
+                && !UNCOPIED_CLAIMS.contains(&claim.as_str())
+                && (self.namespace_attributes
+                    || !(claim.starts_with(CLAIM_ATTRIBUTE_PREFIX)
+                        || claim.starts_with(CONFIG_ATTRIBUTE_PREFIX)))
+        })
+    }
+
+    /// PCA₀ attribute name for a claim-derived attribute
+    fn claim_attribute(&self, attribute: &str) -> String {
+        if self.namespace_attributes {
+            format!("{}{}", CLAIM_ATTRIBUTE_PREFIX, attribute)
+        } else {
+            attribute.to_string()
+        }
+    }
+
+    /// PCA₀ attribute name for a static attribute
+    fn config_attribute(&self, attribute: &str) -> String {
+        if self.namespace_attributes {
+            format!("{}{}", CONFIG_ATTRIBUTE_PREFIX, attribute)
+        } else {
+            attribute.to_string()
+        }
+    }
+
+    /// First attribute name that breaks the merge rules, with the reason
+    fn attribute_conflict(&self) -> Option<String> {
+        let mut names: Vec<&String> =
+            self.custom.keys().chain(self.initial_attributes.keys()).collect();
+        names.sort();
+        if let Some(name) = names.iter().find(|name| name.is_empty()) {
+            return Some(format!("attribute name {:?} is empty", name));
+        }
+        if self.namespace_attributes {
+            return None;
+        }
+        if let Some(name) = names.windows(2).find(|pair| pair[0] == pair[1]).map(|pair| pair[0]) {
+            return Some(format!(
+                "attribute {} is both mapped from a claim and static; \
+                 rename one or enable namespace_attributes",
+                name
+            ));
+        }
+        let reserved = |name: &&String| {
+            name.starts_with(CLAIM_ATTRIBUTE_PREFIX) || name.starts_with(CONFIG_ATTRIBUTE_PREFIX)
+        };
+        names
+            .into_iter()
+            .find(reserved)
+            .map(|name| format!("attribute {} uses a reserved namespace prefix", name))
+    }
+
+    /// Look up a claim, dropping it if the allow/deny lists forbid it
+    fn claim<'a>(&self, claims: &'a Map<String, Value>, name: &str) -> Option<&'a Value> {
+        let value = claims.get(name)?;
+        if !self.is_claim_permitted(name) {
+            debug!(claim = %name, "Claim not permitted by bridge, dropped");
+            return None;
+        }
+        Some(value)
+    }
+}
+
+fn jwt_config_schema() -> Value {
+    let strings = serde_json::json!({ "type": "array", "items": { "type": "string" } });
+    let string_or_strings = serde_json::json!({
+        "oneOf": [{ "type": "string" }, strings]
+    });
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["wellknown_url", "issuers"],
+        "properties": {
+            "wellknown_url": { "type": "string", "format": "uri" },
+            "issuers": string_or_strings,
+            "audiences": strings,
+            "require_audience": { "type": "boolean", "default": false },
+            "require_nonce": { "type": "boolean", "default": false },
+            "require_pop": { "type": "boolean", "default": false },
+            "required_pca_attributes": strings,
+            "mapping": {
+                "type": "object",
+                "properties": {
+                    "subject_claim": { "type": "string", "default": "sub" },
+                    "organization_claims": string_or_strings,
+                    "default_organization": { "type": "string" },
+                    "custom": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "initial_attributes": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "namespace_attributes": { "type": "boolean", "default": false },
+                    "allowed_claims": strings,
+                    "denied_claims": strings,
+                    "copy_all_claims": { "type": "boolean", "default": false }
+                }
+            },
+            "allowed_algorithms": strings,
+            "max_clock_skew_secs": {
+                "type": "integer",
+                "minimum": 0,
+                "default": DEFAULT_MAX_CLOCK_SKEW_SECS
+            },
+            "max_token_age_secs": { "type": "integer", "minimum": 0 },
+            "pinned_thumbprints": {
+                "type": "array",
+                "items": { "type": "string", "pattern": "^[A-Za-z0-9_-]{43}$" }
+            }
+        }
+    })
+}
+
+fn default_subject_claim() -> String {
+    "sub".to_string()
+}
+
+fn default_max_clock_skew_secs() -> u64 {
+    DEFAULT_MAX_CLOCK_SKEW_SECS
+}
+
+fn default_organization_claims() -> Vec<String> {
+    vec!["org".to_string()]
+}
+
+/// Deserialize either a single string or a list of strings
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(issuer) => vec![issuer],
+        OneOrMany::Many(issuers) => issuers,
+    })
+}
+
+/// Whether `alg` can never be accepted: unsigned or symmetric (HMAC) tokens
+/// would let anyone who knows the public key forge credentials
+fn is_forbidden_algorithm(alg: &str) -> bool {
+    alg.eq_ignore_ascii_case("none") || alg.to_ascii_uppercase().starts_with("HS")
+}
+
+/// Whether `value` has the shape of an RFC 7638 SHA-256 thumbprint
+fn is_thumbprint(value: &str) -> bool {
+    value.len() == 43
+        && value.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+impl JwtBridgeConfig {
+    /// Check a token header `alg` against the bridge's accepted algorithms
+    pub fn check_algorithm(&self, alg: &str) -> Result<()> {
+        let listed = self.allowed_algorithms.is_empty()
+            || self.allowed_algorithms.iter().any(|a| a == alg);
+        if is_forbidden_algorithm(alg) || !listed {
+            return Err(Error::AlgorithmNotAllowed(alg.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Check `exp`, `nbf` and the token age from `iat` against `now` (Unix
+    /// seconds), within the configured clock skew
+    pub fn check_validity(&self, claims: &Map<String, Value>, now: u64) -> Result<()> {
+        let time_claim = |name: &str| match claims.get(name) {
+            None => Ok(None),
+            Some(value) => numeric_date(value).map(Some).ok_or_else(|| {
+                Error::Invalid(format!("{} claim must be a NumericDate", name))
+            }),
+        };
+        let skew = self.max_clock_skew_secs;
+        if let Some(exp) = time_claim("exp")?
+            && now > exp.saturating_add(skew)
+        {
+            return Err(Error::Invalid(format!("Token expired at {}", exp)));
+        }
+        if let Some(nbf) = time_claim("nbf")?
+            && now.saturating_add(skew) < nbf
+        {
+            return Err(Error::Invalid(format!("Token not valid before {}", nbf)));
+        }
+        if let Some(max_age) = self.max_token_age_secs {
+            let iat = time_claim("iat")?.ok_or_else(|| {
+                Error::Invalid("iat claim is required by this bridge".to_string())
+            })?;
+            if iat > now.saturating_add(skew) {
+                return Err(Error::Invalid(format!("Token issued in the future at {}", iat)));
+            }
+            let age = now.saturating_sub(iat);
+            if age > max_age.saturating_add(skew) {
+                return Err(Error::TokenTooOld(format!(
+                    "issued {}s ago, limit is {}s",
+                    age, max_age
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the token's `aud` (a string or an array) against the allowed
+    /// audiences, when any are configured
+    pub fn check_audience(&self, claims: &Map<String, Value>) -> Result<()> {
+        let malformed =
+            || Error::Invalid("aud claim must be a string or an array of strings".to_string());
+        let aud: Vec<&str> = match claims.get("aud") {
+            None => vec![],
+            Some(Value::String(aud)) => vec![aud.as_str()],
+            Some(Value::Array(auds)) => auds
+                .iter()
+                .map(|a| a.as_str().ok_or_else(malformed))
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(malformed()),
+        };
+        if aud.is_empty() {
+            if self.require_audience {
+                return Err(Error::MissingAudience(
+                    "aud claim is required by this bridge".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        let accepted = aud.iter().any(|a| self.audiences.iter().any(|x| x == a));
+        if !self.audiences.is_empty() && !accepted {
+            return Err(Error::Invalid(format!(
+                "Audience not accepted by bridge: {}",
+                aud.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the token's `nonce` claim against the nonce the client
+    /// presented. A presented nonce is always checked; with `require_nonce`
+    /// an exchange without one is refused too.
+    pub fn check_nonce(&self, claims: &Map<String, Value>, nonce: &str) -> Result<()> {
+        if nonce.is_empty() {
+            if self.require_nonce {
+                return Err(Error::NonceMismatch(
+                    "a nonce is required by this bridge".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        match claims.get("nonce").and_then(Value::as_str) {
+            Some(claimed) if claimed == nonce => Ok(()),
+            Some(_) => Err(Error::NonceMismatch(
+                "token nonce does not match the presented nonce".to_string(),
+            )),
+            None => Err(Error::NonceMismatch("token has no nonce claim".to_string())),
+        }
+    }
+
+    /// Check the proof-of-possession presented with a token, refusing one
+    /// already in `seen`. A presented proof is always checked; with
+    /// `require_pop` an exchange without one is refused too.
+    pub fn check_pop(
+        &self,
+        credential: &[u8],
+        claims: &Map<String, Value>,
+        proof: &str,
+        seen: &pop::SeenProofs,
+        now: u64,
+    ) -> Result<()> {
+        if proof.is_empty() {
+            if self.require_pop {
+                return Err(Error::PopVerificationFailed(
+                    "a proof-of-possession is required by this bridge".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        pop::verify_pop(proof, credential, claims, seen, now, self.max_clock_skew_secs)
+    }
+
+    /// Check that mapping produced every attribute the bridge requires,
+    /// naming the first one missing
+    pub fn check_required_attributes(&self, mapped: &MappedClaims) -> Result<()> {
+        match self
+            .required_pca_attributes
+            .iter()
+            .find(|name| !mapped.attributes.contains_key(*name))
+        {
+            Some(name) => Err(Error::MissingRequiredAttribute(name.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Keep the JWKS keys matching a pinned thumbprint, logging any other
+    /// key the IdP serves. Without pins every key is kept.
+    pub fn pinned_keys<'a>(&self, keys: &'a [Value]) -> Vec<&'a Value> {
+        if self.pinned_thumbprints.is_empty() {
+            return keys.iter().collect();
+        }
+        keys.iter()
+            .filter(|key| {
+                let thumbprint = jwk_thumbprint(key);
+                let pinned = thumbprint
+                    .as_ref()
+                    .is_some_and(|t| self.pinned_thumbprints.contains(t));
+                if !pinned {
+                    let kid = key.get("kid").and_then(Value::as_str).unwrap_or_default();
+                    warn!(
+                        kid = %kid,
+                        thumbprint = thumbprint.as_deref().unwrap_or_default(),
+                        wellknown_url = %self.wellknown_url,
+                        "Ignoring JWKS key that matches no pinned thumbprint"
+                    );
+                }
+                pinned
+            })
+            .collect()
+    }
+
+    /// Return the configured issuer matching the token's `iss`, if any
+    pub fn match_issuer(&self, iss: &str) -> Option<&str> {
+        self.issuers.iter().map(String::as_str).find(|i| *i == iss)
+    }
+}
+
+impl BridgeConfig {
+    /// Validate the configuration before it is stored
+    pub fn validate(&self) -> Result<()> {
+        if let Some(pca_issuer) = &self.pca_issuer
+            && !is_did(pca_issuer)
+        {
+            return Err(Error::Invalid(format!(
+                "bridge {}: pca_issuer must be a DID, got {}",
+                self.id, pca_issuer
+            )));
+        }
+        if self.pca_audience.as_deref().is_some_and(|a| a.trim().is_empty()) {
+            return Err(Error::Invalid(format!(
+                "bridge {}: pca_audience cannot be empty",
+                self.id
+            )));
+        }
+        match (&self.bridge_type, &self.config) {
+            (BridgeType::Jwt, BridgeTypeConfig::Jwt(jwt)) => {
+                if jwt.wellknown_url.is_empty() {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: wellknown_url is required",
+                        self.id
+                    )));
+                }
+                if jwt.issuers.is_empty() || jwt.issuers.iter().any(String::is_empty) {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: at least one non-empty issuer is required",
+                        self.id
+                    )));
+                }
+                if let Some(conflict) = jwt.mapping.attribute_conflict() {
+                    return Err(Error::Invalid(format!("bridge {}: {}", self.id, conflict)));
+                }
+                if jwt.required_pca_attributes.iter().any(|a| a.trim().is_empty()) {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: required PCA attribute names cannot be empty",
+                        self.id
+                    )));
+                }
+                let malformed_pin = jwt.pinned_thumbprints.iter().find(|t| !is_thumbprint(t));
+                if let Some(pin) = malformed_pin {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: pinned thumbprint {} is not a base64url SHA-256 digest",
+                        self.id, pin
+                    )));
+                }
+                if jwt.require_pop {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: require_pop is not enforced yet (no JWKS fetch)",
+                        self.id
+                    )));
+                }
+                if !jwt.pinned_thumbprints.is_empty() {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: pinned thumbprints are not enforced yet (no JWKS fetch)",
+                        self.id
+                    )));
+                }
+                let forbidden = jwt.allowed_algorithms.iter().find(|a| is_forbidden_algorithm(a));
+                if let Some(alg) = forbidden {
+                    return Err(Error::Invalid(format!(
+                        "bridge {}: algorithm {} cannot be allowed",
+                        self.id, alg
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fluent construction of a validated [`BridgeConfig`].
+///
+/// Starts from the same defaults as a JSON config that only sets the
+/// required fields; [`build`](Self::build) runs [`BridgeConfig::validate`].
+#[derive(Clone, Debug)]
+pub struct BridgeConfigBuilder {
+    config: BridgeConfig,
+}
+
+impl BridgeConfigBuilder {
+    /// JWT bridge accepting tokens from `issuer`, with keys discovered at
+    /// `wellknown_url`. Enabled by default.
+    pub fn jwt(wellknown_url: impl Into<String>, issuer: impl Into<String>) -> Self {
+        Self {
+            config: BridgeConfig {
+                id: String::new(),
+                bridge_type: BridgeType::Jwt,
+                enabled: true,
+                pca_issuer: None,
+                pca_audience: None,
+                disabled_reason: None,
+                pending_deletion: None,
+                version: 0,
+                config: BridgeTypeConfig::Jwt(JwtBridgeConfig {
+                    wellknown_url: wellknown_url.into(),
+                    issuers: vec![issuer.into()],
+                    audiences: vec![],
+                    require_audience: false,
+                    require_nonce: false,
+                    require_pop: false,
+                    mapping: MappingConfig::with_defaults(),
+                    required_pca_attributes: vec![],
+                    allowed_algorithms: vec![],
+                    max_clock_skew_secs: DEFAULT_MAX_CLOCK_SKEW_SECS,
+                    max_token_age_secs: None,
+                    pinned_thumbprints: vec![],
+                }),
+            },
+        }
+    }
+
+    /// Bridge ID (default: assigned by the registry)
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.config.id = id.into();
+        self
+    }
+
+    /// Whether the bridge accepts exchanges
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    /// Issuer DID for minted PCA₀s
+    pub fn pca_issuer(mut self, did: impl Into<String>) -> Self {
+        self.config.pca_issuer = Some(did.into());
+        self
+    }
+
+    /// Audience minted PCA₀s are bound to by default
+    pub fn pca_audience(mut self, audience: impl Into<String>) -> Self {
+        self.config.pca_audience = Some(audience.into());
+        self
+    }
+
+    /// Accept tokens from another issuer
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.jwt_mut().issuers.push(issuer.into());
+        self
+    }
+
+    /// Accept tokens for another audience
+    pub fn audience(mut self, audience: impl Into<String>) -> Self {
+        self.jwt_mut().audiences.push(audience.into());
+        self
+    }
+
+    /// Reject tokens without an `aud` claim
+    pub fn require_audience(mut self, require: bool) -> Self {
+        self.jwt_mut().require_audience = require;
+        self
+    }
+
+    /// Require exchanges to present the token's `nonce`
+    pub fn require_nonce(mut self, require: bool) -> Self {
+        self.jwt_mut().require_nonce = require;
+        self
+    }
+
+    /// Require exchanges to prove possession of the token's `cnf.jwk` key.
+    /// [`build`](Self::build) refuses it until JWKS keys are fetched
+    pub fn require_pop(mut self, require: bool) -> Self {
+        self.jwt_mut().require_pop = require;
+        self
+    }
+
+    /// PCA₀ attribute every exchange must produce
+    pub fn required_pca_attribute(mut self, name: impl Into<String>) -> Self {
+        self.jwt_mut().required_pca_attributes.push(name.into());
+        self
+    }
+
+    /// Claim mapped to the PCA₀ subject
+    pub fn subject_claim(mut self, claim: impl Into<String>) -> Self {
+        self.jwt_mut().mapping.subject_claim = claim.into();
+        self
+    }
+
+    /// Candidate organization claims, first present wins
+    pub fn organization_claims<I, S>(mut self, claims: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.jwt_mut().mapping.organization_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Organization when no candidate claim is present
+    pub fn default_organization(mut self, organization: impl Into<String>) -> Self {
+        self.jwt_mut().mapping.default_organization = Some(organization.into());
+        self
+    }
+
+    /// Only map `claim` and other allowed claims
+    pub fn allowed_claim(mut self, claim: impl Into<String>) -> Self {
+        self.jwt_mut().mapping.allowed_claims.push(claim.into());
+        self
+    }
+
+    /// Never map `claim`
+    pub fn denied_claim(mut self, claim: impl Into<String>) -> Self {
+        self.jwt_mut().mapping.denied_claims.push(claim.into());
+        self
+    }
+
+    /// Map `claim` to the PCA₀ attribute `attribute`
+    pub fn custom_claim(mut self, attribute: impl Into<String>, claim: impl Into<String>) -> Self {
+        self.jwt_mut().mapping.custom.insert(attribute.into(), claim.into());
+        self
+    }
+
+    /// Set the static attribute `attribute` on every PCA₀
+    pub fn initial_attribute(
+        mut self,
+        attribute: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        let mapping = &mut self.jwt_mut().mapping;
+        mapping.initial_attributes.insert(attribute.into(), value.into());
+        self
+    }
+
+    /// Namespace attributes by source (`claims.` / `config.`)
+    pub fn namespace_attributes(mut self, namespace: bool) -> Self {
+        self.jwt_mut().mapping.namespace_attributes = namespace;
+        self
+    }
+
+    /// Copy every permitted claim into the attributes
+    pub fn copy_all_claims(mut self, copy: bool) -> Self {
+        self.jwt_mut().mapping.copy_all_claims = copy;
+        self
+    }
+
+    /// Accept tokens signed with `alg` (none listed: any asymmetric algorithm)
+    pub fn allowed_algorithm(mut self, alg: impl Into<String>) -> Self {
+        self.jwt_mut().allowed_algorithms.push(alg.into());
+        self
+    }
+
+    /// Tolerated clock difference with the IdP
+    pub fn max_clock_skew_secs(mut self, secs: u64) -> Self {
+        self.jwt_mut().max_clock_skew_secs = secs;
+        self
+    }
+
+    /// Reject tokens issued longer ago than this
+    pub fn max_token_age_secs(mut self, secs: u64) -> Self {
+        self.jwt_mut().max_token_age_secs = Some(secs);
+        self
+    }
+
+    /// Trust only JWKS keys with this RFC 7638 thumbprint (and other pins).
+    /// [`build`](Self::build) refuses pins until JWKS keys are fetched
+    pub fn pinned_thumbprint(mut self, thumbprint: impl Into<String>) -> Self {
+        self.jwt_mut().pinned_thumbprints.push(thumbprint.into());
+        self
+    }
+
+    /// Validate and return the configuration
+    pub fn build(self) -> Result<BridgeConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    fn jwt_mut(&mut self) -> &mut JwtBridgeConfig {
+        let BridgeTypeConfig::Jwt(jwt) = &mut self.config.config;
+        jwt
+    }
+}
+
+/// Whether `value` has the `did:<method>:<method-specific-id>` shape
+fn is_did(value: &str) -> bool {
+    let mut parts = value.splitn(3, ':');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some("did"), Some(method), Some(id))
+            if !method.is_empty()
+                && method.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+                && !id.is_empty()
+    )
 }
 
 // ============================================================================
@@ -92,63 +1023,250 @@ pub struct MappingConfig {
 // ============================================================================
 
 /// Registry for bridge configurations
+///
+/// Reads load an immutable snapshot and never block, so the exchange hot path
+/// is lock-free. Writes are serialized and publish a new snapshot atomically.
 pub struct BridgeRegistry {
-    bridges: RwLock<HashMap<String, BridgeConfig>>,
+    bridges: ArcSwap<HashMap<String, BridgeConfig>>,
+    write_lock: Mutex<()>,
+    /// How long a removed bridge lingers before it is deleted (zero: at once)
+    delete_grace: Duration,
 }
 
 impl BridgeRegistry {
     /// Create new empty registry
     pub fn new() -> Self {
         Self {
-            bridges: RwLock::new(HashMap::new()),
+            bridges: ArcSwap::from_pointee(HashMap::new()),
+            write_lock: Mutex::new(()),
+            delete_grace: Duration::ZERO,
         }
     }
 
-    /// List all bridge configurations
+    /// Keep removed bridges for `grace` before deleting them. Exchanges
+    /// against them fail with [`Error::BridgeDecommissioning`] meanwhile,
+    /// and [`undelete`](Self::undelete) can still bring them back.
+    pub fn with_delete_grace(mut self, grace: Duration) -> Self {
+        self.delete_grace = grace;
+        self
+    }
+
+    /// List all bridge configurations, ordered by ID
     pub fn list(&self) -> Vec<BridgeConfig> {
-        self.bridges.read().unwrap().values().cloned().collect()
+        let mut bridges: Vec<BridgeConfig> = self.bridges.load().values().cloned().collect();
+        bridges.sort_by(|a, b| a.id.cmp(&b.id));
+        bridges
     }
 
     /// Get a bridge configuration by ID
     pub fn get(&self, id: &str) -> Option<BridgeConfig> {
-        self.bridges.read().unwrap().get(id).cloned()
+        self.bridges.load().get(id).cloned()
     }
 
-    /// Add a new bridge configuration
+    /// Add a new bridge configuration. An ID already in use is refused:
+    /// replacing that bridge is an [`update`](Self::update).
     pub fn add(&self, mut config: BridgeConfig) -> Result<String> {
+        config.validate()?;
         if config.id.is_empty() {
             config.id = uuid::Uuid::new_v4().to_string();
         }
+        config.version = 1;
         let id = config.id.clone();
-        self.bridges.write().unwrap().insert(id.clone(), config);
+        self.modify(|bridges| {
+            if bridges.contains_key(&id) {
+                return Err(Error::Invalid(format!("bridge {} already exists", id)));
+            }
+            bridges.insert(id.clone(), config);
+            Ok(())
+        })?;
         info!(bridge_id = %id, "Bridge configuration added");
         Ok(id)
     }
 
-    /// Update an existing bridge configuration
-    pub fn update(&self, config: BridgeConfig) -> Result<()> {
-        let mut bridges = self.bridges.write().unwrap();
-        if !bridges.contains_key(&config.id) {
-            return Err(Error::NotFound(config.id));
+    /// Update an existing bridge configuration.
+    ///
+    /// With an expected version, the update is refused if the bridge was
+    /// written since that version was read.
+    pub fn update(&self, mut config: BridgeConfig, expected_version: Option<u64>) -> Result<()> {
+        config.validate()?;
+        let id = config.id.clone();
+        self.modify(|bridges| {
+            let current = bridges
+                .get(&config.id)
+                .ok_or_else(|| Error::NotFound(config.id.clone()))?;
+            check_version(current, expected_version)?;
+            config.version = current.version + 1;
+            config.pending_deletion = current.pending_deletion;
+            bridges.insert(config.id.clone(), config);
+            Ok(())
+        })?;
+        info!(bridge_id = %id, "Bridge configuration updated");
+        Ok(())
+    }
+
+    /// Remove a bridge configuration, optionally only at an expected version.
+    ///
+    /// With a deletion grace the bridge is only marked for deletion, and
+    /// [`purge_deleted`](Self::purge_deleted) drops it once the grace has
+    /// passed. Removing a bridge already marked keeps its deadline.
+    pub fn remove(&self, id: &str, expected_version: Option<u64>) -> Result<()> {
+        let grace = chrono::Duration::from_std(self.delete_grace)
+            .map_err(|e| Error::Config(format!("bridge delete grace out of range: {}", e)))?;
+        let mut deadline = None;
+        self.modify(|bridges| {
+            let current = bridges
+                .get_mut(id)
+                .ok_or_else(|| Error::NotFound(id.to_string()))?;
+            check_version(current, expected_version)?;
+            if grace.is_zero() {
+                bridges.remove(id);
+            } else if current.pending_deletion.is_none() {
+                current.pending_deletion = Some(Utc::now() + grace);
+                current.version += 1;
+            }
+            deadline = bridges.get(id).and_then(|b| b.pending_deletion);
+            Ok(())
+        })?;
+        match deadline {
+            Some(at) => info!(bridge_id = %id, delete_at = %at, "Bridge marked for deletion"),
+            None => info!(bridge_id = %id, "Bridge configuration removed"),
         }
-        info!(bridge_id = %config.id, "Bridge configuration updated");
-        bridges.insert(config.id.clone(), config);
         Ok(())
     }
 
-    /// Remove a bridge configuration
-    pub fn remove(&self, id: &str) -> Result<()> {
-        let mut bridges = self.bridges.write().unwrap();
-        if bridges.remove(id).is_none() {
-            return Err(Error::NotFound(id.to_string()));
+    /// Cancel the pending deletion of a removed bridge
+    pub fn undelete(&self, id: &str, expected_version: Option<u64>) -> Result<()> {
+        self.modify(|bridges| {
+            let bridge = bridges
+                .get_mut(id)
+                .ok_or_else(|| Error::NotFound(id.to_string()))?;
+            check_version(bridge, expected_version)?;
+            if bridge.pending_deletion.take().is_none() {
+                return Err(Error::Invalid(format!("bridge {} is not pending deletion", id)));
+            }
+            bridge.version += 1;
+            Ok(())
+        })?;
+        info!(bridge_id = %id, "Bridge deletion cancelled");
+        Ok(())
+    }
+
+    /// Delete the bridges whose deletion grace ended by `now`, returning
+    /// their IDs
+    pub fn purge_deleted(&self, now: DateTime<Utc>) -> Vec<String> {
+        let expired = |b: &BridgeConfig| b.pending_deletion.is_some_and(|at| at <= now);
+        if !self.bridges.load().values().any(expired) {
+            return Vec::new();
         }
-        info!(bridge_id = %id, "Bridge configuration removed");
+        let mut purged = Vec::new();
+        let _ = self.modify(|bridges| {
+            bridges.retain(|id, bridge| {
+                let keep = !expired(bridge);
+                if !keep {
+                    purged.push(id.clone());
+                }
+                keep
+            });
+            Ok(())
+        });
+        for id in &purged {
+            info!(bridge_id = %id, "Bridge configuration removed after deletion grace");
+        }
+        purged
+    }
+
+    /// Enable or disable a bridge, leaving the rest of its configuration
+    /// untouched. The reason is kept while disabled and cleared on enable.
+    pub fn set_enabled(
+        &self,
+        id: &str,
+        enabled: bool,
+        reason: Option<String>,
+        expected_version: Option<u64>,
+    ) -> Result<()> {
+        self.modify(|bridges| {
+            let bridge = bridges
+                .get_mut(id)
+                .ok_or_else(|| Error::NotFound(id.to_string()))?;
+            check_version(bridge, expected_version)?;
+            bridge.enabled = enabled;
+            bridge.disabled_reason = if enabled { None } else { reason };
+            bridge.version += 1;
+            Ok(())
+        })?;
+        info!(bridge_id = %id, enabled, "Bridge enabled flag changed");
         Ok(())
     }
 
     /// Get enabled bridge by ID
     pub fn get_enabled(&self, id: &str) -> Option<BridgeConfig> {
-        self.get(id).filter(|b| b.enabled)
+        self.bridges
+            .load()
+            .get(id)
+            .filter(|b| b.enabled && b.pending_deletion.is_none())
+            .cloned()
+    }
+
+    /// Insert or replace a batch of bridge configurations.
+    ///
+    /// The whole batch is validated first; if any entry is invalid nothing
+    /// is applied. Every entry must carry an explicit, unique ID.
+    pub fn import(&self, configs: Vec<BridgeConfig>) -> Result<usize> {
+        let mut seen = HashSet::new();
+        for config in &configs {
+            if config.id.is_empty() {
+                return Err(Error::Invalid("bridge id is required for import".into()));
+            }
+            if !seen.insert(config.id.as_str()) {
+                return Err(Error::Invalid(format!(
+                    "duplicate bridge id in import: {}",
+                    config.id
+                )));
+            }
+            config.validate()?;
+        }
+
+        let count = configs.len();
+        self.modify(|bridges| {
+            for mut config in configs {
+                config.version = bridges.get(&config.id).map_or(1, |b| b.version + 1);
+                bridges.insert(config.id.clone(), config);
+            }
+            Ok(())
+        })?;
+        info!(count, "Bridge configurations imported");
+        Ok(count)
+    }
+
+    /// Export all bridge configurations, ordered by ID
+    pub fn export(&self) -> Vec<BridgeConfig> {
+        self.list()
+    }
+
+    /// Apply a change to a copy of the current snapshot and publish it.
+    ///
+    /// Writers are serialized, so no concurrent change can be lost between
+    /// the copy and the swap. Nothing is published if `f` fails.
+    fn modify<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut HashMap<String, BridgeConfig>) -> Result<()>,
+    {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut bridges = HashMap::clone(&self.bridges.load());
+        f(&mut bridges)?;
+        self.bridges.store(Arc::new(bridges));
+        Ok(())
+    }
+}
+
+/// Fail unless `bridge` is still at the expected version
+fn check_version(bridge: &BridgeConfig, expected_version: Option<u64>) -> Result<()> {
+    match expected_version {
+        Some(expected) if expected != bridge.version => Err(Error::Invalid(format!(
+            "version conflict: bridge {} is at version {}, expected {}",
+            bridge.id, bridge.version, expected
+        ))),
+        _ => Ok(()),
     }
 }
 
@@ -162,73 +1280,471 @@ impl Default for BridgeRegistry {
 // Bridge gRPC Service
 // ============================================================================
 
-/// Bridge gRPC service implementation
-pub struct BridgeServiceImpl {
-    credentials: Arc<CredentialsManager>,
-    registry: Arc<BridgeRegistry>,
+/// Size limits checked before a credential is verified
+#[derive(Clone, Debug)]
+pub struct CredentialLimits {
+    /// Maximum raw credential size in bytes
+    pub max_token_bytes: usize,
+    /// Maximum number of claims in a token
+    pub max_claims: usize,
+    /// Maximum length of a mapped value (JSON-encoded for non-strings)
+    pub max_value_len: usize,
 }
 
-impl BridgeServiceImpl {
-    /// Create new bridge service
-    pub fn new(credentials: Arc<CredentialsManager>, registry: Arc<BridgeRegistry>) -> Self {
-        Self { credentials, registry }
-    }
-
-    /// Convert to tonic server
-    pub fn into_server(self) -> BridgeServiceServer<Self> {
-        BridgeServiceServer::new(self)
+impl Default for CredentialLimits {
+    fn default() -> Self {
+        Self {
+            max_token_bytes: 64 * 1024,
+            max_claims: 256,
+            max_value_len: 8 * 1024,
+        }
     }
 }
 
-#[tonic::async_trait]
-impl BridgeService for BridgeServiceImpl {
-    async fn exchange(
-        &self,
-        request: Request<ExchangeRequest>,
-    ) -> std::result::Result<Response<ExchangeResponse>, Status> {
-        let req = request.into_inner();
-        
-        // Validate request
-        if req.bridge_id.is_empty() {
-            return Ok(Response::new(ExchangeResponse {
-                pca: vec![],
-                error: "bridge_id is required".to_string(),
-            }));
+impl CredentialLimits {
+    fn check_token(&self, credential: &[u8]) -> Result<()> {
+        if credential.len() > self.max_token_bytes {
+            return Err(Error::CredentialTooLarge(format!(
+                "{} bytes exceeds limit of {}",
+                credential.len(),
+                self.max_token_bytes
+            )));
         }
+        Ok(())
+    }
 
-        if req.credential.is_empty() {
-            return Ok(Response::new(ExchangeResponse {
-                pca: vec![],
-                error: "credential is required".to_string(),
+    fn check_claims(&self, claims: &Map<String, Value>) -> Result<()> {
+        if claims.len() > self.max_claims {
+            return Err(Error::CredentialTooLarge(format!(
+                "{} claims exceeds limit of {}",
+                claims.len(),
+                self.max_claims
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_mapped(&self, mapped: &MappedClaims) -> Result<()> {
+        let fields = [("subject", mapped.subject.len())]
+            .into_iter()
+            .chain(mapped.organization.as_ref().map(|o| ("organization", o.len())))
+            .chain(mapped.attributes.iter().map(|(name, value)| {
+                let len = match value {
+                    Value::String(s) => s.len(),
+                    other => other.to_string().len(),
+                };
+                (name.as_str(), len)
             }));
+
+        for (name, len) in fields {
+            if len > self.max_value_len {
+                return Err(Error::CredentialTooLarge(format!(
+                    "value of {} is {} bytes, limit is {}",
+                    name, len, self.max_value_len
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bridge service options
+#[derive(Clone, Debug)]
+pub struct BridgeOptions {
+    /// Log PCA₀ attribute values (not just keys) at debug level
+    pub log_pca_values: bool,
+    /// How long an idempotency key replays the same PCA₀
+    pub idempotency_ttl: Duration,
+    /// Maximum number of remembered idempotency keys
+    pub idempotency_capacity: usize,
+    /// Inbound credential size limits
+    pub limits: CredentialLimits,
+    /// Return a mapping trace with HTTP exchange responses (debugging only)
+    pub mapping_trace: bool,
+    /// Reuse PCA₀s minted for identical (bridge, credential) exchanges
+    pub cache_exchanges: bool,
+    /// Upper bound on how long a cached PCA₀ is reused (the token `exp` may cut it short)
+    pub exchange_cache_ttl: Duration,
+    /// Maximum number of cached PCA₀s
+    pub exchange_cache_capacity: usize,
+    /// Bridge used when an exchange request names none
+    pub default_bridge_id: Option<String>,
+    /// Maximum exchanges verified at once (0: unlimited)
+    pub max_concurrent_exchanges: usize,
+    /// Domain-separation context PCA₀s are signed under
+    pub signing_context: String,
+}
+
+impl Default for BridgeOptions {
+    fn default() -> Self {
+        Self {
+            log_pca_values: false,
+            idempotency_ttl: Duration::from_secs(300),
+            idempotency_capacity: 10_000,
+            limits: CredentialLimits::default(),
+            mapping_trace: false,
+            cache_exchanges: false,
+            exchange_cache_ttl: Duration::from_secs(300),
+            exchange_cache_capacity: 10_000,
+            default_bridge_id: None,
+            max_concurrent_exchanges: 0,
+            signing_context: DEFAULT_PCA_SIGNING_CONTEXT.to_string(),
         }
+    }
+}
+
+/// PCA₀ minted for an idempotency key, with the request it answered
+#[derive(Clone)]
+struct IdempotentExchange {
+    /// SHA-256 of bridge ID and credential
+    fingerprint: [u8; 32],
+    pca: SignedPca,
+}
+
+/// Metric label value for request values outside the configuration
+const UNKNOWN_LABEL: &str = "unknown";
+
+/// Bridge gRPC service implementation
+pub struct BridgeServiceImpl {
+    tenants: Arc<TenantCredentials>,
+    registry: Arc<BridgeRegistry>,
+    revocations: Arc<RevocationStore>,
+    options: BridgeOptions,
+    idempotency: TtlCache<String, IdempotentExchange>,
+    /// PCA₀s by exchange fingerprint, when exchange caching is enabled
+    exchanges: Option<TtlCache<[u8; 32], SignedPca>>,
+    /// Proofs-of-possession already accepted
+    proofs: pop::SeenProofs,
+    concurrency: ConcurrencyLimit,
+    /// Time spent in each phase of an exchange
+    phase_seconds: LatencyHistogram,
+    /// Exchanges by bridge, bridge type, issuer and outcome
+    outcomes: LabeledCounter,
+}
+
+impl BridgeServiceImpl {
+    /// Create new bridge service
+    pub fn new(
+        credentials: Arc<CredentialsManager>,
+        registry: Arc<BridgeRegistry>,
+        revocations: Arc<RevocationStore>,
+        options: BridgeOptions,
+    ) -> Self {
+        let idempotency = TtlCache::new(options.idempotency_capacity, options.idempotency_ttl);
+        let exchanges = options.cache_exchanges.then(|| {
+            TtlCache::new(options.exchange_cache_capacity, options.exchange_cache_ttl)
+        });
+        let concurrency = ConcurrencyLimit::new("exchange", options.max_concurrent_exchanges);
+        Self {
+            tenants: Arc::new(TenantCredentials::new(credentials)),
+            registry,
+            revocations,
+            options,
+            idempotency,
+            exchanges,
+            proofs: pop::seen_proofs(),
+            concurrency,
+            phase_seconds: LatencyHistogram::new(
+                "trustplane_exchange_phase_seconds",
+                "Time spent in each phase of a bridge exchange",
+                "phase",
+            ),
+            outcomes: LabeledCounter::new(
+                "trustplane_exchanges_total",
+                "Bridge exchanges by bridge, bridge type, issuer and outcome",
+                &["bridge_id", "bridge_type", "issuer", "outcome"],
+            ),
+        }
+    }
+
+    /// Sign with the keys of every hosted DID, not only the default one
+    pub fn with_tenants(mut self, tenants: Arc<TenantCredentials>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    /// Append exchange metrics in Prometheus text format
+    pub fn render_metrics(&self, out: &mut String) {
+        self.outcomes.render(out);
+        self.phase_seconds.render(out);
+    }
+
+    /// Count an exchange. Labels only take values bounded by the
+    /// configuration: request values that name no configured bridge or
+    /// issuer are counted as `unknown`, and subjects are never labels.
+    fn record_outcome(&self, bridge_id: &str, credential: &[u8], result: &Result<SignedPca>) {
+        let outcome = result.as_ref().map_or_else(Error::code, |_| "success");
+        let bridge = self.resolve_bridge_id(bridge_id).ok().and_then(|id| self.registry.get(id));
+        let Some(bridge) = bridge else {
+            self.outcomes.inc(&[UNKNOWN_LABEL, UNKNOWN_LABEL, UNKNOWN_LABEL, outcome]);
+            return;
+        };
+        let issuer = match &bridge.config {
+            BridgeTypeConfig::Jwt(jwt_config) => std::str::from_utf8(credential)
+                .ok()
+                .and_then(|token| Jwt::decode(token).ok())
+                .and_then(|jwt| jwt.claims.get("iss")?.as_str().map(str::to_string))
+                .filter(|iss| jwt_config.issuers.contains(iss)),
+        };
+        self.outcomes.inc(&[
+            &bridge.id,
+            bridge.bridge_type.as_str(),
+            issuer.as_deref().unwrap_or(UNKNOWN_LABEL),
+            outcome,
+        ]);
+    }
+
+    /// Run one phase of an exchange in a trace-level span, recording how
+    /// long it took
+    fn timed<T>(&self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let _span = trace_span!("exchange_phase", phase).entered();
+        let start = Instant::now();
+        let result = f();
+        self.phase_seconds.observe(phase, start.elapsed());
+        result
+    }
+
+    /// Convert to tonic server
+    pub fn into_server(self) -> BridgeServiceServer<Self> {
+        BridgeServiceServer::new(self)
+    }
+
+    /// Convert a shared instance to tonic server
+    pub fn into_shared_server(self: Arc<Self>) -> BridgeServiceServer<Self> {
+        BridgeServiceServer::from_arc(self)
+    }
+
+    /// The requested bridge, or the default bridge when none is named
+    fn resolve_bridge_id<'a>(&'a self, bridge_id: &'a str) -> Result<&'a str> {
+        if !bridge_id.is_empty() {
+            return Ok(bridge_id);
+        }
+        self.options
+            .default_bridge_id
+            .as_deref()
+            .ok_or_else(|| Error::invalid_field("bridge_id", "bridge_id is required"))
+    }
+
+    /// Exchange an external credential for a PCA₀.
+    ///
+    /// Shared by the gRPC and HTTP front-ends. An empty `bridge_id` selects
+    /// the default bridge, if one is configured. The PCA₀ is to be bound to
+    /// `audience`, or to the bridge's `pca_audience` when it is empty, once
+    /// minting lands. A
+    /// non-empty `nonce` must match the credential's `nonce` claim, and a
+    /// non-empty `pop` must prove possession of its `cnf.jwk` key.
+    /// Repeating a non-empty
+    /// `idempotency_key` within its TTL returns the PCA₀ minted the first
+    /// time, provided the bridge and credential are identical. Other
+    /// exchanges fail with [`Error::Overloaded`] while
+    /// `max_concurrent_exchanges` are already in progress.
+    pub async fn exchange_credential(
+        &self,
+        bridge_id: &str,
+        credential: &[u8],
+        audience: &str,
+        nonce: &str,
+        pop: &str,
+        idempotency_key: &str,
+    ) -> Result<SignedPca> {
+        let binding = Binding { nonce, pop };
+        let result =
+            self.run_exchange(bridge_id, credential, audience, binding, idempotency_key).await;
+        self.record_outcome(bridge_id, credential, &result);
+        result
+    }
+
+    async fn run_exchange(
+        &self,
+        bridge_id: &str,
+        credential: &[u8],
+        audience: &str,
+        binding: Binding<'_>,
+        idempotency_key: &str,
+    ) -> Result<SignedPca> {
+        // Validate request
+        let bridge_id = self.resolve_bridge_id(bridge_id)?;
+
+        if credential.is_empty() {
+            return Err(Error::invalid_field("credential", "credential is required"));
+        }
+        debug!(bridge_id = %bridge_id, credential = %redact(credential), "Exchanging credential");
 
         // Get bridge configuration
-        let bridge = match self.registry.get_enabled(&req.bridge_id) {
-            Some(b) => b,
-            None => {
-                return Ok(Response::new(ExchangeResponse {
-                    pca: vec![],
-                    error: format!("Bridge not found or disabled: {}", req.bridge_id),
-                }));
-            }
+        let bridge = self
+            .registry
+            .get(bridge_id)
+            .ok_or_else(|| Error::BridgeNotFound(bridge_id.to_string()))?;
+        if let Some(at) = bridge.pending_deletion {
+            return Err(Error::BridgeDecommissioning(format!(
+                "bridge {} is being removed and will be deleted at {}",
+                bridge_id,
+                at.to_rfc3339()
+            )));
+        }
+        if !bridge.enabled {
+            return Err(Error::BridgeDisabled(match &bridge.disabled_reason {
+                Some(reason) => format!("bridge {} is disabled: {}", bridge_id, reason),
+                None => format!("bridge {} is disabled", bridge_id),
+            }));
+        }
+
+        // Replays too stop at a disabled or decommissioning bridge, and are
+        // only returned once the credential passes every check again
+        let fingerprint = exchange_fingerprint(bridge_id, credential, audience, binding);
+        let mut reuse = Reuse {
+            fingerprint,
+            replay: None,
         };
-        
+        if !idempotency_key.is_empty()
+            && let Some(previous) = self.idempotency.get(&idempotency_key.to_string())
+        {
+            if previous.fingerprint != fingerprint {
+                return Err(Error::invalid_field(
+                    "idempotency_key",
+                    "idempotency key reused with a different request",
+                ));
+            }
+            reuse.replay = Some(previous.pca);
+        }
+
         // Process based on bridge type
-        let result = match &bridge.config {
+        let _permit = self.concurrency.acquire().await?;
+        let audience = Some(audience).filter(|a| !a.is_empty()).or(bridge.pca_audience.as_deref());
+        let pca = match &bridge.config {
             BridgeTypeConfig::Jwt(jwt_config) => {
-                self.exchange_jwt(&req.credential, jwt_config).await
+                let pca_issuer = bridge.pca_issuer.as_deref();
+                self.exchange_jwt(credential, jwt_config, pca_issuer, audience, binding, reuse)
+                    .await?
             }
         };
 
-        match result {
-            Ok(pca) => Ok(Response::new(ExchangeResponse {
-                pca,
+        if idempotency_key.is_empty() {
+            return Ok(pca);
+        }
+
+        // A concurrent request with the same key may have finished first
+        let stored = self.idempotency.get_or_insert(
+            idempotency_key.to_string(),
+            IdempotentExchange { fingerprint, pca },
+        );
+        Ok(stored.pca)
+    }
+}
+
+impl BridgeServiceImpl {
+    /// Mapping trace for a credential, if tracing is enabled.
+    ///
+    /// The token is decoded but not verified: the trace only explains the
+    /// bridge's mapping of the caller's own claims.
+    pub fn mapping_trace(&self, bridge_id: &str, credential: &[u8]) -> Option<Vec<MappingTrace>> {
+        if !self.options.mapping_trace {
+            return None;
+        }
+        let bridge = self.registry.get(self.resolve_bridge_id(bridge_id).ok()?)?;
+        match &bridge.config {
+            BridgeTypeConfig::Jwt(jwt_config) => {
+                self.options.limits.check_token(credential).ok()?;
+                let jwt = Jwt::decode(std::str::from_utf8(credential).ok()?).ok()?;
+                Some(jwt_config.mapping.trace(&jwt.claims))
+            }
+        }
+    }
+}
+
+/// Current time in Unix seconds
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// RFC 7519 NumericDate, fractional seconds floored. `None` unless a
+/// non-negative number.
+fn numeric_date(value: &Value) -> Option<u64> {
+    if let Some(secs) = value.as_u64() {
+        return Some(secs);
+    }
+    let secs = value.as_f64().filter(|s| s.is_finite() && *s >= 0.0)?;
+    Some(secs.floor() as u64)
+}
+
+/// How long a PCA₀ minted from a token may be reused: at most `max`, and
+/// never past the token's `exp`. `None` if the token has already expired.
+fn exchange_cache_ttl(claims: &Map<String, Value>, max: Duration) -> Option<Duration> {
+    let Some(exp) = claims.get("exp").and_then(numeric_date) else {
+        return Some(max);
+    };
+    let remaining = exp.checked_sub(unix_now()).filter(|r| *r > 0)?;
+    Some(max.min(Duration::from_secs(remaining)))
+}
+
+/// Previously minted PCA₀s an exchange may return instead of signing
+struct Reuse {
+    /// Exchange cache key of the request
+    fingerprint: [u8; 32],
+    /// PCA₀ minted earlier for the request's idempotency key
+    replay: Option<SignedPca>,
+}
+
+/// What a client presents to bind its credential to the exchange
+#[derive(Clone, Copy, Debug, Default)]
+struct Binding<'a> {
+    /// Expected `nonce` claim (empty: none)
+    nonce: &'a str,
+    /// Proof-of-possession of the `cnf.jwk` key (empty: none)
+    pop: &'a str,
+}
+
+/// Fingerprint of an exchange request
+fn exchange_fingerprint(
+    bridge_id: &str,
+    credential: &[u8],
+    audience: &str,
+    binding: Binding<'_>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(bridge_id.as_bytes());
+    hasher.update([0]);
+    hasher.update(audience.as_bytes());
+    hasher.update([0]);
+    hasher.update(binding.nonce.as_bytes());
+    hasher.update([0]);
+    hasher.update(binding.pop.as_bytes());
+    hasher.update([0]);
+    hasher.update(credential);
+    hasher.finalize().into()
+}
+
+#[tonic::async_trait]
+impl BridgeService for BridgeServiceImpl {
+    async fn exchange(
+        &self,
+        request: Request<ExchangeRequest>,
+    ) -> std::result::Result<Response<ExchangeResponse>, Status> {
+        let timeout = deadline::grpc_timeout(request.metadata());
+        let req = request.into_inner();
+
+        let exchange = self.exchange_credential(
+            &req.bridge_id,
+            &req.credential,
+            &req.audience,
+            &req.nonce,
+            &req.pop,
+            &req.idempotency_key,
+        );
+        match deadline::within(timeout, exchange).await? {
+            Ok(signed) => Ok(Response::new(ExchangeResponse {
+                pca: signed.pca,
                 error: String::new(),
+                error_code: String::new(),
+                signing_kid: signed.signing_kid,
             })),
+            // A stubbed feature is UNIMPLEMENTED, with its feature id in the details
+            Err(e @ Error::NotImplemented(_)) => Err(e.into()),
             Err(e) => Ok(Response::new(ExchangeResponse {
                 pca: vec![],
-                error: e,
+                error: e.to_string(),
+                error_code: e.code().to_string(),
+                signing_kid: String::new(),
             })),
         }
     }
@@ -240,33 +1756,152 @@ impl BridgeServiceImpl {
         &self,
         credential: &[u8],
         config: &JwtBridgeConfig,
-    ) -> std::result::Result<Vec<u8>, String> {
+        pca_issuer: Option<&str>,
+        audience: Option<&str>,
+        binding: Binding<'_>,
+        reuse: Reuse,
+    ) -> Result<SignedPca> {
         // TODO: Implement full JWT validation and PCA₀ generation
         //
         // Steps:
         // 1. Parse JWT from credential bytes
         // 2. Fetch JWKS from config.wellknown_url
-        // 3. Validate JWT signature using JWKS
-        // 4. Verify issuer matches config.issuer
-        // 5. Verify audience is in config.audiences
+        // 3. Validate JWT signature using the JWKS keys kept by
+        //    config.pinned_keys
+        // 4. Verify issuer is one of config.issuers
+        // 5. Verify audience is in config.audiences, and the nonce
         // 6. Extract claims using config.mapping
-        // 7. Create PCA₀ with extracted claims
-        // 8. Sign PCA₀ with CAT key from credentials
-        // 9. Return CBOR-encoded PCA₀
+        // 7. Create PCA₀ with extracted claims and the matched issuer,
+        //    `iss` set to pca_issuer (or the Trust Plane DID) and bound to
+        //    audience, if any
+        // 8. Sign PCA₀ with CAT key from credentials under options.signing_context
+        // 9. Return CBOR-encoded PCA₀, recording the signing context
 
-        let _credentials = self.credentials.current();
-        
-        // Parse JWT (just to validate it's UTF-8 for now)
-        let _jwt_str = std::str::from_utf8(credential)
-            .map_err(|_| "Invalid UTF-8 in credential")?;
+        // Each phase is timed (`trustplane_exchange_phase_seconds`); JWKS
+        // fetch and signature verification get their own phases with 2./3.
+
+        // 1. Parse JWT, enforcing size limits before any network access
+        let jwt = self.timed("decode", || {
+            self.options.limits.check_token(credential)?;
+            let jwt_str = std::str::from_utf8(credential)
+                .map_err(|_| Error::invalid_field("credential", "Invalid UTF-8 in credential"))?;
+            let jwt = Jwt::decode(jwt_str)?;
+            self.options.limits.check_claims(&jwt.claims)?;
+            Ok::<_, Error>(jwt)
+        })?;
+
+        // 4./5. Verify algorithm, validity window, issuer, audience and nonce
+        let issuer = self.timed("validate", || {
+            let alg = jwt.header.get("alg").and_then(Value::as_str).unwrap_or("none");
+            config.check_algorithm(alg)?;
+            config.check_validity(&jwt.claims, unix_now())?;
+            let iss = jwt.claims.get("iss").and_then(Value::as_str).unwrap_or_default();
+            let issuer = config
+                .match_issuer(iss)
+                .ok_or_else(|| Error::Invalid(format!("Issuer not accepted by bridge: {}", iss)))?;
+            config.check_audience(&jwt.claims)?;
+            config.check_nonce(&jwt.claims, binding.nonce)?;
+            Ok::<_, Error>(issuer)
+        })?;
+
+        // 6. Extract claims
+        let mapped = self.timed("map", || {
+            let mut mapped = config.mapping.map_claims(&jwt.claims)?;
+            mapped.issuer = issuer.to_string();
+            config.check_required_attributes(&mapped)?;
+            self.options.limits.check_mapped(&mapped)?;
+            self.revocations.check(&mapped.subject)?;
+            Ok::<_, Error>(mapped)
+        })?;
+        self.log_pca(&mapped);
+
+        // Proof-of-possession last, so a proof is only spent by an exchange
+        // that passed every other check
+        self.timed("pop", || {
+            config.check_pop(credential, &jwt.claims, binding.pop, &self.proofs, unix_now())
+        })?;
+
+        // Idempotent replays and identical re-exchanges skip signing, but
+        // only once every check above has passed again
+        if let Some(pca) = reuse.replay {
+            debug!(subject = %mapped.subject, "Replaying PCA₀ for idempotency key");
+            return Ok(pca);
+        }
+        if let Some(pca) = self.exchanges.as_ref().and_then(|c| c.get(&reuse.fingerprint)) {
+            debug!(subject = %mapped.subject, "Reusing cached PCA₀");
+            return Ok(pca);
+        }
+
+        let pca = self.timed("sign", || self.mint_pca0(config, &mapped, pca_issuer, audience))?;
+
+        if let Some(exchanges) = &self.exchanges
+            && let Some(ttl) = exchange_cache_ttl(&jwt.claims, self.options.exchange_cache_ttl)
+        {
+            exchanges.insert_with_ttl(reuse.fingerprint, pca.clone(), ttl);
+        }
+        Ok(pca)
+    }
+
+    /// Sign a PCA₀ for mapped claims
+    fn mint_pca0(
+        &self,
+        config: &JwtBridgeConfig,
+        mapped: &MappedClaims,
+        pca_issuer: Option<&str>,
+        audience: Option<&str>,
+    ) -> Result<SignedPca> {
+        // 7./8. A PCA issuer hosted here signs with its own keys. Any other
+        // issuer DID is signed for by the default tenant: verifiers resolve
+        // the kid under the signer's DID
+        let credentials = pca_issuer
+            .and_then(|did| self.tenants.get(did))
+            .unwrap_or_else(|| self.tenants.default_tenant())
+            .current();
+        let pca_issuer = pca_issuer.unwrap_or(&credentials.did);
+        let kid = credentials.cat_key.kid();
+        if credentials.did_document.verification_method(kid).is_none() {
+            return Err(Error::Crypto(format!(
+                "CAT key {} does not resolve in DID document {}",
+                kid, credentials.did
+            )));
+        }
 
         warn!(
             wellknown = %config.wellknown_url,
-            issuer = %config.issuer,
+            issuer = %mapped.issuer,
+            pca_issuer = %pca_issuer,
+            audience = audience.unwrap_or_default(),
+            kid = %kid,
+            signing_context = %self.options.signing_context,
             "JWT bridge exchange not fully implemented yet"
         );
         
-        Err("JWT bridge not fully implemented".to_string())
+        Err(Error::NotImplemented("jwt_exchange".to_string()))
+    }
+
+    /// Log the PCA₀ contents at debug level.
+    ///
+    /// Only attribute keys are logged unless value logging is enabled, since
+    /// values may carry secrets.
+    fn log_pca(&self, mapped: &MappedClaims) {
+        if self.options.log_pca_values {
+            let attributes = Value::Object(mapped.attributes.clone());
+            debug!(
+                subject = %mapped.subject,
+                sequence = 0,
+                issuer = %mapped.issuer,
+                attributes = %attributes,
+                "PCA₀ contents"
+            );
+        } else {
+            debug!(
+                subject = %mapped.subject,
+                sequence = 0,
+                issuer = %mapped.issuer,
+                attributes = ?mapped.attributes.keys().collect::<Vec<_>>(),
+                "PCA₀ contents"
+            );
+        }
     }
 }
 
@@ -277,23 +1912,20 @@ impl BridgeServiceImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::credentials::testing::{test_credentials_manager, TEST_DID};
 
     #[test]
     fn test_bridge_registry_crud() {
         let registry = BridgeRegistry::new();
         
         // Add
-        let config = BridgeConfig {
-            id: String::new(),
-            bridge_type: BridgeType::Jwt,
-            enabled: true,
-            config: BridgeTypeConfig::Jwt(JwtBridgeConfig {
-                wellknown_url: "https://auth.example.com/.well-known/openid-configuration".into(),
-                issuer: "https://auth.example.com".into(),
-                audiences: vec!["api".into()],
-                mapping: MappingConfig::default(),
-            }),
-        };
+        let config = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .audience("api")
+        .build()
+        .unwrap();
         
         let id = registry.add(config).unwrap();
         assert!(!id.is_empty());
@@ -310,16 +1942,46 @@ mod tests {
         // Update
         let mut updated = fetched.clone();
         updated.enabled = false;
-        registry.update(updated).unwrap();
+        registry.update(updated.clone(), Some(fetched.version)).unwrap();
         
         let fetched2 = registry.get(&id).unwrap();
         assert!(!fetched2.enabled);
+        assert_eq!((fetched.version, fetched2.version), (1, 2));
+
+        // A write based on a stale read is refused rather than lost
+        let err = registry.update(updated, Some(fetched.version)).unwrap_err();
+        assert!(err.to_string().contains("version conflict"), "{}", err);
+        assert!(registry.set_enabled(&id, true, None, Some(1)).is_err());
+        assert!(registry.remove(&id, Some(1)).is_err());
+        assert_eq!(registry.get(&id).unwrap().version, 2);
+
+        // Adding over an existing bridge would reset its version
+        let mut again = fetched.clone();
+        again.enabled = true;
+        let err = registry.add(again).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert_eq!(registry.get(&id).unwrap().version, 2);
         
         // Get enabled (should be None now)
         assert!(registry.get_enabled(&id).is_none());
+
+        // Toggle only the flag; the reason lives while disabled
+        registry.set_enabled(&id, false, Some("IdP key compromise".into()), None).unwrap();
+        let disabled = registry.get(&id).unwrap();
+        assert_eq!(disabled.disabled_reason.as_deref(), Some("IdP key compromise"));
+        assert_eq!(
+            serde_json::to_value(&disabled.config).unwrap(),
+            serde_json::to_value(&fetched.config).unwrap()
+        );
+        registry.set_enabled(&id, true, None, Some(3)).unwrap();
+        assert!(registry.get_enabled(&id).unwrap().disabled_reason.is_none());
+        assert!(matches!(
+            registry.set_enabled("nonexistent", true, None, None),
+            Err(Error::NotFound(_))
+        ));
         
         // Remove
-        registry.remove(&id).unwrap();
+        registry.remove(&id, None).unwrap();
         assert!(registry.get(&id).is_none());
         assert!(registry.list().is_empty());
     }
@@ -329,21 +1991,1003 @@ mod tests {
         let registry = BridgeRegistry::new();
         
         assert!(registry.get("nonexistent").is_none());
-        assert!(registry.remove("nonexistent").is_err());
+        assert!(registry.remove("nonexistent", None).is_err());
         
-        let config = BridgeConfig {
-            id: "test".into(),
-            bridge_type: BridgeType::Jwt,
-            enabled: true,
-            config: BridgeTypeConfig::Jwt(JwtBridgeConfig {
-                wellknown_url: String::new(),
-                issuer: String::new(),
-                audiences: vec![],
-                mapping: MappingConfig::default(),
-            }),
-        };
+        let config = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .id("test")
+        .build()
+        .unwrap();
         
         // Update non-existent should fail
-        assert!(registry.update(config).is_err());
+        assert!(registry.update(config, None).is_err());
+    }
+
+    #[test]
+    fn test_bridge_registry_import_export() {
+        let registry = BridgeRegistry::new();
+
+        let jwt = |id: &str, issuer: &str| {
+            BridgeConfigBuilder::jwt(
+                "https://auth.example.com/.well-known/openid-configuration",
+                issuer,
+            )
+            .id(id)
+            .audience("api")
+            .build()
+            .unwrap()
+        };
+
+        registry.add(jwt("b", "https://old.example.com")).unwrap();
+
+        // Invalid entry rejects the whole batch
+        let mut invalid = jwt("c", "https://auth.example.com");
+        let BridgeTypeConfig::Jwt(config) = &mut invalid.config;
+        config.issuers = vec![String::new()];
+        let batch = vec![jwt("a", "https://auth.example.com"), invalid];
+        assert!(registry.import(batch).is_err());
+        assert!(registry.get("a").is_none());
+
+        // Duplicate ids are rejected
+        let batch = vec![jwt("a", "https://auth.example.com"), jwt("a", "https://auth.example.com")];
+        assert!(registry.import(batch).is_err());
+        assert!(registry.get("a").is_none());
+
+        // Upsert: "b" is replaced, "a" is inserted
+        let batch = vec![jwt("b", "https://new.example.com"), jwt("a", "https://auth.example.com")];
+        assert_eq!(registry.import(batch).unwrap(), 2);
+        match registry.get("b").unwrap().config {
+            BridgeTypeConfig::Jwt(j) => assert_eq!(j.issuers, vec!["https://new.example.com"]),
+        }
+
+        // Export is ordered and can be re-imported as-is
+        let exported = serde_json::to_value(registry.export()).unwrap();
+        assert_eq!(exported[0]["id"], "a");
+        assert_eq!(exported[1]["id"], "b");
+
+        let reimported: Vec<BridgeConfig> = serde_json::from_value(exported).unwrap();
+        let other = BridgeRegistry::new();
+        assert_eq!(other.import(reimported).unwrap(), 2);
+        let ids: Vec<String> = other.list().into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_bridge_type_schemas_cover_config() {
+        let bridge = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .default_organization("acme")
+        .max_token_age_secs(3600)
+        .build()
+        .unwrap();
+        let BridgeTypeConfig::Jwt(config) = bridge.config;
+
+        // Every serialized field is described, so the schema cannot drift
+        let schema = BridgeType::Jwt.config_schema();
+        let config = serde_json::to_value(config).unwrap();
+        for key in config.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(key).is_some(), "{} missing", key);
+        }
+        for key in config["mapping"].as_object().unwrap().keys() {
+            let mapping = &schema["properties"]["mapping"]["properties"];
+            assert!(mapping.get(key).is_some(), "mapping.{} missing", key);
+        }
+        assert_eq!(BridgeType::all(), [BridgeType::Jwt]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bridge_pca_issuer_must_be_did() {
+        let registry = BridgeRegistry::new();
+        let mut config: BridgeConfig = serde_json::from_value(serde_json::json!({
+            "id": "tenant",
+            "type": "jwt",
+            "enabled": true,
+            "pca_issuer": "did:web:tenant.example.com",
+            "config": { "jwt": {
+                "wellknown_url": "https://auth.example.com/.well-known/openid-configuration",
+                "issuer": "https://auth.example.com"
+            }}
+        }))
+        .unwrap();
+        registry.add(config.clone()).unwrap();
+
+        for invalid in ["https://tenant.example.com", "did:web:", "did::x", "did:WEB:x"] {
+            config.pca_issuer = Some(invalid.into());
+            assert!(registry.update(config.clone(), None).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            registry.get("tenant").unwrap().pca_issuer.as_deref(),
+            Some("did:web:tenant.example.com")
+        );
+    }
+
+    #[test]
+    fn test_mapping_allowed_denied_claims() {
+        let claims = serde_json::json!({
+            "sub": "alice",
+            "org": "acme",
+            "email": "alice@acme.example",
+            "groups": ["admin"],
+            "ssn": "000-00-0000",
+        });
+        let claims = claims.as_object().unwrap();
+
+        let mut mapping = MappingConfig::with_defaults();
+        mapping.custom.insert("email".into(), "email".into());
+        mapping.custom.insert("groups".into(), "groups".into());
+        mapping.custom.insert("ssn".into(), "ssn".into());
+
+        // No lists: everything mapped
+        let mapped = mapping.map_claims(claims).unwrap();
+        assert_eq!(mapped.subject, "alice");
+        assert_eq!(mapped.organization.as_deref(), Some("acme"));
+        assert_eq!(mapped.attributes.len(), 3);
+
+        // Denied claim is dropped
+        mapping.denied_claims = vec!["ssn".into()];
+        let mapped = mapping.map_claims(claims).unwrap();
+        assert!(!mapped.attributes.contains_key("ssn"));
+        assert_eq!(mapped.attributes.len(), 2);
+
+        // Allow list restricts mapping; deny still wins
+        mapping.allowed_claims = vec!["sub".into(), "email".into(), "ssn".into()];
+        let mapped = mapping.map_claims(claims).unwrap();
+        assert_eq!(mapped.organization, None);
+        assert_eq!(mapped.attributes.len(), 1);
+        assert_eq!(mapped.attributes["email"], "alice@acme.example");
+
+        // Subject itself can be excluded, which fails the mapping
+        mapping.allowed_claims = vec!["email".into()];
+        assert!(mapping.map_claims(claims).is_err());
+
+        // The trace explains each field
+        mapping.allowed_claims.clear();
+        mapping.custom.insert("phone".into(), "phone_number".into());
+        let trace: Vec<_> = mapping
+            .trace(claims)
+            .into_iter()
+            .map(|t| (t.field, t.transform))
+            .collect();
+        assert_eq!(
+            trace,
+            vec![
+                ("subject".to_string(), "copied"),
+                ("organization".to_string(), "copied"),
+                ("attributes.email".to_string(), "copied"),
+                ("attributes.groups".to_string(), "copied"),
+                ("attributes.phone".to_string(), "absent"),
+                ("attributes.ssn".to_string(), "not_permitted"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mapping_attribute_namespaces() {
+        let claims = serde_json::json!({ "sub": "alice", "tier": "free", "dept": "eng" });
+        let claims = claims.as_object().unwrap();
+        let bridge = |namespace: bool| {
+            BridgeConfigBuilder::jwt(
+                "https://auth.example.com/.well-known/openid-configuration",
+                "https://auth.example.com",
+            )
+            .custom_claim("tier", "tier")
+            .custom_claim("dept", "dept")
+            .initial_attribute("tier", "gold")
+            .initial_attribute("region", "eu")
+            .namespace_attributes(namespace)
+        };
+
+        // Flat: the same name from both sources is refused, not overwritten
+        let err = bridge(false).build().unwrap_err();
+        assert!(err.to_string().contains("attribute tier"), "{}", err);
+
+        // Namespaced: both survive, each under its source
+        let BridgeTypeConfig::Jwt(jwt) = bridge(true).build().unwrap().config;
+        let attributes = jwt.mapping.map_claims(claims).unwrap().attributes;
+        assert_eq!(attributes["claims.tier"], "free");
+        assert_eq!(attributes["config.tier"], "gold");
+        assert_eq!(attributes["claims.dept"], "eng");
+        assert_eq!(attributes["config.region"], "eu");
+        assert_eq!(attributes.len(), 4);
+        let trace = jwt.mapping.trace(claims);
+        let region = trace.iter().find(|t| t.field == "attributes.config.region").unwrap();
+        assert_eq!(region.transform, "static");
+
+        // Flat without collisions merges both sources as is
+        let mut mapping = jwt.mapping.clone();
+        mapping.namespace_attributes = false;
+        mapping.initial_attributes.remove("tier");
+        let attributes = mapping.map_claims(claims).unwrap().attributes;
+        assert_eq!(attributes["tier"], "free");
+        assert_eq!(attributes["region"], "eu");
+
+        // Reserved prefixes only come from namespacing
+        let reserved = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .initial_attribute("claims.admin", "true")
+        .build();
+        assert!(reserved.is_err());
+    }
+
+    #[test]
+    fn test_mapping_copy_all_claims() {
+        let claims = serde_json::json!({
+            "sub": "alice",
+            "exp": 1, "nbf": 1, "iat": 1, "jti": "n-1",
+            "email": "alice@example.com",
+            "ssn": "000-00-0000",
+            "tier": "free",
+            "region": "us",
+            "claims.admin": true,
+        });
+        let claims = claims.as_object().unwrap();
+        let bridge = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .custom_claim("tier", "email")
+        .initial_attribute("region", "eu")
+        .copy_all_claims(true)
+        .build()
+        .unwrap();
+        let BridgeTypeConfig::Jwt(mut jwt) = bridge.config;
+        jwt.mapping.denied_claims.push("ssn".into());
+
+        // Everything but bookkeeping, denied and reserved-prefix claims is
+        // copied; explicit attributes win over copied claims
+        let attributes = jwt.mapping.map_claims(claims).unwrap().attributes;
+        let mut names: Vec<&str> = attributes.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["email", "region", "sub", "tier"]);
+        assert_eq!(attributes["tier"], "alice@example.com");
+        assert_eq!(attributes["region"], "eu");
+
+        let trace = jwt.mapping.trace(claims);
+        let ssn = trace.iter().find(|t| t.claim == "ssn").unwrap();
+        assert_eq!(ssn.transform, "not_permitted");
+        assert!(trace.iter().all(|t| t.claim != "exp"));
+        assert_eq!(trace.iter().filter(|t| t.field == "attributes.tier").count(), 1);
+
+        // Namespaced, copied claims land under claims.
+        let mut mapping = jwt.mapping.clone();
+        mapping.namespace_attributes = true;
+        let attributes = mapping.map_claims(claims).unwrap().attributes;
+        assert_eq!(attributes["claims.claims.admin"], true);
+        assert_eq!(attributes["claims.region"], "us");
+        assert_eq!(attributes["config.region"], "eu");
+
+        // Off by default
+        let mut mapping = jwt.mapping;
+        mapping.copy_all_claims = false;
+        assert_eq!(mapping.map_claims(claims).unwrap().attributes.len(), 2);
+    }
+
+    #[test]
+    fn test_mapping_organization_fallback() {
+        // Legacy single-claim payload
+        let legacy: MappingConfig = serde_json::from_value(serde_json::json!({
+            "organization_claim": "tenant",
+        }))
+        .unwrap();
+        assert_eq!(legacy.organization_claims, vec!["tenant"]);
+
+        let mut mapping: MappingConfig = serde_json::from_value(serde_json::json!({
+            "organization_claims": ["org", "tid", "hd"],
+        }))
+        .unwrap();
+        let organization = |mapping: &MappingConfig, claims: Value| {
+            mapping.map_claims(claims.as_object().unwrap()).unwrap().organization
+        };
+
+        let claims = serde_json::json!({ "sub": "alice", "tid": "t-1", "hd": "acme.example" });
+        assert_eq!(organization(&mapping, claims.clone()).as_deref(), Some("t-1"));
+        let trace = mapping.trace(claims.as_object().unwrap());
+        assert_eq!((trace[1].claim.as_str(), trace[1].transform), ("tid", "copied"));
+
+        // A denied candidate is skipped, not a dead end
+        mapping.denied_claims = vec!["tid".into()];
+        assert_eq!(organization(&mapping, claims).as_deref(), Some("acme.example"));
+
+        let claims = serde_json::json!({ "sub": "alice" });
+        assert_eq!(organization(&mapping, claims.clone()), None);
+        mapping.default_organization = Some("acme".into());
+        assert_eq!(organization(&mapping, claims.clone()).as_deref(), Some("acme"));
+        assert_eq!(mapping.trace(claims.as_object().unwrap())[1].transform, "default");
+    }
+
+    #[test]
+    fn test_jwt_bridge_multiple_issuers() {
+        // Legacy single-issuer payload
+        let single: JwtBridgeConfig = serde_json::from_value(serde_json::json!({
+            "wellknown_url": "https://auth.example.com/.well-known/openid-configuration",
+            "issuer": "https://auth.example.com",
+        }))
+        .unwrap();
+        assert_eq!(single.issuers, vec!["https://auth.example.com"]);
+
+        let multi: JwtBridgeConfig = serde_json::from_value(serde_json::json!({
+            "wellknown_url": "https://auth.example.com/.well-known/openid-configuration",
+            "issuers": ["https://eu.example.com", "https://us.example.com"],
+        }))
+        .unwrap();
+        assert_eq!(multi.match_issuer("https://us.example.com"), Some("https://us.example.com"));
+        assert_eq!(multi.match_issuer("https://evil.example.com"), None);
+        assert_eq!(multi.match_issuer(""), None);
+    }
+
+    #[test]
+    fn test_bridge_config_builder() {
+        let wellknown = "https://auth.example.com/.well-known/openid-configuration";
+        let built = BridgeConfigBuilder::jwt(wellknown, "https://eu.example.com")
+            .id("idp")
+            .issuer("https://us.example.com")
+            .audience("api")
+            .require_audience(true)
+            .subject_claim("oid")
+            .organization_claims(["tid", "org"])
+            .custom_claim("email", "email")
+            .allowed_algorithm("EdDSA")
+            .pca_issuer("did:web:tenant.example.com")
+            .enabled(false)
+            .build()
+            .unwrap();
+
+        // Same result as the equivalent JSON, defaults included
+        let parsed: BridgeConfig = serde_json::from_value(serde_json::json!({
+            "id": "idp",
+            "type": "jwt",
+            "enabled": false,
+            "pca_issuer": "did:web:tenant.example.com",
+            "config": { "jwt": {
+                "wellknown_url": wellknown,
+                "issuers": ["https://eu.example.com", "https://us.example.com"],
+                "audiences": ["api"],
+                "require_audience": true,
+                "mapping": {
+                    "subject_claim": "oid",
+                    "organization_claims": ["tid", "org"],
+                    "custom": { "email": "email" }
+                },
+                "allowed_algorithms": ["EdDSA"]
+            }}
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&parsed).unwrap()
+        );
+
+        // Missing required fields are reported by build
+        let err = BridgeConfigBuilder::jwt("", "https://auth.example.com").build().unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{}", err);
+        assert!(BridgeConfigBuilder::jwt(wellknown, "").build().is_err());
+        assert!(BridgeConfigBuilder::jwt(wellknown, "https://auth.example.com")
+            .allowed_algorithm("HS256")
+            .build()
+            .is_err());
+        assert!(BridgeConfigBuilder::jwt(wellknown, "https://auth.example.com")
+            .pca_audience(" ")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_jwt_audience() {
+        let mut config: JwtBridgeConfig = serde_json::from_value(serde_json::json!({
+            "wellknown_url": "https://auth.example.com/.well-known/openid-configuration",
+            "issuer": "https://auth.example.com",
+            "audiences": ["api"],
+        }))
+        .unwrap();
+        let claims = |aud: Option<Value>| {
+            let mut claims = Map::new();
+            if let Some(aud) = aud {
+                claims.insert("aud".into(), aud);
+            }
+            claims
+        };
+        let present = claims(Some(serde_json::json!(["web", "api"])));
+        let absent = claims(None);
+        let mismatched = claims(Some(serde_json::json!("web")));
+
+        assert!(config.check_audience(&present).is_ok());
+        assert!(config.check_audience(&absent).is_ok());
+        assert_eq!(config.check_audience(&mismatched).unwrap_err().code(), "invalid_argument");
+
+        config.require_audience = true;
+        assert!(config.check_audience(&present).is_ok());
+        assert_eq!(config.check_audience(&absent).unwrap_err().code(), "missing_audience");
+        assert_eq!(config.check_audience(&mismatched).unwrap_err().code(), "invalid_argument");
+
+        // Without allowed audiences, any present audience passes
+        config.audiences.clear();
+        assert!(config.check_audience(&mismatched).is_ok());
+        assert!(config.check_audience(&absent).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_nonce() {
+        let service = bridge_service(BridgeOptions::default());
+        let bound = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "nonce": "n-1",
+        }));
+        let unbound =
+            token(serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" }));
+        let exchange = |credential: &[u8], nonce: &str| {
+            let (service, credential, nonce) = (&service, credential.to_vec(), nonce.to_string());
+            async move { service.exchange_credential("idp", &credential, "", &nonce, "", "").await }
+        };
+
+        // A presented nonce is checked even when the bridge does not require one
+        let err = exchange(&bound, "").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+        let err = exchange(&bound, "n-1").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+        assert_eq!(exchange(&bound, "n-2").await.unwrap_err().code(), "nonce_mismatch");
+        assert_eq!(exchange(&unbound, "n-1").await.unwrap_err().code(), "nonce_mismatch");
+
+        let mut config = service.registry.get("idp").unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut config.config;
+        jwt.require_nonce = true;
+        service.registry.update(config, None).unwrap();
+        let err = exchange(&bound, "").await.unwrap_err();
+        assert_eq!(err.code(), "nonce_mismatch");
+        assert_eq!(err.status_code(), axum::http::StatusCode::BAD_REQUEST);
+        let err = exchange(&bound, "n-1").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_pop() {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let service = bridge_service(BridgeOptions::default());
+        let mut config = service.registry.get("idp").unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut config.config;
+        jwt.require_pop = true;
+        let err = service.registry.update(config, None).unwrap_err();
+        assert!(err.to_string().contains("not enforced yet"), "{}", err);
+
+        let holder = crate::credentials::KeyPair::generate("client#pop");
+        let credential = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "cnf": { "jwk": holder.public_jwk() },
+        }));
+        let header = serde_json::json!({ "typ": "dpop+jwt", "alg": "EdDSA" });
+        let claims = serde_json::json!({
+            "jti": "p-1",
+            "iat": unix_now(),
+            "htm": "POST",
+            "htu": "https://tp.example.com/v1/bridge/exchange",
+            "ath": URL_SAFE_NO_PAD.encode(Sha256::digest(&credential)),
+        });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = URL_SAFE_NO_PAD.encode(holder.sign(signing_input.as_bytes()));
+        let proof = format!("{}.{}", signing_input, signature);
+        let exchange = |pop: &str| {
+            let (service, credential, pop) = (&service, credential.clone(), pop.to_string());
+            async move { service.exchange_credential("idp", &credential, "", "", &pop, "").await }
+        };
+
+        // A presented proof is checked: a bad one is refused
+        let err = exchange(&proof.replace(".ey", ".eyX")).await.unwrap_err();
+        assert_eq!(err.code(), "pop_verification_failed");
+        assert_eq!(err.status_code(), axum::http::StatusCode::UNAUTHORIZED);
+
+        // A proof is not spent by an exchange failing another check
+        service.revocations.revoke("alice").unwrap();
+        assert_eq!(exchange(&proof).await.unwrap_err().code(), "subject_revoked");
+        service.revocations.unrevoke("alice").unwrap();
+        let err = exchange(&proof).await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+        let err = exchange(&proof).await.unwrap_err();
+        assert!(err.to_string().contains("already used"), "{}", err);
+
+        // A proof for another token does not transfer
+        let other =
+            token(serde_json::json!({ "iss": "https://auth.example.com", "sub": "mallory" }));
+        let err = service.exchange_credential("idp", &other, "", "", &proof, "").await.unwrap_err();
+        assert_eq!(err.code(), "pop_verification_failed");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_required_attributes() {
+        let service = bridge_service(BridgeOptions::default());
+        let mut config = service.registry.get("idp").unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut config.config;
+        jwt.required_pca_attributes = vec!["bio".into()];
+        service.registry.update(config, None).unwrap();
+        let exchange = |claims: Value| {
+            let (service, credential) = (&service, token(claims));
+            async move { service.exchange_credential("idp", &credential, "", "", "", "").await }
+        };
+
+        // Present once mapped, the exchange proceeds to minting
+        let err = exchange(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "bio": "hi",
+        }))
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+
+        // Missing, the exchange is refused naming the attribute
+        let err = exchange(serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "missing_required_attribute");
+        assert_eq!(err.to_string(), "missing_required_attribute: bio");
+        assert_eq!(err.status_code(), axum::http::StatusCode::BAD_REQUEST);
+
+        // Required names follow namespacing
+        let mut config = service.registry.get("idp").unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut config.config;
+        jwt.mapping.namespace_attributes = true;
+        service.registry.update(config, None).unwrap();
+        let err = exchange(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "bio": "hi",
+        }))
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "missing_required_attribute: bio");
+    }
+
+    #[test]
+    fn test_jwks_pinned_thumbprints() {
+        let key = |x: &str| serde_json::json!({ "kty": "OKP", "crv": "Ed25519", "x": x });
+        let keys = [
+            key("11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"),
+            key("attacker-key"),
+            serde_json::json!({ "kty": "oct", "k": "c2VjcmV0" }),
+        ];
+        let mut bridge = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .id("pinned")
+        .build()
+        .unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut bridge.config;
+
+        // Unpinned: every served key is trusted
+        assert_eq!(jwt.pinned_keys(&keys).len(), 3);
+
+        jwt.pinned_thumbprints = vec!["kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k".into()];
+        assert_eq!(jwt.pinned_keys(&keys), [&keys[0]]);
+        // Without a JWKS fetch to apply them, pins are refused
+        let err = bridge.validate().unwrap_err();
+        assert!(err.to_string().contains("not enforced yet"), "{}", err);
+
+        let BridgeTypeConfig::Jwt(jwt) = &mut bridge.config;
+        jwt.pinned_thumbprints.push("not-a-thumbprint".into());
+        let err = bridge.validate().unwrap_err();
+        assert!(err.to_string().contains("not a base64url"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_jwt_clock_skew() {
+        let mut config: JwtBridgeConfig = serde_json::from_value(serde_json::json!({
+            "wellknown_url": "https://auth.example.com/.well-known/openid-configuration",
+            "issuer": "https://auth.example.com",
+        }))
+        .unwrap();
+        assert_eq!(config.max_clock_skew_secs, DEFAULT_MAX_CLOCK_SKEW_SECS);
+        config.max_clock_skew_secs = 10;
+
+        let now = 1_700_000_000;
+        let claims = |name: &str, at: u64| {
+            serde_json::json!({ name: at }).as_object().unwrap().clone()
+        };
+
+        // nbf a few seconds ahead of our clock: accepted within skew only
+        assert!(config.check_validity(&claims("nbf", now + 5), now).is_ok());
+        assert!(config.check_validity(&claims("nbf", now + 10), now).is_ok());
+        assert!(config.check_validity(&claims("nbf", now + 11), now).is_err());
+
+        // exp gets the same tolerance in the other direction
+        assert!(config.check_validity(&claims("exp", now - 10), now).is_ok());
+        assert!(config.check_validity(&claims("exp", now - 11), now).is_err());
+
+        let bad = serde_json::json!({ "exp": "tomorrow" });
+        assert!(config.check_validity(bad.as_object().unwrap(), now).is_err());
+
+        // NumericDates may carry fractional seconds, but not a sign
+        let fractional = serde_json::json!({ "exp": now as f64 - 9.5 });
+        assert!(config.check_validity(fractional.as_object().unwrap(), now).is_ok());
+        let fractional = serde_json::json!({ "exp": now as f64 - 10.5 });
+        assert!(config.check_validity(fractional.as_object().unwrap(), now).is_err());
+        let negative = serde_json::json!({ "nbf": -1 });
+        assert!(config.check_validity(negative.as_object().unwrap(), now).is_err());
+
+        // Maximum age, measured from iat, also within skew; iat becomes required
+        config.max_token_age_secs = Some(300);
+        assert!(config.check_validity(&claims("iat", now - 310), now).is_ok());
+        let err = config.check_validity(&claims("iat", now - 311), now).unwrap_err();
+        assert_eq!(err.code(), "token_too_old");
+        assert!(config.check_validity(&claims("exp", now + 60), now).is_err());
+        // An iat ahead of our clock beyond the skew is refused, not aged 0
+        assert!(config.check_validity(&claims("iat", now + 10), now).is_ok());
+        assert!(config.check_validity(&claims("iat", now + 11), now).is_err());
+
+        // Enforced on exchange, before any key lookup
+        let service = bridge_service(BridgeOptions::default());
+        let future = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "nbf": unix_now() + 5,
+        }));
+        let err = service.exchange_credential("idp", &future, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+        let expired = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "exp": unix_now() - 3600,
+        }));
+        let err = service.exchange_credential("idp", &expired, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{}", err);
+    }
+
+    fn bridge_service(options: BridgeOptions) -> BridgeServiceImpl {
+        let credentials = test_credentials_manager(TEST_DID);
+        let registry = Arc::new(BridgeRegistry::new());
+        let idp = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .id("idp")
+        .custom_claim("bio", "bio")
+        .build()
+        .unwrap();
+        registry.add(idp).unwrap();
+        BridgeServiceImpl::new(
+            credentials,
+            registry,
+            Arc::new(RevocationStore::in_memory()),
+            options,
+        )
+    }
+
+    fn token(claims: Value) -> Vec<u8> {
+        use base64::Engine;
+        let b64 = |v: Value| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(v.to_string())
+        };
+        format!("{}.{}.sig", b64(serde_json::json!({ "alg": "EdDSA" })), b64(claims)).into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rejects_oversized_credentials() {
+        let limits = CredentialLimits {
+            max_token_bytes: 1024,
+            max_claims: 8,
+            max_value_len: 64,
+        };
+        let service = bridge_service(BridgeOptions {
+            limits,
+            ..Default::default()
+        });
+        let base = serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" });
+
+        // Within limits the exchange gets past the checks (to the unimplemented JWKS step)
+        let err = service
+            .exchange_credential("idp", &token(base.clone()), "", "", "", "")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+
+        // Raw size is checked before the token is even parsed
+        let mut oversized = token(base.clone());
+        oversized.resize(2048, b'A');
+        let err = service.exchange_credential("idp", &oversized, "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "credential_too_large");
+
+        let mut many = base.clone();
+        for i in 0..16 {
+            many[format!("c{}", i)] = Value::from(i);
+        }
+        let err =
+            service.exchange_credential("idp", &token(many), "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "credential_too_large");
+
+        let mut long = base;
+        long["bio"] = Value::from("x".repeat(100));
+        let err =
+            service.exchange_credential("idp", &token(long), "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "credential_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rejects_alg_none_and_hmac() {
+        use base64::Engine;
+        let service = bridge_service(BridgeOptions::default());
+        let b64 = |v: Value| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(v.to_string());
+        let claims = b64(serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" }));
+
+        // Unsigned token with an empty signature: the classic bypass attempt
+        for header in [
+            serde_json::json!({ "alg": "none" }),
+            serde_json::json!({ "alg": "None" }),
+            serde_json::json!({ "typ": "JWT" }),
+            serde_json::json!({ "alg": "HS256" }),
+        ] {
+            let token = format!("{}.{}.", b64(header.clone()), claims);
+            let err = service
+                .exchange_credential("idp", token.as_bytes(), "", "", "", "")
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), "algorithm_not_allowed", "{}", header);
+        }
+
+        // Configured allow-list is enforced, and cannot re-enable HMAC
+        let mut config = service.registry.get("idp").unwrap();
+        let BridgeTypeConfig::Jwt(jwt) = &mut config.config;
+        jwt.allowed_algorithms = vec!["ES256".into()];
+        assert!(jwt.check_algorithm("EdDSA").is_err());
+        assert!(jwt.check_algorithm("ES256").is_ok());
+        jwt.allowed_algorithms.push("HS256".into());
+        assert!(service.registry.update(config, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_default_bridge() {
+        let credential = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+        }));
+
+        let service = bridge_service(BridgeOptions::default());
+        let err = service.exchange_credential("", &credential, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidField { ref field, .. } if field == "bridge_id"));
+
+        let service = bridge_service(BridgeOptions {
+            default_bridge_id: Some("idp".into()),
+            ..Default::default()
+        });
+        let err = service.exchange_credential("", &credential, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+
+        // An explicit bridge still wins over the default
+        let err =
+            service.exchange_credential("other", &credential, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::BridgeNotFound(_)), "{}", err);
+        assert_eq!(err.status_code(), axum::http::StatusCode::NOT_FOUND);
+
+        // Disabled bridges are told apart from missing ones
+        service
+            .registry
+            .set_enabled("idp", false, Some("maintenance".into()), None)
+            .unwrap();
+        let err =
+            service.exchange_credential("idp", &credential, "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "bridge_disabled");
+        assert_eq!(err.status_code(), axum::http::StatusCode::CONFLICT);
+        assert!(err.to_string().contains("maintenance"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_metrics_labels_are_bounded() {
+        let service = bridge_service(BridgeOptions::default());
+        for i in 0..50 {
+            let subject = format!("user-{}", i);
+            for issuer in ["https://auth.example.com", &format!("https://evil-{}.example", i)] {
+                let credential = token(serde_json::json!({ "iss": issuer, "sub": subject }));
+                let _ = service.exchange_credential("idp", &credential, "", "", "", "").await;
+                let bogus_bridge = format!("bridge-{}", i);
+                let _ =
+                    service.exchange_credential(&bogus_bridge, &credential, "", "", "", "").await;
+            }
+        }
+
+        // One series per configured bridge, issuer and outcome; nothing per
+        // subject, unconfigured issuer or unknown bridge
+        let mut out = String::new();
+        service.render_metrics(&mut out);
+        let series: Vec<&str> =
+            out.lines().filter(|l| l.starts_with("trustplane_exchanges_total{")).collect();
+        assert_eq!(series.len(), 3, "{:#?}", series);
+        assert!(series.contains(
+            &"trustplane_exchanges_total{bridge_id=\"idp\",bridge_type=\"jwt\",\
+              issuer=\"https://auth.example.com\",outcome=\"not_implemented\"} 50"
+        ));
+        assert!(series.contains(
+            &"trustplane_exchanges_total{bridge_id=\"unknown\",bridge_type=\"unknown\",\
+              issuer=\"unknown\",outcome=\"bridge_not_found\"} 100"
+        ));
+        assert!(!out.contains("user-") && !out.contains("evil") && !out.contains("bridge-"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_logs_redact_credential() {
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let logs = Capture::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let credential = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+        }));
+        let service = bridge_service(BridgeOptions::default());
+        let err =
+            service.exchange_credential("idp", &credential, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains(&redact(&credential).to_string()), "{}", logs);
+        let raw = std::str::from_utf8(&credential).unwrap();
+        assert!(!logs.contains(raw), "{}", logs);
+        for segment in raw.split('.').filter(|s| s.len() > 8) {
+            assert!(!logs.contains(segment), "{}", logs);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bridge_soft_delete() {
+        let registry = Arc::new(BridgeRegistry::new().with_delete_grace(Duration::from_secs(60)));
+        let idp = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .id("idp")
+        .build()
+        .unwrap();
+        registry.add(idp).unwrap();
+        let service = BridgeServiceImpl::new(
+            test_credentials_manager(TEST_DID),
+            registry.clone(),
+            Arc::new(RevocationStore::in_memory()),
+            BridgeOptions::default(),
+        );
+        let credential =
+            token(serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" }));
+
+        // Removal only marks the bridge, and exchanges say why they fail
+        assert!(registry.undelete("idp", None).is_err());
+        registry.remove("idp", None).unwrap();
+        let marked = registry.get("idp").unwrap();
+        let delete_at = marked.pending_deletion.unwrap();
+        assert_eq!(marked.version, 2);
+        assert!(registry.get_enabled("idp").is_none());
+        let err =
+            service.exchange_credential("idp", &credential, "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "bridge_decommissioning");
+        assert_eq!(err.status_code(), axum::http::StatusCode::GONE);
+
+        // Removing again keeps the deadline; updates keep the mark
+        registry.remove("idp", None).unwrap();
+        registry.update(marked, None).unwrap();
+        assert_eq!(registry.get("idp").unwrap().pending_deletion, Some(delete_at));
+
+        // Undelete brings it back
+        assert!(registry.undelete("idp", Some(1)).is_err());
+        registry.undelete("idp", None).unwrap();
+        assert!(registry.get_enabled("idp").is_some());
+        let err =
+            service.exchange_credential("idp", &credential, "", "", "", "").await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(_)), "{}", err);
+
+        // Bridges are purged once their grace has passed
+        registry.remove("idp", None).unwrap();
+        assert!(registry.purge_deleted(Utc::now()).is_empty());
+        let later = Utc::now() + chrono::Duration::seconds(61);
+        assert_eq!(registry.purge_deleted(later), vec!["idp".to_string()]);
+        assert!(registry.get("idp").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_idempotency_replay() {
+        let service = bridge_service(BridgeOptions::default());
+        let credential =
+            token(serde_json::json!({ "iss": "https://auth.example.com", "sub": "alice" }));
+        let minted = SignedPca {
+            pca: b"pca0".to_vec(),
+            signing_kid: format!("{}#cat-key", TEST_DID),
+        };
+        service.idempotency.insert(
+            "key-1".to_string(),
+            IdempotentExchange {
+                fingerprint: exchange_fingerprint("idp", &credential, "", Binding::default()),
+                pca: minted.clone(),
+            },
+        );
+        let exchange = |key: &str| {
+            let (service, credential, key) = (&service, credential.clone(), key.to_string());
+            async move { service.exchange_credential("idp", &credential, "", "", "", &key).await }
+        };
+
+        assert_eq!(exchange("key-1").await.unwrap(), minted);
+
+        // A replay does not outlive a revocation
+        service.revocations.revoke("alice").unwrap();
+        assert_eq!(exchange("key-1").await.unwrap_err().code(), "subject_revoked");
+        service.revocations.unrevoke("alice").unwrap();
+
+        // A disabled bridge stops replays too
+        let mut config = service.registry.get("idp").unwrap();
+        config.enabled = false;
+        service.registry.update(config, None).unwrap();
+        assert_eq!(exchange("key-1").await.unwrap_err().code(), "bridge_disabled");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_cache() {
+        let service = bridge_service(BridgeOptions {
+            cache_exchanges: true,
+            ..Default::default()
+        });
+        let credential = token(serde_json::json!({
+            "iss": "https://auth.example.com",
+            "sub": "alice",
+            "exp": u64::MAX,
+        }));
+        // Signed before a key rotation: the original kid is still reported
+        let cached = SignedPca {
+            pca: b"pca0".to_vec(),
+            signing_kid: format!("{}#cat-key-2026-01-01", TEST_DID),
+        };
+        service
+            .exchanges
+            .as_ref()
+            .unwrap()
+            .insert(
+                exchange_fingerprint("idp", &credential, "", Binding::default()),
+                cached.clone(),
+            );
+
+        let reused = service.exchange_credential("idp", &credential, "", "", "", "").await;
+        assert_eq!(reused.unwrap(), cached);
+        // A PCA₀ bound to one audience is never reused for another
+        let other_audience =
+            service.exchange_credential("idp", &credential, "billing", "", "", "").await;
+        assert!(matches!(other_audience, Err(Error::NotImplemented(_))));
+
+        // A cached PCA₀ does not outlive a revocation
+        service.revocations.revoke("alice").unwrap();
+        let err =
+            service.exchange_credential("idp", &credential, "", "", "", "").await.unwrap_err();
+        assert_eq!(err.code(), "subject_revoked");
+
+        // Reuse never extends past the token's expiry
+        let max = Duration::from_secs(300);
+        let claims = |exp: Value| serde_json::json!({ "exp": exp }).as_object().unwrap().clone();
+        assert_eq!(exchange_cache_ttl(&Map::new(), max), Some(max));
+        assert_eq!(exchange_cache_ttl(&claims(Value::from(u64::MAX)), max), Some(max));
+        assert_eq!(exchange_cache_ttl(&claims(Value::from(1)), max), None);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let ttl = exchange_cache_ttl(&claims(Value::from(now + 60)), max).unwrap();
+        assert!(ttl <= Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,336 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bridge configurations seeded from a directory.
+//!
+//! Each `.json` file in the directory holds one [`BridgeConfig`]; a file
+//! without an `id` names its bridge after the file stem. The directory is
+//! loaded at startup and then watched: an added or changed file is applied
+//! to the registry, a deleted one removes its bridge (honouring the delete
+//! grace); a renamed file, or one written back within the grace, keeps
+//! its bridge. A file that cannot be read or parsed is logged and skipped,
+//! and the bridge it configured before, if any, is kept as it was.
+//!
+//! A file naming a bridge added through the admin API is skipped. A bridge
+//! changed through the admin API after it was read from its file is not
+//! removed with the file.
+
+use super::{BridgeConfig, BridgeRegistry};
+use crate::error::{Error, Result};
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Quiet time after a change before the directory is rescanned, so that a
+/// burst of writes (an editor saving, a GitOps sync) is applied once
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Bridge last applied from a file
+struct Applied {
+    id: String,
+    /// Registry version the file produced
+    version: u64,
+    contents: Vec<u8>,
+}
+
+/// Directory of bridge configuration files
+pub struct BridgeDir {
+    dir: PathBuf,
+    registry: Arc<BridgeRegistry>,
+    files: Mutex<Files>,
+}
+
+/// What was last made of each file
+#[derive(Default)]
+struct Files {
+    applied: HashMap<PathBuf, Applied>,
+    /// Contents skipped as invalid, not logged again until they change
+    rejected: HashMap<PathBuf, Vec<u8>>,
+    /// Bridges left marked for deletion by a removed file, at the version
+    /// that left them; a file naming one again (a rename) takes it back
+    released: HashMap<String, u64>,
+}
+
+impl BridgeDir {
+    /// Load every bridge file in `dir` into `registry`
+    pub fn load(dir: impl Into<PathBuf>, registry: Arc<BridgeRegistry>) -> Result<Self> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(Error::Config(format!(
+                "bridges dir {} is not a directory",
+                dir.display()
+            )));
+        }
+        let seeded = Self {
+            dir,
+            registry,
+            files: Mutex::new(Files::default()),
+        };
+        seeded.sync()?;
+        info!(
+            dir = %seeded.dir.display(),
+            count = seeded.files.lock().unwrap().applied.len(),
+            "Bridges loaded from directory"
+        );
+        Ok(seeded)
+    }
+
+    /// Apply changes to the directory as they happen, until the watch fails
+    pub async fn watch(self: Arc<Self>) -> Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| Error::Internal(format!("cannot watch bridges dir: {}", e)))?;
+        watcher
+            .watch(&self.dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Internal(format!("cannot watch bridges dir: {}", e)))?;
+        info!(dir = %self.dir.display(), "Watching bridges directory");
+
+        // Catch up on changes made since the startup load
+        self.sync()?;
+        while let Some(event) = rx.recv().await {
+            match event {
+                // Our own reads of the files
+                Ok(notify::Event { kind, .. }) if kind.is_access() => continue,
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Bridges directory watch error"),
+            }
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            if let Err(e) = self.sync() {
+                error!(dir = %self.dir.display(), error = %e, "Could not rescan bridges directory");
+            }
+        }
+        Ok(())
+    }
+
+    /// Bring the registry in line with the files currently in the directory
+    fn sync(&self) -> Result<()> {
+        let files = bridge_files(&self.dir)?;
+        let mut state = self.files.lock().unwrap();
+        let Files { applied, rejected, released } = &mut *state;
+
+        rejected.retain(|path, _| files.contains(path));
+        released.retain(|id, version| self.registry.get(id).is_some_and(|b| b.version == *version));
+        let gone: Vec<PathBuf> = applied.keys().filter(|p| !files.contains(p)).cloned().collect();
+        for path in gone {
+            let Some(Applied { id, version, .. }) = applied.remove(&path) else {
+                continue;
+            };
+            if let Some(version) = self.remove(&id, version, &path) {
+                released.insert(id, version);
+            }
+        }
+
+        for path in files {
+            let contents = match std::fs::read(&path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    error!(file = %path.display(), error = %e, "Skipping unreadable bridge file");
+                    continue;
+                }
+            };
+            let previous = applied.get(&path);
+            if previous.is_some_and(|p| p.contents == contents)
+                || rejected.get(&path) == Some(&contents)
+            {
+                continue;
+            }
+            let config = match parse(&path, &contents) {
+                Ok(config) => config,
+                Err(e) => {
+                    error!(file = %path.display(), error = %e, "Skipping invalid bridge file");
+                    rejected.insert(path, contents);
+                    continue;
+                }
+            };
+            let owner = applied.iter().find(|(p, a)| a.id == config.id && **p != path);
+            if let Some((other, _)) = owner {
+                error!(
+                    file = %path.display(),
+                    bridge_id = %config.id,
+                    other = %other.display(),
+                    "Skipping bridge file: bridge id already configured by another file"
+                );
+                continue;
+            }
+            let owned = previous.is_some_and(|p| p.id == config.id)
+                || released.contains_key(&config.id);
+            if !owned && self.registry.get(&config.id).is_some() {
+                error!(
+                    file = %path.display(),
+                    bridge_id = %config.id,
+                    "Skipping bridge file: bridge id already configured through the admin API"
+                );
+                continue;
+            }
+            let replaced = previous
+                .filter(|p| p.id != config.id)
+                .map(|p| (p.id.clone(), p.version));
+
+            let id = config.id.clone();
+            if let Err(e) = self.registry.import(vec![config]) {
+                error!(file = %path.display(), error = %e, "Skipping invalid bridge file");
+                rejected.insert(path, contents);
+                continue;
+            }
+            rejected.remove(&path);
+            released.remove(&id);
+            info!(file = %path.display(), bridge_id = %id, "Bridge configuration read from file");
+            if let Some((replaced, version)) = replaced
+                && let Some(version) = self.remove(&replaced, version, &path)
+            {
+                released.insert(replaced, version);
+            }
+            let version = self.registry.get(&id).map_or(0, |b| b.version);
+            applied.insert(path, Applied { id, version, contents });
+        }
+        Ok(())
+    }
+
+    /// Remove a bridge read from `path`, unless it changed since. Returns
+    /// the version of a bridge left marked for deletion.
+    fn remove(&self, id: &str, version: u64, path: &Path) -> Option<u64> {
+        match self.registry.remove(id, Some(version)) {
+            Ok(()) | Err(Error::NotFound(_)) => {
+                info!(file = %path.display(), bridge_id = %id, "Bridge file removed");
+            }
+            Err(e) => error!(bridge_id = %id, error = %e, "Could not remove bridge"),
+        }
+        self.registry.get(id).filter(|b| b.pending_deletion.is_some()).map(|b| b.version)
+    }
+}
+
+/// Visible `.json` files directly in `dir`, ordered by name
+fn bridge_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let visible = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| !name.starts_with('.'));
+        if visible && path.extension().is_some_and(|ext| ext == "json") && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Bridge configuration in a file, its ID defaulting to the file stem
+fn parse(path: &Path, contents: &[u8]) -> Result<BridgeConfig> {
+    let mut config: BridgeConfig = serde_json::from_slice(contents)
+        .map_err(|e| Error::Invalid(format!("invalid bridge config: {}", e)))?;
+    if config.id.is_empty() {
+        config.id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::BridgeConfigBuilder;
+
+    fn bridge_json(id: &str) -> Vec<u8> {
+        let config = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://auth.example.com",
+        )
+        .id(id)
+        .build()
+        .unwrap();
+        serde_json::to_vec(&config).unwrap()
+    }
+
+    #[test]
+    fn test_bridge_dir_sync() {
+        let dir = std::env::temp_dir().join(format!("permguard-bridges-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("okta.json"), bridge_json("okta")).unwrap();
+        std::fs::write(dir.join("entra.json"), bridge_json("")).unwrap();
+        std::fs::write(dir.join("broken.json"), b"{ not json").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        // Malformed files are skipped, the rest loads; IDs default to the stem
+        let registry = Arc::new(BridgeRegistry::new());
+        let seeded = BridgeDir::load(&dir, registry.clone()).unwrap();
+        let ids: Vec<String> = registry.list().into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, ["entra", "okta"]);
+
+        // Added, removed and fixed files are applied; admin bridges stay
+        registry.add(parse(Path::new("admin.json"), &bridge_json("")).unwrap()).unwrap();
+        std::fs::remove_file(dir.join("okta.json")).unwrap();
+        std::fs::write(dir.join("broken.json"), bridge_json("fixed")).unwrap();
+        std::fs::write(dir.join("auth0.json"), bridge_json("")).unwrap();
+        seeded.sync().unwrap();
+        let ids: Vec<String> = registry.list().into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, ["admin", "auth0", "entra", "fixed"]);
+
+        // A file breaking later keeps its bridge; an unchanged file is not rewritten
+        let version = registry.get("entra").unwrap().version;
+        std::fs::write(dir.join("auth0.json"), b"[]").unwrap();
+        std::fs::write(dir.join("copy.json"), bridge_json("entra")).unwrap();
+        seeded.sync().unwrap();
+        assert!(registry.get("auth0").is_some());
+        assert_eq!(registry.get("entra").unwrap().version, version);
+
+        // Files never take over admin bridges
+        let version = registry.get("admin").unwrap().version;
+        std::fs::write(dir.join("admin.json"), bridge_json("")).unwrap();
+        seeded.sync().unwrap();
+        assert_eq!(registry.get("admin").unwrap().version, version);
+
+        // A bridge changed through the admin API outlives its file
+        let mut entra = registry.get("entra").unwrap();
+        entra.pca_audience = Some("https://edited.example.com".to_string());
+        registry.update(entra, None).unwrap();
+        std::fs::remove_file(dir.join("entra.json")).unwrap();
+        seeded.sync().unwrap();
+        assert!(registry.get("entra").is_some());
+        assert!(registry.get("fixed").is_some());
+        std::fs::remove_file(dir.join("broken.json")).unwrap();
+        seeded.sync().unwrap();
+        assert!(registry.get("fixed").is_none());
+
+        // Renaming a file keeps its bridge, even under a delete grace
+        let graced = Arc::new(BridgeRegistry::new().with_delete_grace(Duration::from_secs(60)));
+        let seeded = BridgeDir::load(&dir, graced.clone()).unwrap();
+        assert!(graced.get("entra").is_some());
+        std::fs::rename(dir.join("copy.json"), dir.join("renamed.json")).unwrap();
+        seeded.sync().unwrap();
+        assert!(graced.get("entra").unwrap().pending_deletion.is_none());
+        graced.purge_deleted(chrono::Utc::now() + chrono::Duration::minutes(2));
+        assert!(graced.get("entra").is_some());
+        std::fs::remove_file(dir.join("renamed.json")).unwrap();
+        seeded.sync().unwrap();
+        assert!(graced.get("entra").unwrap().pending_deletion.is_some());
+
+        assert!(BridgeDir::load(dir.join("missing"), registry).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
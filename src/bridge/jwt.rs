@@ -0,0 +1,132 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JWT parsing.
+
+use crate::error::{Error, Result};
+use base64::Engine;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+/// Compact JWS, decoded but not yet verified
+#[derive(Clone, Debug)]
+pub struct Jwt {
+    /// JOSE header
+    pub header: Map<String, Value>,
+    /// Claims set
+    pub claims: Map<String, Value>,
+}
+
+impl Jwt {
+    /// Decode a compact JWS (`header.payload.signature`)
+    ///
+    /// A compact JWE (five segments) is recognised and rejected with
+    /// [`Error::NotImplemented`]: decryption needs a key-agreement key, which
+    /// the Trust Plane credentials do not carry yet. [`Error::DecryptFailed`]
+    /// is kept for ciphertexts that fail to decrypt once they do.
+    pub fn decode(token: &str) -> Result<Self> {
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() == 5 {
+            let header = decode_segment(parts[0], "JWE header")?;
+            let alg = header.get("alg").and_then(Value::as_str).unwrap_or("?");
+            let enc = header.get("enc").and_then(Value::as_str).unwrap_or("?");
+            debug!(alg, enc, "JWE credential refused: no key-agreement key to decrypt it");
+            return Err(Error::NotImplemented("jwe_decryption".to_string()));
+        }
+        if parts.len() != 3 {
+            return Err(Error::Invalid(format!(
+                "malformed JWT: expected 3 segments, got {}",
+                parts.len()
+            )));
+        }
+
+        Ok(Self {
+            header: decode_segment(parts[0], "header")?,
+            claims: decode_segment(parts[1], "payload")?,
+        })
+    }
+}
+
+/// RFC 7638 thumbprint of a public JWK: base64url SHA-256 of its required
+/// members in lexicographic order. `None` for key types without a
+/// thumbprint definition or missing members.
+pub fn jwk_thumbprint(jwk: &Value) -> Option<String> {
+    let members: &[&str] = match jwk.get("kty")?.as_str()? {
+        "EC" => &["crv", "kty", "x", "y"],
+        "OKP" => &["crv", "kty", "x"],
+        "RSA" => &["e", "kty", "n"],
+        _ => return None,
+    };
+    let mut canonical = String::from("{");
+    for (i, member) in members.iter().enumerate() {
+        if i > 0 {
+            canonical.push(',');
+        }
+        let value = jwk.get(member)?.as_str()?;
+        canonical.push_str(&format!("\"{}\":{}", member, Value::from(value)));
+    }
+    canonical.push('}');
+    Some(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(canonical)))
+}
+
+/// Decode a base64url JSON object segment
+fn decode_segment(segment: &str, name: &str) -> Result<Map<String, Value>> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|e| Error::Invalid(format!("malformed JWT {}: {}", name, e)))?;
+
+    match serde_json::from_slice(&bytes) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(Error::Invalid(format!("malformed JWT {}: not an object", name))),
+        Err(e) => Err(Error::Invalid(format!("malformed JWT {}: {}", name, e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwe_is_reported_as_not_implemented() {
+        let header = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(r#"{"alg":"ECDH-ES+A256KW","enc":"A256GCM"}"#);
+        let jwe = format!("{}.key.iv.ciphertext.tag", header);
+        let err = Jwt::decode(&jwe).unwrap_err();
+        assert!(matches!(err, Error::NotImplemented(ref feature) if feature == "jwe_decryption"));
+        assert_eq!(err.status_code(), axum::http::StatusCode::NOT_IMPLEMENTED);
+
+        let err = Jwt::decode("a.b").unwrap_err();
+        assert_eq!(err.code(), "invalid_argument");
+    }
+
+    #[test]
+    fn test_jwk_thumbprint() {
+        // RFC 8037, appendix A.3
+        let jwk = serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+            "kid": "ignored",
+        });
+        assert_eq!(
+            jwk_thumbprint(&jwk).as_deref(),
+            Some("kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k")
+        );
+        assert_eq!(jwk_thumbprint(&serde_json::json!({ "kty": "oct", "k": "c2VjcmV0" })), None);
+        assert_eq!(jwk_thumbprint(&serde_json::json!({ "kty": "OKP", "crv": "Ed25519" })), None);
+    }
+}
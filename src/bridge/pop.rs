@@ -0,0 +1,237 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Proof-of-possession of bridge credentials.
+//!
+//! A token bound to a key (RFC 7800 `cnf.jwk`) is only exchanged together
+//! with a proof that the client holds that key: a DPoP-style JWT (RFC 9449)
+//! signed with it. The proof carries `ath`, the base64url SHA-256 of the
+//! token, so it cannot be moved to another token; `htm` and `htu` naming
+//! the exchange request; a recent `iat`; and a `jti` that is refused if
+//! seen again while the proof is still recent. Confirmation keys must be
+//! Ed25519 (`EdDSA`).
+//!
+//! The confirmation key is read from the token claims, and token
+//! signatures are not verified against the issuer's JWKS yet. Until they
+//! are, a proof only shows the client holds the key the token names, and
+//! whoever forges a token can name their own key in it. Bridges therefore
+//! cannot set `require_pop` yet; proofs clients present are still checked.
+
+use super::jwt::{jwk_thumbprint, Jwt};
+use crate::cache::TtlCache;
+use crate::credentials::{public_key_from_jwk, verify_signature};
+use crate::error::{Error, Result};
+use base64::Engine;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// `typ` header of a proof
+const POP_TYPE: &str = "dpop+jwt";
+
+/// Seconds a proof is accepted after its `iat`, on top of the clock skew
+pub const POP_MAX_AGE_SECS: u64 = 60;
+
+/// Proofs remembered at most for replay detection
+pub const POP_REPLAY_CAPACITY: usize = 10_000;
+
+/// Paths of the exchange request a proof's `htu` may name: the HTTP route
+/// and the gRPC method
+const EXCHANGE_PATHS: [&str; 2] = [
+    "/v1/bridge/exchange",
+    "/permguard.trustplane.bridge.v1.BridgeService/Exchange",
+];
+
+/// `jti`s of accepted proofs, keyed with the confirmation key thumbprint
+pub type SeenProofs = TtlCache<(String, String), ()>;
+
+/// Remembers accepted proofs for as long as they could be replayed
+pub fn seen_proofs() -> SeenProofs {
+    SeenProofs::new(POP_REPLAY_CAPACITY, Duration::from_secs(POP_MAX_AGE_SECS))
+}
+
+/// Verify `proof` for `credential`, whose decoded claims are `claims`,
+/// refusing a proof already in `seen` and recording it otherwise
+pub fn verify_pop(
+    proof: &str,
+    credential: &[u8],
+    claims: &Map<String, Value>,
+    seen: &SeenProofs,
+    now: u64,
+    skew: u64,
+) -> Result<()> {
+    let fail = Error::PopVerificationFailed;
+    let cnf_jwk = claims
+        .get("cnf")
+        .and_then(|cnf| cnf.get("jwk"))
+        .ok_or_else(|| fail("token has no cnf.jwk confirmation key".into()))?;
+
+    let jwt = Jwt::decode(proof).map_err(|e| fail(format!("malformed proof: {}", e)))?;
+    let header = |name: &str| jwt.header.get(name).and_then(Value::as_str);
+    if header("typ") != Some(POP_TYPE) {
+        return Err(fail(format!("proof typ must be {}", POP_TYPE)));
+    }
+    let alg = header("alg").unwrap_or("none");
+    if alg != "EdDSA" {
+        return Err(fail(format!("unsupported proof algorithm {}", alg)));
+    }
+    // A proof naming its key must name the confirmation key
+    if let Some(jwk) = jwt.header.get("jwk")
+        && jwk_thumbprint(jwk) != jwk_thumbprint(cnf_jwk)
+    {
+        return Err(fail("proof key is not the token's confirmation key".into()));
+    }
+
+    let public_key = public_key_from_jwk(cnf_jwk)
+        .map_err(|e| fail(format!("unsupported confirmation key: {}", e)))?;
+    let (signing_input, signature) = proof.rsplit_once('.').unwrap_or_default();
+    let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|e| fail(format!("malformed proof signature: {}", e)))?;
+    verify_signature(&public_key, signing_input.as_bytes(), &signature)
+        .map_err(|_| fail("proof is not signed by the token's confirmation key".into()))?;
+
+    let claim = |name: &str| jwt.claims.get(name);
+    let ath = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(credential));
+    if claim("ath").and_then(Value::as_str) != Some(ath.as_str()) {
+        return Err(fail("proof is not bound to this token (ath)".into()));
+    }
+    if claim("htm").and_then(Value::as_str) != Some("POST") {
+        return Err(fail("proof is not for a POST request (htm)".into()));
+    }
+    let htu = claim("htu").and_then(Value::as_str).unwrap_or_default();
+    if !is_exchange_uri(htu) {
+        return Err(fail(format!("proof is not for the exchange endpoint (htu {:?})", htu)));
+    }
+    let jti = claim("jti")
+        .and_then(Value::as_str)
+        .filter(|jti| !jti.is_empty())
+        .ok_or_else(|| fail("proof has no jti".into()))?;
+    let iat = claim("iat")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| fail("proof has no iat".into()))?;
+    if iat > now.saturating_add(skew) || now > iat.saturating_add(POP_MAX_AGE_SECS + skew) {
+        return Err(fail(format!("proof issued at {} is not recent", iat)));
+    }
+
+    // Recent for at most max age + twice the skew after it is first seen
+    let ttl = Duration::from_secs(POP_MAX_AGE_SECS + 2 * skew);
+    let key = (jti.to_string(), jwk_thumbprint(cnf_jwk).unwrap_or_default());
+    if !seen.insert_new_with_ttl(key, (), ttl) {
+        return Err(fail(format!("proof {} was already used", jti)));
+    }
+    Ok(())
+}
+
+/// Whether `htu` is an absolute HTTP(S) URI for the exchange request.
+///
+/// Only the path is compared: behind a proxy the Trust Plane does not know
+/// the origin clients reach it at. Query and fragment are ignored, as in
+/// RFC 9449.
+fn is_exchange_uri(htu: &str) -> bool {
+    let Ok(uri) = htu.parse::<axum::http::Uri>() else {
+        return false;
+    };
+    let http = matches!(uri.scheme_str(), Some("https" | "http"));
+    http && uri.host().is_some() && EXCHANGE_PATHS.contains(&uri.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::KeyPair;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use serde_json::json;
+
+    const NOW: u64 = 1_800_000_000;
+
+    fn proof(key: &KeyPair, header: Value, claims: Value) -> String {
+        let encode = |v: &Value| URL_SAFE_NO_PAD.encode(v.to_string());
+        let signing_input = format!("{}.{}", encode(&header), encode(&claims));
+        let signature = key.sign(signing_input.as_bytes());
+        format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    #[test]
+    fn test_verify_pop() {
+        let holder = KeyPair::generate("client#pop");
+        let thief = KeyPair::generate("thief#pop");
+        let credential = b"header.payload.signature";
+        let claims = json!({ "sub": "alice", "cnf": { "jwk": holder.public_jwk() } });
+        let claims = claims.as_object().unwrap();
+        let ath = URL_SAFE_NO_PAD.encode(Sha256::digest(credential));
+        let header = json!({ "typ": "dpop+jwt", "alg": "EdDSA", "jwk": holder.public_jwk() });
+        let htu = "https://tp.example.com/v1/bridge/exchange";
+        let body = |jti: &str| {
+            json!({ "jti": jti, "iat": NOW, "ath": ath, "htm": "POST", "htu": htu })
+        };
+        let seen = seen_proofs();
+        let verify = |proof: &str| verify_pop(proof, credential, claims, &seen, NOW, 60);
+
+        assert!(verify(&proof(&holder, header.clone(), body("1"))).is_ok());
+        // Each proof is accepted once; gRPC exchanges name the RPC
+        let grpc = "https://tp.example.com/permguard.trustplane.bridge.v1.BridgeService/Exchange";
+        let mut grpc_body = body("2");
+        grpc_body["htu"] = json!(grpc);
+        assert!(verify(&proof(&holder, header.clone(), grpc_body)).is_ok());
+        let err = verify(&proof(&holder, header.clone(), body("1"))).unwrap_err();
+        assert!(err.to_string().contains("already used"), "{}", err);
+
+        let with = |name: &str, value: Value| {
+            let mut body = body("3");
+            match value {
+                Value::Null => body.as_object_mut().unwrap().remove(name),
+                value => body.as_object_mut().unwrap().insert(name.to_string(), value),
+            };
+            body
+        };
+        let mut failures = vec![
+            // Signed by another key, or naming another key
+            proof(&thief, header.clone(), body("3")),
+            proof(
+                &holder,
+                json!({ "typ": "dpop+jwt", "alg": "EdDSA", "jwk": thief.public_jwk() }),
+                body("3"),
+            ),
+            // Not a proof, or for another token
+            proof(&holder, json!({ "typ": "JWT", "alg": "EdDSA" }), body("3")),
+            proof(&holder, header.clone(), with("ath", json!("other"))),
+            // For another request
+            proof(&holder, header.clone(), with("htm", json!("GET"))),
+            proof(&holder, header.clone(), with("htm", Value::Null)),
+            proof(&holder, header.clone(), with("htu", json!("https://tp.example.com/v1/other"))),
+            proof(&holder, header.clone(), with("htu", json!("/v1/bridge/exchange"))),
+            proof(&holder, header.clone(), with("htu", Value::Null)),
+            // Stale, or without jti
+            proof(&holder, header.clone(), with("iat", json!(NOW - 200))),
+            proof(&holder, header.clone(), with("jti", Value::Null)),
+            "not-a-jwt".to_string(),
+        ];
+        for proof in failures.drain(..) {
+            let err = verify(&proof).unwrap_err();
+            assert_eq!(err.code(), "pop_verification_failed", "{}", err);
+        }
+        // Refused proofs are not remembered
+        assert!(verify(&proof(&holder, header.clone(), body("3"))).is_ok());
+
+        // A token without a confirmation key cannot be proven
+        let bearer = json!({ "sub": "alice" });
+        let proof = proof(&holder, header, body("4"));
+        let bearer = bearer.as_object().unwrap();
+        let err = verify_pop(&proof, credential, bearer, &seen, NOW, 60).unwrap_err();
+        assert!(err.to_string().contains("cnf.jwk"), "{}", err);
+    }
+}
@@ -16,14 +16,21 @@
 
 //! Bridge Admin gRPC service.
 
-use crate::bridge::{BridgeConfig, BridgeRegistry, BridgeType, BridgeTypeConfig, JwtBridgeConfig, MappingConfig};
+use crate::bridge::{
+    BridgeConfig, BridgeRegistry, BridgeType, BridgeTypeConfig, JwtBridgeConfig, MappingConfig,
+    DEFAULT_MAX_CLOCK_SKEW_SECS,
+};
+use crate::error::{Error, Result};
 use crate::proto::bridge_admin::{
     bridge_admin_service_server::{BridgeAdminService, BridgeAdminServiceServer},
     AddBridgeRequest, AddBridgeResponse,
     GetBridgeRequest, GetBridgeResponse,
     ListBridgesRequest, ListBridgesResponse,
     RemoveBridgeRequest, RemoveBridgeResponse,
+    SetBridgeEnabledRequest, SetBridgeEnabledResponse,
+    UndeleteBridgeRequest, UndeleteBridgeResponse,
     UpdateBridgeRequest, UpdateBridgeResponse,
+    bridge_config::Config as ProtoConfig,
     BridgeConfig as ProtoBridgeConfig,
     BridgeType as ProtoBridgeType,
     JwtBridgeConfig as ProtoJwtBridgeConfig,
@@ -86,7 +93,7 @@ impl BridgeAdminService for BridgeAdminServiceImpl {
         let req = request.into_inner();
         
         let bridge = req.bridge
-            .ok_or_else(|| Status::invalid_argument("Bridge config required"))?;
+            .ok_or_else(|| Status::from(Error::invalid_field("bridge", "Bridge config required")))?;
         
         let config = from_proto_bridge_config(bridge)?;
         
@@ -109,11 +116,11 @@ impl BridgeAdminService for BridgeAdminServiceImpl {
         let req = request.into_inner();
         
         let bridge = req.bridge
-            .ok_or_else(|| Status::invalid_argument("Bridge config required"))?;
+            .ok_or_else(|| Status::from(Error::invalid_field("bridge", "Bridge config required")))?;
         
         let config = from_proto_bridge_config(bridge)?;
         
-        match self.registry.update(config) {
+        match self.registry.update(config, req.expected_version) {
             Ok(()) => Ok(Response::new(UpdateBridgeResponse {
                 success: true,
                 error: String::new(),
@@ -131,7 +138,7 @@ impl BridgeAdminService for BridgeAdminServiceImpl {
     ) -> std::result::Result<Response<RemoveBridgeResponse>, Status> {
         let req = request.into_inner();
         
-        match self.registry.remove(&req.id) {
+        match self.registry.remove(&req.id, req.expected_version) {
             Ok(()) => Ok(Response::new(RemoveBridgeResponse {
                 success: true,
                 error: String::new(),
@@ -142,78 +149,280 @@ impl BridgeAdminService for BridgeAdminServiceImpl {
             })),
         }
     }
+
+    async fn set_bridge_enabled(
+        &self,
+        request: Request<SetBridgeEnabledRequest>,
+    ) -> std::result::Result<Response<SetBridgeEnabledResponse>, Status> {
+        let req = request.into_inner();
+        let reason = Some(req.reason).filter(|r| !r.is_empty());
+
+        match self.registry.set_enabled(&req.id, req.enabled, reason, req.expected_version) {
+            Ok(()) => Ok(Response::new(SetBridgeEnabledResponse {
+                success: true,
+                error: String::new(),
+            })),
+            Err(e) => Ok(Response::new(SetBridgeEnabledResponse {
+                success: false,
+                error: e.to_string(),
+            })),
+        }
+    }
+
+    async fn undelete_bridge(
+        &self,
+        request: Request<UndeleteBridgeRequest>,
+    ) -> std::result::Result<Response<UndeleteBridgeResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.registry.undelete(&req.id, req.expected_version) {
+            Ok(()) => Ok(Response::new(UndeleteBridgeResponse {
+                success: true,
+                error: String::new(),
+            })),
+            Err(e) => Ok(Response::new(UndeleteBridgeResponse {
+                success: false,
+                error: e.to_string(),
+            })),
+        }
+    }
 }
 
 // Conversion helpers
+//
+// Matches on bridge types are exhaustive on both sides, so a new variant
+// in the domain or the proto fails to compile until it is converted.
 
 fn to_proto_bridge_config(config: BridgeConfig) -> ProtoBridgeConfig {
     let bridge_type = match config.bridge_type {
-        BridgeType::Jwt => ProtoBridgeType::Jwt as i32,
+        BridgeType::Jwt => ProtoBridgeType::Jwt,
     };
 
     let config_oneof = match config.config {
-        BridgeTypeConfig::Jwt(jwt) => {
-            let jwt_proto = ProtoJwtBridgeConfig {
-                wellknown_url: jwt.wellknown_url,
-                issuer: jwt.issuer,
-                audiences: jwt.audiences,
-                mapping: Some(ProtoMappingConfig {
-                    subject_claim: jwt.mapping.subject_claim,
-                    organization_claim: jwt.mapping.organization_claim,
-                    custom: jwt.mapping.custom,
-                }),
-            };
-            Some(crate::proto::bridge_admin::bridge_config::Config::Jwt(jwt_proto))
-        }
+        BridgeTypeConfig::Jwt(jwt) => ProtoConfig::Jwt(to_proto_jwt_config(jwt)),
     };
 
     ProtoBridgeConfig {
         id: config.id,
-        r#type: bridge_type,
+        r#type: bridge_type as i32,
         enabled: config.enabled,
-        config: config_oneof,
+        pca_issuer: config.pca_issuer.unwrap_or_default(),
+        disabled_reason: config.disabled_reason.unwrap_or_default(),
+        version: config.version,
+        pca_audience: config.pca_audience.unwrap_or_default(),
+        pending_deletion_at: config
+            .pending_deletion
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_default(),
+        config: Some(config_oneof),
     }
 }
 
-fn from_proto_bridge_config(proto: ProtoBridgeConfig) -> Result<BridgeConfig, Status> {
-    let bridge_type = ProtoBridgeType::try_from(proto.r#type)
-        .map_err(|_| Status::invalid_argument("Invalid bridge type"))?;
-    
-    let config = match bridge_type {
-        ProtoBridgeType::Jwt => {
-            // Extract JWT config from oneof
-            let jwt = match proto.config {
-                Some(crate::proto::bridge_admin::bridge_config::Config::Jwt(j)) => j,
-                _ => return Err(Status::invalid_argument("JWT config required for JWT bridge")),
-            };
-            let mapping = jwt.mapping.unwrap_or_default();
-            
-            BridgeTypeConfig::Jwt(JwtBridgeConfig {
-                wellknown_url: jwt.wellknown_url,
-                issuer: jwt.issuer,
-                audiences: jwt.audiences,
-                mapping: MappingConfig {
-                    subject_claim: if mapping.subject_claim.is_empty() { 
-                        "sub".to_string() 
-                    } else { 
-                        mapping.subject_claim 
-                    },
-                    organization_claim: if mapping.organization_claim.is_empty() {
-                        "org".to_string()
-                    } else {
-                        mapping.organization_claim
-                    },
-                    custom: mapping.custom,
-                },
-            })
+fn from_proto_bridge_config(proto: ProtoBridgeConfig) -> Result<BridgeConfig> {
+    let proto_type = ProtoBridgeType::try_from(proto.r#type)
+        .map_err(|_| Error::invalid_field("bridge.type", "Invalid bridge type"))?;
+
+    let bridge_type = match proto_type {
+        ProtoBridgeType::Jwt => BridgeType::Jwt,
+        ProtoBridgeType::Unspecified => {
+            return Err(Error::invalid_field("bridge.type", "Bridge type is required"))
+        }
+        ProtoBridgeType::Spiffe | ProtoBridgeType::Vc => {
+            return Err(Error::invalid_field(
+                "bridge.type",
+                format!("Unsupported bridge type: {}", proto_type.as_str_name()),
+            ))
+        }
+    };
+
+    // The oneof must carry the configuration for the declared type
+    let config = match (&bridge_type, proto.config) {
+        (BridgeType::Jwt, Some(ProtoConfig::Jwt(jwt))) => {
+            BridgeTypeConfig::Jwt(from_proto_jwt_config(jwt))
+        }
+        (BridgeType::Jwt, None) => {
+            return Err(Error::invalid_field(
+                "bridge.jwt",
+                "JWT config required for JWT bridge",
+            ))
         }
-        _ => return Err(Status::invalid_argument("Unsupported bridge type")),
     };
-    
+
     Ok(BridgeConfig {
         id: proto.id,
-        bridge_type: BridgeType::Jwt,
+        bridge_type,
         enabled: proto.enabled,
+        pca_issuer: Some(proto.pca_issuer).filter(|i| !i.is_empty()),
+        pca_audience: Some(proto.pca_audience).filter(|a| !a.is_empty()),
+        disabled_reason: Some(proto.disabled_reason).filter(|r| !r.is_empty()),
+        // Output only: deletion is scheduled by RemoveBridge alone
+        pending_deletion: None,
+        version: proto.version,
         config,
     })
-}
\ No newline at end of file
+}
+
+fn to_proto_jwt_config(jwt: JwtBridgeConfig) -> ProtoJwtBridgeConfig {
+    ProtoJwtBridgeConfig {
+        wellknown_url: jwt.wellknown_url,
+        // Single-issuer clients only read `issuer`
+        issuer: if jwt.issuers.len() == 1 {
+            jwt.issuers[0].clone()
+        } else {
+            String::new()
+        },
+        issuers: jwt.issuers,
+        audiences: jwt.audiences,
+        require_audience: jwt.require_audience,
+        require_nonce: jwt.require_nonce,
+        require_pop: jwt.require_pop,
+        mapping: Some(ProtoMappingConfig {
+            subject_claim: jwt.mapping.subject_claim,
+            // Single-claim clients only read `organization_claim`
+            organization_claim: if jwt.mapping.organization_claims.len() == 1 {
+                jwt.mapping.organization_claims[0].clone()
+            } else {
+                String::new()
+            },
+            custom: jwt.mapping.custom,
+            allowed_claims: jwt.mapping.allowed_claims,
+            denied_claims: jwt.mapping.denied_claims,
+            organization_claims: jwt.mapping.organization_claims,
+            default_organization: jwt.mapping.default_organization.unwrap_or_default(),
+            initial_attributes: jwt.mapping.initial_attributes,
+            namespace_attributes: jwt.mapping.namespace_attributes,
+            copy_all_claims: jwt.mapping.copy_all_claims,
+        }),
+        required_pca_attributes: jwt.required_pca_attributes,
+        allowed_algorithms: jwt.allowed_algorithms,
+        max_clock_skew_secs: Some(jwt.max_clock_skew_secs),
+        max_token_age_secs: jwt.max_token_age_secs,
+        pinned_thumbprints: jwt.pinned_thumbprints,
+    }
+}
+
+fn from_proto_jwt_config(jwt: ProtoJwtBridgeConfig) -> JwtBridgeConfig {
+    let mapping = jwt.mapping.unwrap_or_default();
+
+    // Legacy `issuer` is merged into `issuers`
+    let mut issuers = jwt.issuers;
+    if !jwt.issuer.is_empty() && !issuers.contains(&jwt.issuer) {
+        issuers.insert(0, jwt.issuer);
+    }
+
+    // Legacy `organization_claim` is merged into `organization_claims`
+    let mut organization_claims = mapping.organization_claims;
+    if !mapping.organization_claim.is_empty()
+        && !organization_claims.contains(&mapping.organization_claim)
+    {
+        organization_claims.insert(0, mapping.organization_claim);
+    }
+    if organization_claims.is_empty() {
+        organization_claims.push("org".to_string());
+    }
+
+    JwtBridgeConfig {
+        wellknown_url: jwt.wellknown_url,
+        issuers,
+        audiences: jwt.audiences,
+        require_audience: jwt.require_audience,
+        require_nonce: jwt.require_nonce,
+        require_pop: jwt.require_pop,
+        mapping: MappingConfig {
+            subject_claim: if mapping.subject_claim.is_empty() {
+                "sub".to_string()
+            } else {
+                mapping.subject_claim
+            },
+            organization_claims,
+            default_organization: Some(mapping.default_organization).filter(|o| !o.is_empty()),
+            custom: mapping.custom,
+            allowed_claims: mapping.allowed_claims,
+            denied_claims: mapping.denied_claims,
+            initial_attributes: mapping.initial_attributes,
+            namespace_attributes: mapping.namespace_attributes,
+            copy_all_claims: mapping.copy_all_claims,
+        },
+        required_pca_attributes: jwt.required_pca_attributes,
+        allowed_algorithms: jwt.allowed_algorithms,
+        max_clock_skew_secs: jwt.max_clock_skew_secs.unwrap_or(DEFAULT_MAX_CLOCK_SKEW_SECS),
+        max_token_age_secs: jwt.max_token_age_secs,
+        pinned_thumbprints: jwt.pinned_thumbprints,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::BridgeConfigBuilder;
+
+    fn jwt_bridge() -> BridgeConfig {
+        let mut bridge = BridgeConfigBuilder::jwt(
+            "https://auth.example.com/.well-known/openid-configuration",
+            "https://eu.example.com",
+        )
+        .id("idp")
+        .pca_issuer("did:web:tenant.example.com")
+        .pca_audience("https://api.example.com")
+        .issuer("https://us.example.com")
+        .audience("api")
+        .require_audience(true)
+        .require_nonce(true)
+        .required_pca_attribute("department")
+        .organization_claims(["org", "tid"])
+        .default_organization("acme")
+        .custom_claim("email", "email")
+        .denied_claim("ssn")
+        .initial_attribute("tier", "gold")
+        .namespace_attributes(true)
+        .copy_all_claims(true)
+        .allowed_algorithm("EdDSA")
+        .max_clock_skew_secs(30)
+        .max_token_age_secs(3600)
+        .build()
+        .unwrap();
+        // Refused by validation for now, but still carried over the wire
+        let BridgeTypeConfig::Jwt(jwt) = &mut bridge.config;
+        jwt.pinned_thumbprints = vec!["kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k".into()];
+        jwt.require_pop = true;
+        bridge
+    }
+
+    #[test]
+    fn test_jwt_bridge_round_trip() {
+        let bridge = jwt_bridge();
+        let proto = to_proto_bridge_config(bridge.clone());
+        assert_eq!(proto.r#type, ProtoBridgeType::Jwt as i32);
+
+        let back = from_proto_bridge_config(proto).unwrap();
+        assert_eq!(back.bridge_type, BridgeType::Jwt);
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&bridge).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unsupported_bridge_types_rejected() {
+        for bridge_type in [
+            ProtoBridgeType::Unspecified,
+            ProtoBridgeType::Spiffe,
+            ProtoBridgeType::Vc,
+        ] {
+            let mut proto = to_proto_bridge_config(jwt_bridge());
+            proto.r#type = bridge_type as i32;
+            let err = from_proto_bridge_config(proto).unwrap_err();
+            assert!(matches!(err, Error::InvalidField { ref field, .. } if field == "bridge.type"));
+        }
+
+        let mut proto = to_proto_bridge_config(jwt_bridge());
+        proto.r#type = 42;
+        assert!(from_proto_bridge_config(proto).is_err());
+
+        // A JWT bridge without its JWT configuration is not coerced
+        let mut proto = to_proto_bridge_config(jwt_bridge());
+        proto.config = None;
+        let err = from_proto_bridge_config(proto).unwrap_err();
+        assert!(matches!(err, Error::InvalidField { ref field, .. } if field == "bridge.jwt"));
+    }
+}
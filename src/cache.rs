@@ -0,0 +1,176 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bounded TTL cache.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Thread-safe cache with per-entry expiry and a maximum size.
+///
+/// When full, expired entries are purged first, then the entry closest to
+/// expiry is evicted.
+pub struct TtlCache<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Create a cache holding at most `capacity` entries for `ttl` each
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+            ttl,
+        }
+    }
+
+    /// Get a live entry
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Insert an entry with the default TTL, replacing any existing one
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_with_ttl(key, value, self.ttl);
+    }
+
+    /// Insert an entry with a specific TTL, replacing any existing one
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&key) {
+            Self::make_room(&mut entries, self.capacity);
+        }
+        entries.insert(key, (Instant::now() + ttl, value));
+    }
+
+    /// Insert an entry unless a live one exists, returning the cached value
+    pub fn get_or_insert(&self, key: K, value: V) -> V {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if let Some((expires_at, existing)) = entries.get(&key) {
+            if *expires_at > now {
+                return existing.clone();
+            }
+            entries.remove(&key);
+        }
+        if self.capacity > 0 {
+            Self::make_room(&mut entries, self.capacity);
+            entries.insert(key, (now + self.ttl, value.clone()));
+        }
+        value
+    }
+
+    /// Insert an entry with a specific TTL unless a live one exists.
+    /// Returns whether it was inserted; a zero-capacity cache records
+    /// nothing and always inserts.
+    pub fn insert_new_with_ttl(&self, key: K, value: V, ttl: Duration) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.get(&key).is_some_and(|(expires_at, _)| *expires_at > now) {
+            return false;
+        }
+        entries.remove(&key);
+        Self::make_room(&mut entries, self.capacity);
+        entries.insert(key, (now + ttl, value));
+        true
+    }
+
+    /// Number of entries, including expired ones not yet purged
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn make_room(entries: &mut HashMap<K, (Instant, V)>, capacity: usize) {
+        if entries.len() < capacity {
+            return;
+        }
+        let now = Instant::now();
+        entries.retain(|_, (expires_at, _)| *expires_at > now);
+        while entries.len() >= capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (expires_at, _))| *expires_at)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(k) => entries.remove(&k),
+                None => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_cache_expiry_and_capacity() {
+        let cache = TtlCache::new(2, Duration::from_secs(60));
+
+        cache.insert("a", 1);
+        cache.insert_with_ttl("b", 2, Duration::from_secs(120));
+        assert_eq!(cache.get(&"a"), Some(1));
+
+        // Full: the entry closest to expiry ("a") is evicted
+        cache.insert("c", 3);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.get(&"b"), Some(2));
+
+        // Expired entries are not returned
+        cache.insert_with_ttl("d", 4, Duration::ZERO);
+        assert_eq!(cache.get(&"d"), None);
+
+        // Existing live entry wins
+        assert_eq!(cache.get_or_insert("b", 20), 2);
+        assert_eq!(cache.get_or_insert("e", 5), 5);
+        assert_eq!(cache.get(&"e"), Some(5));
+
+        // Only the first of several inserts of a live key is taken
+        assert!(cache.insert_new_with_ttl("f", 6, Duration::from_secs(60)));
+        assert!(!cache.insert_new_with_ttl("f", 7, Duration::from_secs(60)));
+        assert_eq!(cache.get(&"f"), Some(6));
+        assert!(cache.insert_new_with_ttl("g", 8, Duration::ZERO));
+        assert!(cache.insert_new_with_ttl("g", 9, Duration::from_secs(60)));
+    }
+}
@@ -16,28 +16,287 @@
 
 //! CAT (Causal Authority Transition) gRPC service.
 
-use crate::credentials::CredentialsManager;
+mod policy;
+mod quota;
+
+pub use policy::{AllowAll, PcaView, PolicyDecision, SubjectDenyList, TransitionPolicy};
+pub use quota::SubjectQuota;
+
+use crate::concurrency::ConcurrencyLimit;
+use crate::credentials::{
+    CredentialsManager, SignedPca, TenantCredentials, DEFAULT_PCA_SIGNING_CONTEXT,
+};
+use crate::deadline;
+use crate::metrics::{Counter, ValueHistogram};
+use crate::redact::redact;
+use crate::error::{Error, Result};
+use crate::revocation::RevocationStore;
 use crate::proto::cat::{
     cat_service_server::{CatService, CatServiceServer},
     TransitionRequest, TransitionResponse,
 };
+use std::ops::RangeInclusive;
+use std::pin::Pin;
 use std::sync::Arc;
-use tonic::{Request, Response, Status};
-use tracing::{warn};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, warn};
+
+/// PCA format versions this build can transition
+pub const SUPPORTED_PCA_VERSIONS: RangeInclusive<u32> = 1..=1;
+
+/// Successors buffered per stream before reading more PCAs from the client
+const STREAM_BUFFER: usize = 16;
+
+/// Upper bounds of the chain depth buckets
+const CHAIN_DEPTH_BUCKETS: &[u64] = &[1, 2, 3, 4, 5, 6, 8, 10, 12, 16, 24, 32, 64];
+
+type TransitionStream =
+    Pin<Box<dyn Stream<Item = std::result::Result<TransitionResponse, Status>> + Send>>;
 
 /// CAT gRPC service implementation
+#[derive(Clone)]
 pub struct CatServiceImpl {
-    credentials: Arc<CredentialsManager>,
+    tenants: Arc<TenantCredentials>,
+    policy: Arc<dyn TransitionPolicy>,
+    revocations: Arc<RevocationStore>,
+    quota: Arc<SubjectQuota>,
+    limits: PcaLimits,
+    concurrency: Arc<ConcurrencyLimit>,
+    /// Domain-separation context successors are signed under
+    signing_context: String,
+    /// Require each successor's sequence to be exactly its input's plus one
+    strict_sequence: bool,
+    metrics: Arc<CatMetrics>,
+}
+
+/// Minting rate and chain depths of CAT transitions
+#[derive(Debug)]
+pub struct CatMetrics {
+    pca_minted: Counter,
+    chain_depth: ValueHistogram,
+}
+
+impl Default for CatMetrics {
+    fn default() -> Self {
+        Self {
+            pca_minted: Counter::new(
+                "trustplane_pca_minted_total",
+                "PCAs minted by CAT transitions",
+            ),
+            chain_depth: ValueHistogram::new(
+                "trustplane_chain_depth",
+                "Sequence of PCAs minted by CAT transitions",
+                CHAIN_DEPTH_BUCKETS,
+            ),
+        }
+    }
+}
+
+impl CatMetrics {
+    /// Record a successor about to be signed
+    fn record(&self, successor: &PcaView) {
+        self.pca_minted.inc();
+        self.chain_depth.observe(successor.sequence);
+    }
+
+    /// Append transition metrics in Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        self.pca_minted.render(out);
+        self.chain_depth.render(out);
+    }
+}
+
+/// Size limits on PCAs entering and leaving a transition
+#[derive(Clone, Debug)]
+pub struct PcaLimits {
+    /// Maximum number of attributes; not enforced until transitions decode
+    /// PCAs, see [`CatServiceImpl::check_transition`]
+    pub max_attributes: usize,
+    /// Maximum serialized size in bytes, enforced on the encoded input
+    pub max_bytes: usize,
+}
+
+impl Default for PcaLimits {
+    fn default() -> Self {
+        Self {
+            max_attributes: 256,
+            max_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl PcaLimits {
+    fn check_encoded(&self, pca: &[u8]) -> Result<()> {
+        if pca.len() > self.max_bytes {
+            return Err(Error::PcaTooLarge(format!(
+                "{} bytes exceeds limit of {}",
+                pca.len(),
+                self.max_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a successor before it is signed.
+    ///
+    /// Size is estimated from the JSON encoding of its fields, which is
+    /// never smaller than the CBOR the PCA is signed as.
+    fn check(&self, pca: &PcaView) -> Result<()> {
+        if pca.attributes.len() > self.max_attributes {
+            return Err(Error::PcaTooLarge(format!(
+                "{} attributes exceeds limit of {}",
+                pca.attributes.len(),
+                self.max_attributes
+            )));
+        }
+        let size = pca.subject.len()
+            + pca.issuer.len()
+            + serde_json::to_vec(&pca.attributes).map_or(0, |a| a.len());
+        if size > self.max_bytes {
+            return Err(Error::PcaTooLarge(format!(
+                "{} bytes exceeds limit of {}",
+                size, self.max_bytes
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl CatServiceImpl {
     pub fn new(credentials: Arc<CredentialsManager>) -> Self {
-        Self { credentials }
+        Self {
+            tenants: Arc::new(TenantCredentials::new(credentials)),
+            policy: Arc::new(AllowAll),
+            revocations: Arc::new(RevocationStore::in_memory()),
+            quota: Arc::new(SubjectQuota::default()),
+            limits: PcaLimits::default(),
+            concurrency: Arc::new(ConcurrencyLimit::unlimited("transition")),
+            signing_context: DEFAULT_PCA_SIGNING_CONTEXT.to_string(),
+            strict_sequence: false,
+            metrics: Arc::new(CatMetrics::default()),
+        }
+    }
+
+    /// Sign successors with the keys of every hosted DID, not only the
+    /// default one
+    pub fn with_tenants(mut self, tenants: Arc<TenantCredentials>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    /// Credentials a successor is signed with: those of the hosted DID
+    /// that issued the input PCA, so each tenant's chains stay under its
+    /// own keys. PCAs from any other issuer are signed for by the default
+    /// tenant.
+    pub fn signer(&self, pca_issuer: &str) -> &Arc<CredentialsManager> {
+        self.tenants
+            .get(pca_issuer)
+            .unwrap_or_else(|| self.tenants.default_tenant())
+    }
+
+    /// Sign successors under another domain-separation context. PCAs
+    /// signed under the previous context stop verifying.
+    pub fn with_signing_context(mut self, context: impl Into<String>) -> Self {
+        self.signing_context = context.into();
+        self
+    }
+
+    /// Refuse successors whose sequence is not exactly the input's plus
+    /// one, detecting replayed or rolled-back chains (off by default, as
+    /// some topologies legitimately skip sequence numbers)
+    pub fn with_strict_sequence(mut self, strict: bool) -> Self {
+        self.strict_sequence = strict;
+        self
+    }
+
+    /// Record into shared metrics instead of the service's own
+    pub fn with_metrics(mut self, metrics: Arc<CatMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Replace the PCA size limits
+    pub fn with_limits(mut self, limits: PcaLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Use a shared subject revocation list
+    pub fn with_revocations(mut self, revocations: Arc<RevocationStore>) -> Self {
+        self.revocations = revocations;
+        self
+    }
+
+    /// Use a shared per-subject quota (unlimited by default)
+    pub fn with_quota(mut self, quota: Arc<SubjectQuota>) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Cap transitions in progress at once, across unary and streaming
+    /// calls (unlimited by default)
+    pub fn with_concurrency(mut self, concurrency: ConcurrencyLimit) -> Self {
+        self.concurrency = Arc::new(concurrency);
+        self
+    }
+
+    /// Replace the transition policy (allow-all by default)
+    pub fn with_policy(mut self, policy: Arc<dyn TransitionPolicy>) -> Self {
+        self.policy = policy;
+        self
     }
 
     pub fn into_server(self) -> CatServiceServer<Self> {
         CatServiceServer::new(self)
     }
+
+    /// Convert a shared instance to tonic server
+    pub fn into_shared_server(self: Arc<Self>) -> CatServiceServer<Self> {
+        CatServiceServer::from_arc(self)
+    }
+
+    /// Run revocation, policy, sequence, size and quota checks on a proposed
+    /// successor.
+    ///
+    /// Only transitions that pass every other check count against the
+    /// input subject's quota, and in the minting metrics.
+    ///
+    /// Not reached from the RPCs yet: [`transition_pca`](Self::transition_pca)
+    /// stops before decoding the input PCA, so the policy, sequence and quota
+    /// checks only run once PCA decoding lands.
+    pub fn check_transition(&self, input: &PcaView, successor: &mut PcaView) -> Result<()> {
+        self.revocations.check(&input.subject)?;
+        match self.policy.evaluate(input, successor) {
+            PolicyDecision::Allow => {
+                self.revocations.check(&successor.subject)?;
+                self.check_sequence(input, successor)?;
+                self.limits.check(successor)?;
+                self.quota.check(&input.subject)?;
+                self.metrics.record(successor);
+                Ok(())
+            }
+            PolicyDecision::Deny(reason) => {
+                debug!(subject = %input.subject, reason = %reason, "CAT transition denied by policy");
+                Err(Error::Denied(reason))
+            }
+        }
+    }
+
+    /// Under strict sequencing, check the successor comes right after its
+    /// input: neither out of order, duplicate nor skipping ahead
+    fn check_sequence(&self, input: &PcaView, successor: &PcaView) -> Result<()> {
+        let expected = input.sequence.checked_add(1);
+        if !self.strict_sequence || expected == Some(successor.sequence) {
+            return Ok(());
+        }
+        Err(Error::SequenceRollback(format!(
+            "successor sequence {} does not follow input sequence {}",
+            successor.sequence, input.sequence
+        )))
+    }
 }
 
 #[tonic::async_trait]
@@ -46,30 +305,286 @@ impl CatService for CatServiceImpl {
         &self,
         request: Request<TransitionRequest>,
     ) -> std::result::Result<Response<TransitionResponse>, Status> {
+        let timeout = deadline::grpc_timeout(request.metadata());
         let req = request.into_inner();
-        
-        if req.pca.is_empty() {
-            return Ok(Response::new(TransitionResponse {
-                pca: vec![],
-                error: "PCA is required".to_string(),
-            }));
+
+        let transition = self.transition_pca(&req.pca, &req.expected_audience);
+        match deadline::within(timeout, transition).await? {
+            Ok(signed) => Ok(Response::new(TransitionResponse {
+                pca: signed.pca,
+                error: String::new(),
+                error_code: String::new(),
+                signing_kid: signed.signing_kid,
+            })),
+            // A stubbed feature is UNIMPLEMENTED, with its feature id in the details
+            Err(e @ Error::NotImplemented(_)) => Err(e.into()),
+            Err(e) => Ok(error_response(e)),
         }
+    }
+
+    type TransitionStreamStream = TransitionStream;
+
+    async fn transition_stream(
+        &self,
+        request: Request<Streaming<TransitionRequest>>,
+    ) -> std::result::Result<Response<Self::TransitionStreamStream>, Status> {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(self.clone().serve_stream(request.into_inner(), tx));
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+impl CatServiceImpl {
+    /// Transition a PCA to its successor. A non-empty `expected_audience`
+    /// is meant to refuse PCAs bound to any other audience, once decoding
+    /// lands (step 2 below).
+    pub async fn transition_pca(&self, pca: &[u8], expected_audience: &str) -> Result<SignedPca> {
+        if pca.is_empty() {
+            return Err(Error::invalid_field("pca", "PCA is required"));
+        }
+        self.limits.check_encoded(pca)?;
+        let _permit = self.concurrency.acquire().await?;
         
         // TODO: Implement actual PCA transition logic using pic-protocol
-        // 1. Decode incoming PCA (CBOR)
-        // 2. Validate PCA signature and chain
-        // 3. Create new PCA with incremented sequence
-        // 4. Sign with CAT key
-        // 5. Encode as CBOR
+        // 1. Decode incoming PCA (CBOR), failing with Error::InvalidCbor for
+        //    malformed CBOR and Error::InvalidPcaStructure for CBOR that is
+        //    not a PCA; debug-log subject, sequence, issuer and attribute
+        //    keys (values only with --log-pca-attributes-values)
+        // 2. Validate PCA signature and chain, then
+        //    input.check_audience(expected_audience)
+        // 3. Create new PCA with incremented sequence, keeping the audience
+        // 4. Apply self.check_transition() to input and successor
+        // 5. Sign with the CAT key of the tenant that issued the input:
+        //    self.signer(<input iss>).current().sign_pca(&self.signing_context, ..)
+        // 6. Encode as CBOR, with the CAT key's kid and the signing context
         
-        let _credentials = self.credentials.current();
+        let _ = expected_audience;
         
-        warn!("CAT transition not fully implemented yet");
+        warn!(
+            pca = %redact(pca),
+            signing_context = %self.signing_context,
+            "CAT transition not fully implemented yet"
+        );
         
-        // Placeholder response
-        Ok(Response::new(TransitionResponse {
-            pca: vec![],
-            error: "Not implemented".to_string(),
-        }))
+        Err(Error::NotImplemented("cat_transition".to_string()))
     }
-}
\ No newline at end of file
+}
+
+impl CatServiceImpl {
+    /// Answer each inbound PCA, in order, until either side closes.
+    ///
+    /// The bounded channel applies backpressure: no more PCAs are read
+    /// while the client is not consuming successors. Transition failures
+    /// are answered in-band; only a broken inbound stream ends it.
+    async fn serve_stream<S>(
+        self,
+        mut inbound: S,
+        tx: mpsc::Sender<std::result::Result<TransitionResponse, Status>>,
+    ) where
+        S: Stream<Item = std::result::Result<TransitionRequest, Status>> + Unpin,
+    {
+        while let Some(request) = inbound.next().await {
+            let response = match request {
+                Ok(req) => Ok(match self.transition_pca(&req.pca, &req.expected_audience).await {
+                    Ok(signed) => TransitionResponse {
+                        pca: signed.pca,
+                        error: String::new(),
+                        error_code: String::new(),
+                        signing_kid: signed.signing_kid,
+                    },
+                    Err(e) => error_response(e).into_inner(),
+                }),
+                Err(status) => {
+                    debug!(error = %status, "CAT transition stream closed by client error");
+                    let _ = tx.send(Err(status)).await;
+                    return;
+                }
+            };
+            if tx.send(response).await.is_err() {
+                // Client went away
+                return;
+            }
+        }
+    }
+}
+
+/// In-band error response
+fn error_response(e: Error) -> Response<TransitionResponse> {
+    Response::new(TransitionResponse {
+        pca: vec![],
+        error: e.to_string(),
+        error_code: e.code().to_string(),
+        signing_kid: String::new(),
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::testing::{test_credentials_manager, TEST_DID};
+    use serde_json::{Map, Value};
+    use std::time::Duration;
+
+    fn pca(attributes: usize) -> PcaView {
+        PcaView {
+            subject: "alice".to_string(),
+            issuer: "https://idp.example.com".to_string(),
+            sequence: 1,
+            attributes: (0..attributes)
+                .map(|i| (format!("a{}", i), Value::from("x")))
+                .collect::<Map<_, _>>(),
+            audience: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transition_pca_limits() {
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID)).with_limits(PcaLimits {
+            max_attributes: 4,
+            max_bytes: 128,
+        });
+
+        let input = pca(3);
+        assert!(service.check_transition(&input, &mut pca(4)).is_ok());
+
+        let err = service.check_transition(&input, &mut pca(5)).unwrap_err();
+        assert_eq!(err.code(), "pca_too_large");
+
+        let mut oversized = pca(1);
+        oversized.attributes.insert("bio".into(), Value::from("x".repeat(128)));
+        let err = service.check_transition(&input, &mut oversized).unwrap_err();
+        assert_eq!(err.code(), "pca_too_large");
+
+        let err = service.transition_pca(&[0u8; 129], "").await.unwrap_err();
+        assert_eq!(err.code(), "pca_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_transition_concurrency() {
+        let limit = ConcurrencyLimit::new("transition", 1).with_wait(Duration::from_millis(10));
+        let service =
+            CatServiceImpl::new(test_credentials_manager(TEST_DID)).with_concurrency(limit);
+
+        let held = service.concurrency.acquire().await.unwrap();
+        let err = service.transition_pca(&[1], "").await.unwrap_err();
+        assert_eq!(err.code(), "overloaded");
+
+        drop(held);
+        let err = service.transition_pca(&[1], "").await.unwrap_err();
+        assert_eq!(err.code(), "not_implemented");
+    }
+
+    #[test]
+    fn test_transition_signer() {
+        let hosted = "did:web:tenant.example.com";
+        let mut tenants = TenantCredentials::new(test_credentials_manager(TEST_DID));
+        tenants.insert(test_credentials_manager(hosted)).unwrap();
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID))
+            .with_tenants(Arc::new(tenants));
+
+        // A hosted tenant's chains are signed with its own CAT key
+        assert_eq!(service.signer(hosted).current().did, hosted);
+        assert_eq!(service.signer(TEST_DID).current().did, TEST_DID);
+        assert_eq!(service.signer("did:web:other.example.com").current().did, TEST_DID);
+    }
+
+    #[test]
+    fn test_transition_quota() {
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID))
+            .with_limits(PcaLimits {
+                max_attributes: 4,
+                max_bytes: 1024,
+            })
+            .with_quota(Arc::new(SubjectQuota::new(1)));
+
+        // Rejected transitions do not use up the subject's quota
+        let input = pca(1);
+        assert!(service.check_transition(&input, &mut pca(5)).is_err());
+        assert!(service.check_transition(&input, &mut pca(1)).is_ok());
+        let err = service.check_transition(&input, &mut pca(1)).unwrap_err();
+        assert_eq!(err.code(), "quota_exceeded");
+    }
+
+    #[test]
+    fn test_transition_metrics() {
+        let metrics = Arc::new(CatMetrics::default());
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID))
+            .with_limits(PcaLimits {
+                max_attributes: 4,
+                max_bytes: 1024,
+            })
+            .with_metrics(metrics.clone());
+
+        // Refused transitions are not counted
+        let input = pca(1);
+        assert!(service.check_transition(&input, &mut pca(5)).is_err());
+        for sequence in [2, 3, 12] {
+            let mut successor = PcaView { sequence, ..pca(1) };
+            service.check_transition(&input, &mut successor).unwrap();
+        }
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains("trustplane_pca_minted_total 3\n"));
+        assert!(out.contains("trustplane_chain_depth_bucket{le=\"2\"} 1\n"));
+        assert!(out.contains("trustplane_chain_depth_bucket{le=\"12\"} 3\n"));
+        assert!(out.contains("trustplane_chain_depth_sum 17\n"));
+    }
+
+    #[test]
+    fn test_transition_strict_sequence() {
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID));
+        let input = pca(1);
+        let successor = |sequence| PcaView {
+            sequence,
+            ..pca(1)
+        };
+
+        // Lenient by default: gaps and even repeats pass
+        assert!(service.check_transition(&input, &mut successor(1)).is_ok());
+        assert!(service.check_transition(&input, &mut successor(5)).is_ok());
+
+        let service = service.with_strict_sequence(true);
+        assert!(service.check_transition(&input, &mut successor(2)).is_ok());
+        for sequence in [0, 1, 3] {
+            let err = service.check_transition(&input, &mut successor(sequence)).unwrap_err();
+            assert_eq!(err.code(), "sequence_rollback", "{}", sequence);
+            assert_eq!(err.status_code(), axum::http::StatusCode::FORBIDDEN);
+        }
+
+        // No successor can follow the last representable sequence
+        let last = PcaView {
+            sequence: u64::MAX,
+            ..pca(1)
+        };
+        assert!(service.check_transition(&last, &mut successor(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transition_stream_survives_errors() {
+        let service = CatServiceImpl::new(test_credentials_manager(TEST_DID));
+        let request = |pca: Vec<u8>| TransitionRequest {
+            pca,
+            expected_audience: String::new(),
+        };
+        let inbound = tokio_stream::iter(vec![
+            Ok(request(vec![])),
+            Ok(request(vec![0; PcaLimits::default().max_bytes + 1])),
+            Ok(request(vec![0; 8])),
+            Err(Status::cancelled("client gone")),
+            Ok(request(vec![0; 8])),
+        ]);
+
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(service.serve_stream(inbound, tx));
+        let responses: Vec<_> = ReceiverStream::new(rx).collect().await;
+
+        // One in-band answer per PCA, then the client error ends the stream
+        let codes: Vec<_> = responses[..3]
+            .iter()
+            .map(|r| r.as_ref().unwrap().error_code.as_str())
+            .collect();
+        assert_eq!(codes, ["invalid_argument", "pca_too_large", "not_implemented"]);
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[3].as_ref().unwrap_err().code(), tonic::Code::Cancelled);
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! CAT transition policies.
+//!
+//! A [`TransitionPolicy`] runs after the input PCA is decoded and verified,
+//! and before the successor is signed. It may approve, deny or rewrite the
+//! proposed successor.
+//!
+//! Transitions do not decode PCAs yet, so no policy is consulted on a real
+//! request until they do.
+
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Policy-relevant fields of a PCA
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PcaView {
+    /// Subject the authority was granted to
+    pub subject: String,
+    /// Issuer of the authority chain
+    pub issuer: String,
+    /// Position in the causal chain (0 for PCA₀)
+    pub sequence: u64,
+    /// Attributes carried by the PCA
+    pub attributes: Map<String, Value>,
+    /// Resource audience the chain is bound to, if any
+    pub audience: Option<String>,
+}
+
+impl PcaView {
+    /// Check the PCA is bound to the audience a caller expects (an empty
+    /// expectation accepts any PCA)
+    pub fn check_audience(&self, expected: &str) -> Result<()> {
+        if expected.is_empty() || self.audience.as_deref() == Some(expected) {
+            return Ok(());
+        }
+        Err(Error::AudienceMismatch(format!(
+            "PCA is bound to {}, expected {}",
+            self.audience.as_deref().unwrap_or("no audience"),
+            expected
+        )))
+    }
+}
+
+/// Outcome of a transition policy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// Mint the (possibly modified) successor
+    Allow,
+    /// Refuse the transition
+    Deny(String),
+}
+
+/// Business rules applied to every CAT transition
+pub trait TransitionPolicy: Send + Sync {
+    /// Decide on a transition from `input` to `successor`.
+    ///
+    /// Changes made to `successor` are kept when the decision is
+    /// [`PolicyDecision::Allow`].
+    fn evaluate(&self, input: &PcaView, successor: &mut PcaView) -> PolicyDecision;
+}
+
+/// Policy that approves every transition unchanged
+#[derive(Clone, Debug, Default)]
+pub struct AllowAll;
+
+impl TransitionPolicy for AllowAll {
+    fn evaluate(&self, _input: &PcaView, _successor: &mut PcaView) -> PolicyDecision {
+        PolicyDecision::Allow
+    }
+}
+
+/// Policy that denies transitions for listed subjects
+#[derive(Clone, Debug, Default)]
+pub struct SubjectDenyList {
+    subjects: HashSet<String>,
+}
+
+impl SubjectDenyList {
+    /// Create a deny-list from subject identifiers
+    pub fn new<I, S>(subjects: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            subjects: subjects.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TransitionPolicy for SubjectDenyList {
+    fn evaluate(&self, input: &PcaView, successor: &mut PcaView) -> PolicyDecision {
+        for subject in [&input.subject, &successor.subject] {
+            if self.subjects.contains(subject) {
+                return PolicyDecision::Deny(format!("subject {} is denied", subject));
+            }
+        }
+        PolicyDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pca(subject: &str, sequence: u64) -> PcaView {
+        PcaView {
+            subject: subject.to_string(),
+            issuer: "https://idp.example.com".to_string(),
+            sequence,
+            attributes: Map::new(),
+            audience: None,
+        }
+    }
+
+    #[test]
+    fn test_audience_binding() {
+        let mut bound = pca("alice", 1);
+        assert!(bound.check_audience("").is_ok());
+        assert_eq!(bound.check_audience("billing").unwrap_err().code(), "audience_mismatch");
+
+        bound.audience = Some("orders".into());
+        assert!(bound.check_audience("orders").is_ok());
+        assert!(bound.check_audience("").is_ok());
+        let err = bound.check_audience("billing").unwrap_err();
+        assert_eq!(err.status_code(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_subject_deny_list() {
+        let policy = SubjectDenyList::new(["mallory"]);
+
+        let mut successor = pca("alice", 1);
+        assert_eq!(
+            policy.evaluate(&pca("alice", 0), &mut successor),
+            PolicyDecision::Allow
+        );
+
+        let mut successor = pca("mallory", 1);
+        assert!(matches!(
+            policy.evaluate(&pca("mallory", 0), &mut successor),
+            PolicyDecision::Deny(_)
+        ));
+
+        let mut successor = pca("alice", 1);
+        assert_eq!(
+            AllowAll.evaluate(&pca("mallory", 0), &mut successor),
+            PolicyDecision::Allow
+        );
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Per-subject transition quota.
+//!
+//! Counts transitions per PCA subject in fixed one-minute windows, so a
+//! subject is limited however many connections or clients it uses.
+//!
+//! Not enforced yet: transitions stop before the input PCA is decoded, so
+//! no subject is ever counted and overrides have no effect.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Windows tracked before stale ones are purged
+const MAX_TRACKED_SUBJECTS: usize = 100_000;
+
+/// Transitions-per-minute limit, keyed on the PCA subject
+#[derive(Debug, Default)]
+pub struct SubjectQuota {
+    /// Limit for subjects without an override (0: unlimited)
+    default_per_minute: u32,
+    overrides: RwLock<HashMap<String, u32>>,
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl SubjectQuota {
+    /// Create a quota with a default limit (0: unlimited)
+    pub fn new(default_per_minute: u32) -> Self {
+        Self {
+            default_per_minute,
+            ..Default::default()
+        }
+    }
+
+    /// Limit applied to a subject (0: unlimited)
+    pub fn limit(&self, subject: &str) -> u32 {
+        self.overrides
+            .read()
+            .unwrap()
+            .get(subject)
+            .copied()
+            .unwrap_or(self.default_per_minute)
+    }
+
+    /// Override the limit for one subject (0: unlimited)
+    pub fn set_override(&self, subject: &str, per_minute: u32) -> Result<()> {
+        if subject.is_empty() {
+            return Err(Error::Invalid("subject is required".to_string()));
+        }
+        self.overrides.write().unwrap().insert(subject.to_string(), per_minute);
+        Ok(())
+    }
+
+    /// Drop a subject's override. Returns false if it had none.
+    pub fn clear_override(&self, subject: &str) -> bool {
+        self.overrides.write().unwrap().remove(subject).is_some()
+    }
+
+    /// Count one transition for `subject`, failing with
+    /// [`Error::QuotaExceeded`] once its limit for the window is reached
+    pub fn check(&self, subject: &str) -> Result<()> {
+        let limit = self.limit(subject);
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= MAX_TRACKED_SUBJECTS && !windows.contains_key(subject) {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+        }
+        let (start, count) = windows.entry(subject.to_string()).or_insert((now, 0));
+        if now.duration_since(*start) >= WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= limit {
+            return Err(Error::QuotaExceeded(format!(
+                "subject {} exceeded {} transitions per minute",
+                subject, limit
+            )));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_quota() {
+        let quota = SubjectQuota::new(2);
+        assert!(quota.check("alice").is_ok());
+        assert!(quota.check("alice").is_ok());
+        let err = quota.check("alice").unwrap_err();
+        assert_eq!(err.code(), "quota_exceeded");
+
+        // Counted per subject
+        assert!(quota.check("bob").is_ok());
+
+        // Overrides raise, lower or lift the limit for one subject
+        quota.set_override("alice", 3).unwrap();
+        assert!(quota.check("alice").is_ok());
+        assert!(quota.check("alice").is_err());
+        quota.set_override("alice", 0).unwrap();
+        assert!(quota.check("alice").is_ok());
+        assert!(quota.clear_override("alice"));
+        assert!(!quota.clear_override("alice"));
+        assert!(quota.check("alice").is_err());
+
+        // Unlimited by default
+        let quota = SubjectQuota::new(0);
+        assert!((0..1000).all(|_| quota.check("alice").is_ok()));
+    }
+}
@@ -0,0 +1,110 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Panic capture.
+//!
+//! A panic in a handler or service fails only the request that caused it:
+//! it is logged with a fresh request ID and answered with a `500` (HTTP)
+//! or `INTERNAL` (gRPC) carrying the same ID, instead of dropping the
+//! connection.
+
+use axum::http::{header, HeaderValue, Response, StatusCode};
+use std::any::Any;
+use tower_http::catch_panic::CatchPanicLayer;
+use tracing::error;
+
+/// Header carrying the request ID of a panicked request
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+type PanicPayload = Box<dyn Any + Send + 'static>;
+type HttpPanicHandler = fn(PanicPayload) -> Response<String>;
+type GrpcPanicHandler = fn(PanicPayload) -> Response<tonic::body::BoxBody>;
+
+/// Layer turning HTTP handler panics into a JSON `500`
+pub(crate) fn http_layer() -> CatchPanicLayer<HttpPanicHandler> {
+    CatchPanicLayer::custom(http_response as HttpPanicHandler)
+}
+
+/// Layer turning gRPC service panics into an `INTERNAL` status
+pub(crate) fn grpc_layer() -> CatchPanicLayer<GrpcPanicHandler> {
+    CatchPanicLayer::custom(grpc_response as GrpcPanicHandler)
+}
+
+fn http_response(payload: PanicPayload) -> Response<String> {
+    let request_id = log_panic(&payload);
+    let body = serde_json::json!({
+        "error": "internal error",
+        "code": "internal",
+        "request_id": request_id,
+    });
+    let mut response = Response::new(body.to_string());
+    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn grpc_response(payload: PanicPayload) -> Response<tonic::body::BoxBody> {
+    let request_id = log_panic(&payload);
+    let mut response =
+        tonic::Status::internal(format!("internal error, request id {}", request_id)).into_http();
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Log the panic under a new request ID, returned for the response
+fn log_panic(payload: &PanicPayload) -> String {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload");
+    error!(request_id = %request_id, panic = %message, "Request handler panicked");
+    request_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::Service;
+
+    #[tokio::test]
+    async fn test_panics_become_errors() {
+        async fn boom() -> &'static str {
+            panic!("decode failed")
+        }
+        let mut router = Router::new()
+            .route("/boom", get(boom))
+            .layer(http_layer());
+        let request = Request::get("/boom").body(Body::empty()).unwrap();
+        let response = router.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+
+        let response = grpc_response(Box::new("decode failed"));
+        assert_eq!(response.headers()["grpc-status"], "13");
+        let request_id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        let message = response.headers()["grpc-message"].to_str().unwrap();
+        assert!(message.ends_with(request_id));
+    }
+}
@@ -33,6 +33,18 @@ pub struct Cli {
     #[arg(long, env = "PERMGUARD_LOG_LEVEL", default_value = "INFO")]
     pub log_level: String,
 
+    /// Log full PCA attribute values at debug level (local debugging only, may leak secrets)
+    #[arg(long, env = "PERMGUARD_LOG_PCA_ATTRIBUTES_VALUES", default_value = "false")]
+    pub log_pca_attributes_values: bool,
+
+    /// Print the effective configuration as JSON and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Skip the signing self-test at startup (dev only)
+    #[arg(long, env = "PERMGUARD_SKIP_STARTUP_SELFTEST", default_value = "false")]
+    pub skip_startup_selftest: bool,
+
     /// Directory for application data
     #[arg(long, env = "PERMGUARD_APPDATA", default_value = "./")]
     pub appdata: String,
@@ -42,19 +54,38 @@ pub struct Cli {
     #[arg(long, env = "PERMGUARD_DID", default_value = "did:web:localhost")]
     pub did: String,
 
+    /// Additional did:web DIDs to host, each with its own keys (comma-separated).
+    /// Discovery requests select a DID by Host header and path.
+    #[arg(long, env = "PERMGUARD_TENANT_DIDS", value_delimiter = ',')]
+    pub tenant_dids: Vec<String>,
+
     /// Organization name
     #[arg(long, env = "PERMGUARD_ORGANIZATION", default_value = "Permguard")]
     pub organization: String,
 
+    /// DID document public key format: jwk, multibase, both
+    #[arg(long, env = "PERMGUARD_DID_KEY_FORMAT", default_value = "jwk")]
+    pub did_key_format: String,
+
+    /// Fixed RFC 3339 issuance date for generated credentials and kid dates
+    /// (in-memory provider; default: now), for reproducible artifacts
+    #[arg(long, env = "PERMGUARD_CREDENTIAL_ISSUANCE_DATE")]
+    pub credential_issuance_date: Option<String>,
+
+    /// Extra credentialSubject fields for the generated self-credential, as a
+    /// JSON object (e.g. {"jurisdiction":"IT","website":"https://acme.example"})
+    #[arg(long, env = "PERMGUARD_CREDENTIAL_SUBJECT")]
+    pub credential_subject: Option<String>,
+
     /// Credential provider: inmemory, file
     #[arg(long, env = "PERMGUARD_CREDENTIAL_PROVIDER", default_value = "inmemory")]
     pub credential_provider: String,
 
-    /// Path to issuer private key (file provider)
+    /// Path to issuer private key, JWK or PKCS#8 PEM (file provider)
     #[arg(long, env = "PERMGUARD_ISSUER_KEY_PATH")]
     pub issuer_key_path: Option<String>,
 
-    /// Path to CAT private key (file provider)
+    /// Path to CAT private key, JWK or PKCS#8 PEM (file provider)
     #[arg(long, env = "PERMGUARD_CAT_KEY_PATH")]
     pub cat_key_path: Option<String>,
 
@@ -66,6 +97,18 @@ pub struct Cli {
     #[arg(long, env = "PERMGUARD_CREDENTIAL_PATH")]
     pub credential_path: Option<String>,
 
+    /// Allow the issuer and CAT keys to be the same key (file provider, warns)
+    #[arg(long, env = "PERMGUARD_ALLOW_SHARED_KEYS", default_value = "false")]
+    pub allow_shared_keys: bool,
+
+    /// Warn when a key's kid date suffix (-YYYYMM) is older than this many months (file provider)
+    #[arg(long, env = "PERMGUARD_WARN_KEY_AGE_MONTHS")]
+    pub warn_key_age_months: Option<u32>,
+
+    /// Fail instead of warning on keys older than --warn-key-age-months (file provider)
+    #[arg(long, env = "PERMGUARD_FAIL_ON_STALE_KEYS", default_value = "false")]
+    pub fail_on_stale_keys: bool,
+
     // === Server ===
     /// Bind address
     #[arg(long, env = "PERMGUARD_BIND_ADDRESS", default_value = "0.0.0.0")]
@@ -79,16 +122,160 @@ pub struct Cli {
     #[arg(long, env = "PERMGUARD_GRPC_PORT", default_value = "9001")]
     pub grpc_port: u16,
 
+    /// Seconds between HTTP/2 pings on idle gRPC connections, keeping them
+    /// open through NATs and load balancers that drop idle connections
+    /// (0 = no pings)
+    #[arg(long, env = "PERMGUARD_GRPC_HTTP2_KEEPALIVE_INTERVAL", default_value = "30")]
+    pub grpc_http2_keepalive_interval: u64,
+
+    /// Seconds to wait for a keepalive ping to be acknowledged before the
+    /// gRPC connection is closed as dead
+    #[arg(long, env = "PERMGUARD_GRPC_HTTP2_KEEPALIVE_TIMEOUT", default_value = "20")]
+    pub grpc_http2_keepalive_timeout: u64,
+
+    /// Seconds after which a gRPC connection is closed, so clients reconnect
+    /// and rebalance across instances (0 = no limit)
+    #[arg(long, env = "PERMGUARD_GRPC_MAX_CONNECTION_AGE", default_value = "0")]
+    pub grpc_max_connection_age: u64,
+
+    /// URL this instance is publicly reachable at (e.g. https://trust.acme.com),
+    /// checked against the did:web host at startup
+    #[arg(long, env = "PERMGUARD_PUBLIC_BASE_URL")]
+    pub public_base_url: Option<String>,
+
+    /// Fail at startup, instead of warning, when the did:web host does not
+    /// match --public-base-url
+    #[arg(long, env = "PERMGUARD_STRICT_DID_HOST", default_value = "false")]
+    pub strict_did_host: bool,
+
+    /// Compress HTTP responses (gzip/br) per Accept-Encoding
+    #[arg(long, env = "PERMGUARD_HTTP_COMPRESSION", default_value = "true")]
+    pub http_compression: bool,
+
+    /// Serve the DID document at /.well-known/did.json
+    #[arg(long, env = "PERMGUARD_SERVE_DID_DOCUMENT", default_value = "true")]
+    pub serve_did_document: bool,
+
+    /// Serve Trust Plane metadata at /.well-known/trustplane.json
+    #[arg(long, env = "PERMGUARD_SERVE_TRUSTPLANE_METADATA", default_value = "true")]
+    pub serve_trustplane_metadata: bool,
+
     /// Enable metrics endpoint
     #[arg(long, env = "PERMGUARD_METRICS_ENABLED", default_value = "true")]
     pub metrics_enabled: bool,
 
+    /// Serve the OpenAPI document of the HTTP gateway at /openapi.json
+    #[arg(long, env = "PERMGUARD_SERVE_OPENAPI", default_value = "true")]
+    pub serve_openapi: bool,
+
     /// Enable Bridge Admin service (disabled by default for security)
     #[arg(long, env = "PERMGUARD_BRIDGE_ADMIN_ENABLED", default_value = "false")]
     pub bridge_admin_enabled: bool,
 
+    /// Enable Admin endpoints, e.g. subject revocation (disabled by default for security)
+    #[arg(long, env = "PERMGUARD_ADMIN_ENABLED", default_value = "false")]
+    pub admin_enabled: bool,
+
+    // === Runtime ===
+    /// Tokio runtime flavor: multi-thread, single-thread
+    #[arg(long, env = "PERMGUARD_RUNTIME", default_value = "multi-thread")]
+    pub runtime: String,
+
+    /// Tokio worker threads for the multi-thread runtime (0 = one per CPU)
+    #[arg(long, env = "PERMGUARD_WORKER_THREADS", default_value = "0")]
+    pub worker_threads: usize,
+
     // === Shutdown ===
     /// Shutdown grace period in seconds
     #[arg(long, env = "PERMGUARD_SHUTDOWN_GRACE_PERIOD", default_value = "30")]
     pub shutdown_grace_period: u64,
+
+    /// Seconds to keep serving after gRPC health flips to NOT_SERVING
+    #[arg(long, env = "PERMGUARD_DRAIN_DELAY", default_value = "5")]
+    pub drain_delay: u64,
+
+    // === CAT ===
+    /// Subjects whose PCAs must never be transitioned (comma-separated).
+    /// Not enforced yet: CAT transitions do not decode PCAs
+    #[arg(long, env = "PERMGUARD_CAT_DENY_SUBJECTS", value_delimiter = ',')]
+    pub cat_deny_subjects: Vec<String>,
+
+    /// Transitions per minute allowed for each PCA subject (0: unlimited).
+    /// Not enforced yet: CAT transitions do not decode PCAs
+    #[arg(long, env = "PERMGUARD_CAT_SUBJECT_QUOTA", default_value = "0")]
+    pub cat_subject_quota: u32,
+
+    /// Maximum CAT transitions in progress at once (0: unlimited)
+    #[arg(long, env = "PERMGUARD_MAX_CONCURRENT_TRANSITIONS", default_value = "0")]
+    pub max_concurrent_transitions: usize,
+
+    /// Maximum number of attributes a transitioned PCA may carry.
+    /// Not enforced yet: CAT transitions do not decode PCAs
+    #[arg(long, env = "PERMGUARD_MAX_PCA_ATTRIBUTES", default_value = "256")]
+    pub max_pca_attributes: usize,
+
+    /// Maximum serialized PCA size in bytes
+    #[arg(long, env = "PERMGUARD_MAX_PCA_BYTES", default_value = "65536")]
+    pub max_pca_bytes: usize,
+
+    /// Require each transitioned PCA's sequence to be exactly its
+    /// predecessor's plus one, refusing gaps, repeats and rollbacks.
+    /// Not enforced yet: CAT transitions do not decode PCAs
+    #[arg(long, env = "PERMGUARD_STRICT_SEQUENCE", default_value = "false")]
+    pub strict_sequence: bool,
+
+    // === Bridge ===
+    /// Seconds a bridge exchange idempotency key is remembered
+    #[arg(long, env = "PERMGUARD_IDEMPOTENCY_TTL", default_value = "300")]
+    pub idempotency_ttl: u64,
+
+    /// Maximum number of remembered bridge exchange idempotency keys
+    #[arg(long, env = "PERMGUARD_IDEMPOTENCY_CACHE_SIZE", default_value = "10000")]
+    pub idempotency_cache_size: usize,
+
+    /// Maximum inbound credential size in bytes
+    #[arg(long, env = "PERMGUARD_MAX_CREDENTIAL_BYTES", default_value = "65536")]
+    pub max_credential_bytes: usize,
+
+    /// Maximum number of claims in an inbound token
+    #[arg(long, env = "PERMGUARD_MAX_CREDENTIAL_CLAIMS", default_value = "256")]
+    pub max_credential_claims: usize,
+
+    /// Maximum length of a mapped claim value in bytes
+    #[arg(long, env = "PERMGUARD_MAX_CLAIM_VALUE_BYTES", default_value = "8192")]
+    pub max_claim_value_bytes: usize,
+
+    /// Include a claim mapping trace in HTTP exchange responses (debugging only)
+    #[arg(long, env = "PERMGUARD_BRIDGE_MAPPING_TRACE", default_value = "false")]
+    pub bridge_mapping_trace: bool,
+
+    /// Bridge used by exchange requests that name none
+    #[arg(long, env = "PERMGUARD_DEFAULT_BRIDGE_ID")]
+    pub default_bridge_id: Option<String>,
+
+    /// Reuse the PCA₀ minted for an identical (bridge, credential) exchange
+    #[arg(long, env = "PERMGUARD_CACHE_EXCHANGES", default_value = "false")]
+    pub cache_exchanges: bool,
+
+    /// Maximum seconds a cached PCA₀ is reused (never past the token's exp)
+    #[arg(long, env = "PERMGUARD_EXCHANGE_CACHE_TTL", default_value = "300")]
+    pub exchange_cache_ttl: u64,
+
+    /// Maximum number of cached PCA₀s
+    #[arg(long, env = "PERMGUARD_EXCHANGE_CACHE_SIZE", default_value = "10000")]
+    pub exchange_cache_size: usize,
+
+    /// Maximum bridge exchanges in progress at once (0: unlimited)
+    #[arg(long, env = "PERMGUARD_MAX_CONCURRENT_EXCHANGES", default_value = "0")]
+    pub max_concurrent_exchanges: usize,
+
+    /// Seconds a removed bridge is kept, refusing exchanges, before it is
+    /// deleted (0: delete at once)
+    #[arg(long, env = "PERMGUARD_BRIDGE_DELETE_GRACE", default_value = "0")]
+    pub bridge_delete_grace: u64,
+
+    /// Directory of bridge configurations, one `.json` file per bridge,
+    /// loaded at startup and watched for changes
+    #[arg(long, env = "PERMGUARD_BRIDGES_DIR")]
+    pub bridges_dir: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Client for a peer Trust Plane.
+//!
+//! Forwards CAT transitions over gRPC, e.g. when one plane hands a PCA on
+//! to the plane of another trust domain. `https://` endpoints need the
+//! `tls` feature.
+
+use crate::credentials::SignedPca;
+use crate::error::{Error, Result};
+use crate::proto::cat::cat_service_client::CatServiceClient;
+use crate::proto::cat::TransitionRequest;
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+
+/// Default time allowed to establish a connection
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS settings for a peer connection
+#[cfg(feature = "tls")]
+#[derive(Clone, Debug, Default)]
+pub struct ClientTls {
+    /// PEM CA certificate the peer's certificate must chain to
+    pub ca_certificate_pem: Option<Vec<u8>>,
+    /// Name expected in the peer's certificate (default: the endpoint host)
+    pub domain_name: Option<String>,
+    /// PEM certificate and private key, for mutual TLS
+    pub identity_pem: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Connection to a peer Trust Plane's gRPC API
+#[derive(Clone, Debug)]
+pub struct TrustPlaneClient {
+    cat: CatServiceClient<Channel>,
+}
+
+impl TrustPlaneClient {
+    /// Connect to a peer at `endpoint` (e.g. `http://trustplane.internal:9443`)
+    pub async fn connect(endpoint: impl Into<String>) -> Result<Self> {
+        Self::connect_endpoint(endpoint_for(endpoint.into())?).await
+    }
+
+    /// Connect to a peer over TLS
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls(endpoint: impl Into<String>, tls: ClientTls) -> Result<Self> {
+        use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+
+        let mut config = ClientTlsConfig::new();
+        if let Some(ca) = tls.ca_certificate_pem {
+            config = config.ca_certificate(Certificate::from_pem(ca));
+        }
+        if let Some(domain) = tls.domain_name {
+            config = config.domain_name(domain);
+        }
+        if let Some((cert, key)) = tls.identity_pem {
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+        Self::connect_endpoint(endpoint_for(endpoint.into())?.tls_config(config)?).await
+    }
+
+    /// Use an existing channel
+    pub fn from_channel(channel: Channel) -> Self {
+        Self {
+            cat: CatServiceClient::new(channel),
+        }
+    }
+
+    async fn connect_endpoint(endpoint: Endpoint) -> Result<Self> {
+        Ok(Self::from_channel(endpoint.connect().await?))
+    }
+
+    /// Transition a PCA on the peer. `expected_audience` is forwarded, but
+    /// peers do not check it until PCAs record their audience.
+    ///
+    /// A transition the peer refuses fails with [`Error::Remote`], carrying
+    /// the peer's error code; a peer that has not implemented transitions
+    /// yet answers `UNIMPLEMENTED`, reported as code `not_implemented`.
+    pub async fn transition(&mut self, pca: Vec<u8>, expected_audience: &str) -> Result<SignedPca> {
+        let response = self
+            .cat
+            .transition(TransitionRequest {
+                pca,
+                expected_audience: expected_audience.to_string(),
+            })
+            .await
+            .map_err(|status| match status.code() {
+                tonic::Code::Unimplemented => Error::Remote {
+                    code: "not_implemented".to_string(),
+                    message: status.message().to_string(),
+                },
+                _ => Error::Transport(status.to_string()),
+            })?
+            .into_inner();
+        if !response.error.is_empty() || response.pca.is_empty() {
+            return Err(Error::Remote {
+                code: response.error_code,
+                message: response.error,
+            });
+        }
+        Ok(SignedPca {
+            pca: response.pca,
+            signing_kid: response.signing_kid,
+        })
+    }
+}
+
+fn endpoint_for(uri: String) -> Result<Endpoint> {
+    Ok(Endpoint::from_shared(uri)?.connect_timeout(DEFAULT_CONNECT_TIMEOUT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::CatServiceImpl;
+    use crate::credentials::testing::{test_credentials_manager, TEST_DID};
+    use tonic::transport::server::TcpIncoming;
+
+    #[tokio::test]
+    async fn test_transition_against_peer() {
+        // Serve on the bound listener: connections queue until it accepts
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, false, None).unwrap();
+        let peer = CatServiceImpl::new(test_credentials_manager(TEST_DID)).into_server();
+        let server = tonic::transport::Server::builder().add_service(peer);
+        tokio::spawn(server.serve_with_incoming(incoming));
+
+        let mut client = TrustPlaneClient::connect(format!("http://{}", addr)).await.unwrap();
+
+        // Refusals come back with the peer's error code
+        let err = client.transition(vec![], "").await.unwrap_err();
+        assert!(matches!(err, Error::Remote { ref code, .. } if code == "invalid_argument"));
+        let err = client.transition(vec![1], "orders").await.unwrap_err();
+        assert!(matches!(err, Error::Remote { ref code, .. } if code == "not_implemented"));
+
+        assert!(TrustPlaneClient::connect("not a uri").await.is_err());
+    }
+}
@@ -0,0 +1,106 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Global concurrency limits.
+//!
+//! Caps how many exchanges or transitions run at once, across every client
+//! and front-end. A request that cannot get a slot within a short wait is
+//! refused with [`Error::Overloaded`] rather than queued, so load spikes
+//! push back on callers instead of on IdPs and the CPU.
+
+use crate::error::{Error, Result};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// How long a request waits for a free slot before being refused
+pub const DEFAULT_PERMIT_WAIT: Duration = Duration::from_millis(100);
+
+/// Cap on concurrent executions of one operation
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    /// Operation name, for error messages
+    operation: &'static str,
+    /// `None`: unlimited
+    permits: Option<Semaphore>,
+    max: usize,
+    wait: Duration,
+}
+
+impl ConcurrencyLimit {
+    /// Allow at most `max` concurrent `operation`s (0: unlimited)
+    pub fn new(operation: &'static str, max: usize) -> Self {
+        Self {
+            operation,
+            permits: (max > 0).then(|| Semaphore::new(max)),
+            max,
+            wait: DEFAULT_PERMIT_WAIT,
+        }
+    }
+
+    /// No limit
+    pub fn unlimited(operation: &'static str) -> Self {
+        Self::new(operation, 0)
+    }
+
+    /// Replace the wait for a free slot
+    pub fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    /// Take a slot, held until the returned permit is dropped (`None` when
+    /// unlimited). Fails with [`Error::Overloaded`] if every slot stays
+    /// busy for the whole wait.
+    pub async fn acquire(&self) -> Result<Option<SemaphorePermit<'_>>> {
+        let Some(permits) = &self.permits else {
+            return Ok(None);
+        };
+        match tokio::time::timeout(self.wait, permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            // The semaphore is never closed
+            Ok(Err(_)) | Err(_) => Err(Error::Overloaded(format!(
+                "{} concurrent {}s in progress, retry later",
+                self.max, self.operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let limit = ConcurrencyLimit::new("exchange", 1).with_wait(Duration::from_millis(10));
+        let held = limit.acquire().await.unwrap();
+        assert!(held.is_some());
+        let err = limit.acquire().await.unwrap_err();
+        assert_eq!(err.code(), "overloaded");
+        assert_eq!(err.status_code(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(tonic::Status::from(err).code(), tonic::Code::ResourceExhausted);
+
+        // Released slots are reused
+        drop(held);
+        assert!(limit.acquire().await.unwrap().is_some());
+
+        // Unlimited never waits
+        let limit = ConcurrencyLimit::unlimited("transition");
+        for _ in 0..100 {
+            assert!(limit.acquire().await.unwrap().is_none());
+        }
+    }
+}
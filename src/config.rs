@@ -17,28 +17,208 @@
 //! Configuration management.
 
 use crate::cli::Cli;
+use crate::credentials::{check_subject_fields, DidWebLocation, KeyFormat};
 use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
-/// Server configuration
-#[derive(Debug, Clone)]
+/// Tokio runtime flavor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeFlavor {
+    /// Work-stealing multi-thread scheduler
+    MultiThread,
+    /// Everything on the main thread (constrained sidecars)
+    SingleThread,
+}
+
+impl std::str::FromStr for RuntimeFlavor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "multi-thread" => Ok(RuntimeFlavor::MultiThread),
+            "single-thread" => Ok(RuntimeFlavor::SingleThread),
+            other => Err(Error::Config(format!(
+                "Invalid runtime: {} (expected multi-thread or single-thread)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for RuntimeFlavor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuntimeFlavor::MultiThread => write!(f, "multi-thread"),
+            RuntimeFlavor::SingleThread => write!(f, "single-thread"),
+        }
+    }
+}
+
+/// Where the Trust Plane's keys and credentials come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CredentialSource {
+    /// Generated at startup, one set per hosted DID
+    InMemory,
+    /// Keys, DID document and self-credential read from files
+    File {
+        issuer_key_path: PathBuf,
+        cat_key_path: PathBuf,
+        did_doc_path: PathBuf,
+        credential_path: PathBuf,
+        /// Accept one key for both the issuer and CAT roles
+        allow_shared_keys: bool,
+        /// Flag keys whose kid date is older than this many months
+        warn_key_age_months: Option<u32>,
+        /// Refuse to start on stale keys instead of warning
+        fail_on_stale_keys: bool,
+    },
+}
+
+impl CredentialSource {
+    /// Source selected by `--credential-provider` and its flags
+    fn from_cli(cli: &Cli) -> Result<Self> {
+        let path = |path: &Option<String>| {
+            path.as_deref().filter(|p| !p.is_empty()).map(PathBuf::from)
+        };
+        match cli.credential_provider.as_str() {
+            "inmemory" => {
+                let file_only = [
+                    ("--issuer-key-path", path(&cli.issuer_key_path).is_some()),
+                    ("--cat-key-path", path(&cli.cat_key_path).is_some()),
+                    ("--did-doc-path", path(&cli.did_doc_path).is_some()),
+                    ("--credential-path", path(&cli.credential_path).is_some()),
+                    ("--allow-shared-keys", cli.allow_shared_keys),
+                    ("--warn-key-age-months", cli.warn_key_age_months.is_some()),
+                    ("--fail-on-stale-keys", cli.fail_on_stale_keys),
+                ];
+                match file_only.into_iter().find(|(_, set)| *set) {
+                    Some((flag, _)) => Err(Error::Config(format!(
+                        "{} requires --credential-provider file",
+                        flag
+                    ))),
+                    None => Ok(CredentialSource::InMemory),
+                }
+            }
+            "file" => {
+                if cli.fail_on_stale_keys && cli.warn_key_age_months.is_none() {
+                    return Err(Error::Config(
+                        "--fail-on-stale-keys requires --warn-key-age-months".to_string(),
+                    ));
+                }
+                // One DID document holds one identity
+                if !cli.tenant_dids.is_empty() {
+                    return Err(Error::Config(
+                        "--tenant-dids requires --credential-provider inmemory".to_string(),
+                    ));
+                }
+                let required = |flag: &str, value: &Option<String>| {
+                    path(value).ok_or_else(|| {
+                        Error::Config(format!("--credential-provider file requires {}", flag))
+                    })
+                };
+                Ok(CredentialSource::File {
+                    issuer_key_path: required("--issuer-key-path", &cli.issuer_key_path)?,
+                    cat_key_path: required("--cat-key-path", &cli.cat_key_path)?,
+                    did_doc_path: required("--did-doc-path", &cli.did_doc_path)?,
+                    credential_path: required("--credential-path", &cli.credential_path)?,
+                    allow_shared_keys: cli.allow_shared_keys,
+                    warn_key_age_months: cli.warn_key_age_months,
+                    fail_on_stale_keys: cli.fail_on_stale_keys,
+                })
+            }
+            other => Err(Error::Config(format!(
+                "Invalid credential provider: {} (expected inmemory or file)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Server configuration.
+///
+/// Serializes to the effective configuration shown by `--print-config`,
+/// with durations in seconds. It holds no key material; keys are only
+/// referenced by path on the CLI.
+#[derive(Debug, Clone, Serialize)]
 pub struct Config {
     pub debug: bool,
     pub log_level: String,
+    pub log_pca_attributes_values: bool,
+    pub skip_startup_selftest: bool,
     pub appdata: PathBuf,
 
     // Identity
     pub did: String,
+    pub tenant_dids: Vec<String>,
     pub organization: String,
+    #[serde(serialize_with = "as_display")]
+    pub did_key_format: KeyFormat,
+    pub credential_issuance_date: Option<DateTime<Utc>>,
+    pub credential_subject: Map<String, Value>,
+    pub credential_source: CredentialSource,
 
     // Server
     pub http_addr: SocketAddr,
     pub grpc_addr: SocketAddr,
+    /// HTTP/2 keepalive ping interval (zero: no pings)
+    #[serde(serialize_with = "as_secs")]
+    pub grpc_http2_keepalive_interval: Duration,
+    #[serde(serialize_with = "as_secs")]
+    pub grpc_http2_keepalive_timeout: Duration,
+    /// Age at which gRPC connections are closed (zero: no limit)
+    #[serde(serialize_with = "as_secs")]
+    pub grpc_max_connection_age: Duration,
+    pub public_base_url: Option<String>,
+    pub strict_did_host: bool,
+    pub http_compression: bool,
+    pub serve_did_document: bool,
+    pub serve_trustplane_metadata: bool,
     pub metrics_enabled: bool,
+    pub serve_openapi: bool,
     pub bridge_admin_enabled: bool,
+    pub admin_enabled: bool,
+    #[serde(serialize_with = "as_secs")]
     pub shutdown_grace_period: Duration,
+
+    // Runtime
+    #[serde(serialize_with = "as_display")]
+    pub runtime: RuntimeFlavor,
+    /// Worker threads (`None` = one per CPU)
+    pub worker_threads: Option<usize>,
+    #[serde(serialize_with = "as_secs")]
+    pub drain_delay: Duration,
+
+    // CAT
+    pub cat_deny_subjects: Vec<String>,
+    pub cat_subject_quota: u32,
+    pub max_concurrent_transitions: usize,
+    pub max_pca_attributes: usize,
+    pub max_pca_bytes: usize,
+    pub strict_sequence: bool,
+
+    // Bridge
+    #[serde(serialize_with = "as_secs")]
+    pub idempotency_ttl: Duration,
+    pub idempotency_cache_size: usize,
+    pub max_credential_bytes: usize,
+    pub max_credential_claims: usize,
+    pub max_claim_value_bytes: usize,
+    pub bridge_mapping_trace: bool,
+    pub default_bridge_id: Option<String>,
+    pub cache_exchanges: bool,
+    #[serde(serialize_with = "as_secs")]
+    pub exchange_cache_ttl: Duration,
+    pub exchange_cache_size: usize,
+    pub max_concurrent_exchanges: usize,
+    #[serde(serialize_with = "as_secs")]
+    pub bridge_delete_grace: Duration,
+    pub bridges_dir: Option<PathBuf>,
 }
 
 impl TryFrom<Cli> for Config {
@@ -53,17 +233,325 @@ impl TryFrom<Cli> for Config {
             .parse()
             .map_err(|e| Error::Config(format!("Invalid gRPC address: {}", e)))?;
 
-        Ok(Self {
+        if cli.did.starts_with("did:web:") {
+            DidWebLocation::parse(&cli.did).map_err(|e| Error::Config(e.to_string()))?;
+        }
+        // Tenants are selected by host, so each must be a did:web DID
+        for did in &cli.tenant_dids {
+            DidWebLocation::parse(did)
+                .map_err(|e| Error::Config(format!("--tenant-dids: {}", e)))?;
+        }
+        let credential_source = CredentialSource::from_cli(&cli)?;
+
+        let credential_issuance_date = cli
+            .credential_issuance_date
+            .map(|date| {
+                DateTime::parse_from_rfc3339(&date)
+                    .map(|date| date.with_timezone(&Utc))
+                    .map_err(|e| {
+                        Error::Config(format!("Invalid --credential-issuance-date {}: {}", date, e))
+                    })
+            })
+            .transpose()?;
+
+        let credential_subject = match cli.credential_subject.as_deref() {
+            None | Some("") => Map::new(),
+            Some(json) => match serde_json::from_str(json) {
+                Ok(Value::Object(fields)) => fields,
+                Ok(_) => {
+                    return Err(Error::Config(
+                        "--credential-subject must be a JSON object".to_string(),
+                    ));
+                }
+                Err(e) => {
+                    return Err(Error::Config(format!("Invalid --credential-subject: {}", e)));
+                }
+            },
+        };
+        check_subject_fields(&credential_subject)?;
+
+        let public_base_url = cli.public_base_url.filter(|url| !url.is_empty());
+        if let Some(url) = &public_base_url {
+            url_authority(url).ok_or_else(|| {
+                Error::Config(format!("Invalid --public-base-url {}: expected http(s)://host", url))
+            })?;
+        }
+
+        let runtime: RuntimeFlavor = cli.runtime.parse()?;
+        if runtime == RuntimeFlavor::SingleThread && cli.worker_threads > 0 {
+            return Err(Error::Config(
+                "--worker-threads cannot be used with --runtime single-thread".to_string(),
+            ));
+        }
+
+        let config = Self {
             debug: cli.debug,
             log_level: cli.log_level,
+            log_pca_attributes_values: cli.log_pca_attributes_values,
+            skip_startup_selftest: cli.skip_startup_selftest,
             appdata: PathBuf::from(cli.appdata),
             did: cli.did,
+            tenant_dids: cli.tenant_dids,
             organization: cli.organization,
+            did_key_format: cli.did_key_format.parse()?,
+            credential_issuance_date,
+            credential_subject,
+            credential_source,
             http_addr,
             grpc_addr,
+            grpc_http2_keepalive_interval: Duration::from_secs(cli.grpc_http2_keepalive_interval),
+            grpc_http2_keepalive_timeout: Duration::from_secs(cli.grpc_http2_keepalive_timeout),
+            grpc_max_connection_age: Duration::from_secs(cli.grpc_max_connection_age),
+            public_base_url,
+            strict_did_host: cli.strict_did_host,
+            http_compression: cli.http_compression,
+            serve_did_document: cli.serve_did_document,
+            serve_trustplane_metadata: cli.serve_trustplane_metadata,
             metrics_enabled: cli.metrics_enabled,
+            serve_openapi: cli.serve_openapi,
             bridge_admin_enabled: cli.bridge_admin_enabled,
+            admin_enabled: cli.admin_enabled,
             shutdown_grace_period: Duration::from_secs(cli.shutdown_grace_period),
+            runtime,
+            worker_threads: (cli.worker_threads > 0).then_some(cli.worker_threads),
+            drain_delay: Duration::from_secs(cli.drain_delay),
+            cat_deny_subjects: cli.cat_deny_subjects,
+            cat_subject_quota: cli.cat_subject_quota,
+            max_concurrent_transitions: cli.max_concurrent_transitions,
+            max_pca_attributes: cli.max_pca_attributes,
+            max_pca_bytes: cli.max_pca_bytes,
+            strict_sequence: cli.strict_sequence,
+            idempotency_ttl: Duration::from_secs(cli.idempotency_ttl),
+            idempotency_cache_size: cli.idempotency_cache_size,
+            max_credential_bytes: cli.max_credential_bytes,
+            max_credential_claims: cli.max_credential_claims,
+            max_claim_value_bytes: cli.max_claim_value_bytes,
+            bridge_mapping_trace: cli.bridge_mapping_trace,
+            default_bridge_id: cli.default_bridge_id.filter(|id| !id.is_empty()),
+            cache_exchanges: cli.cache_exchanges,
+            exchange_cache_ttl: Duration::from_secs(cli.exchange_cache_ttl),
+            exchange_cache_size: cli.exchange_cache_size,
+            max_concurrent_exchanges: cli.max_concurrent_exchanges,
+            bridge_delete_grace: Duration::from_secs(cli.bridge_delete_grace),
+            bridges_dir: cli.bridges_dir.filter(|dir| !dir.is_empty()).map(PathBuf::from),
+        };
+
+        if !config.grpc_http2_keepalive_interval.is_zero()
+            && config.grpc_http2_keepalive_timeout.is_zero()
+        {
+            return Err(Error::Config(
+                "--grpc-http2-keepalive-timeout must be positive when keepalive pings are sent"
+                    .to_string(),
+            ));
+        }
+
+        if config.strict_did_host
+            && let Some(mismatch) = config.did_host_mismatch()
+        {
+            return Err(Error::Config(mismatch));
+        }
+        Ok(config)
+    }
+}
+
+impl Config {
+    /// Describe a mismatch between the `did:web` host and `--public-base-url`.
+    ///
+    /// `None` when they agree, or when either is absent (no public URL, or a
+    /// DID of another method). Default ports are ignored.
+    pub fn did_host_mismatch(&self) -> Option<String> {
+        let public = url_authority(self.public_base_url.as_deref()?)?;
+        let did = DidWebLocation::parse(&self.did).ok()?.authority.to_ascii_lowercase();
+        (did != public).then(|| {
+            format!(
+                "DID {} is published for host {}, but --public-base-url is served from {}",
+                self.did, did, public
+            )
         })
     }
-}
\ No newline at end of file
+}
+
+/// Lowercase `host[:port]` of an http(s) URL, without the scheme's default port
+fn url_authority(url: &str) -> Option<String> {
+    let (rest, default_port) = if let Some(rest) = url.strip_prefix("https://") {
+        (rest, ":443")
+    } else {
+        (url.strip_prefix("http://")?, ":80")
+    };
+    let authority = rest.split(['/', '?', '#']).next()?.to_ascii_lowercase();
+    let authority = authority.strip_suffix(default_port).unwrap_or(&authority);
+    (!authority.is_empty() && !authority.contains('@')).then(|| authority.to_string())
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+fn as_display<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: std::fmt::Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_effective_config_json() {
+        let cli = Cli::parse_from([
+            "permguard-trustplane",
+            "--idempotency-ttl",
+            "60",
+            "--runtime",
+            "single-thread",
+            "--did-key-format",
+            "both",
+            "--credential-issuance-date",
+            "2025-06-01T02:00:00+02:00",
+        ]);
+        let json = serde_json::to_value(Config::try_from(cli).unwrap()).unwrap();
+        assert_eq!(json["idempotency_ttl"], 60);
+        assert_eq!(json["runtime"], "single-thread");
+        assert_eq!(json["did_key_format"], "both");
+        assert_eq!(json["credential_issuance_date"], "2025-06-01T00:00:00Z");
+        assert!(json["http_addr"].is_string());
+    }
+
+    #[test]
+    fn test_credential_source() {
+        let config = |args: &[&str]| {
+            Config::try_from(Cli::parse_from(["permguard-trustplane"].iter().chain(args)))
+        };
+        let file = [
+            "--credential-provider",
+            "file",
+            "--issuer-key-path",
+            "issuer.jwk",
+            "--cat-key-path",
+            "cat.jwk",
+            "--did-doc-path",
+            "did.json",
+            "--credential-path",
+            "credential.json",
+        ];
+
+        assert_eq!(config(&[]).unwrap().credential_source, CredentialSource::InMemory);
+        let source = config(&[&file[..], &["--allow-shared-keys"]].concat()).unwrap();
+        let CredentialSource::File { cat_key_path, allow_shared_keys, .. } =
+            source.credential_source
+        else {
+            panic!("expected the file provider");
+        };
+        assert_eq!(cat_key_path, PathBuf::from("cat.jwk"));
+        assert!(allow_shared_keys);
+
+        // File-provider flags are refused rather than ignored
+        assert!(config(&["--allow-shared-keys"]).is_err());
+        assert!(config(&["--warn-key-age-months", "12"]).is_err());
+        assert!(config(&[&file[..], &["--fail-on-stale-keys"]].concat()).is_err());
+        let stale = ["--warn-key-age-months", "12", "--fail-on-stale-keys"];
+        let source = config(&[&file[..], &stale].concat()).unwrap().credential_source;
+        assert!(matches!(
+            source,
+            CredentialSource::File {
+                warn_key_age_months: Some(12),
+                fail_on_stale_keys: true,
+                ..
+            }
+        ));
+        assert!(config(&file[..8]).is_err());
+        let tenants = ["--tenant-dids", "did:web:b.example.com"];
+        assert!(config(&[&file[..], &tenants].concat()).is_err());
+        assert!(config(&["--credential-provider", "vault"]).is_err());
+    }
+
+    #[test]
+    fn test_grpc_keepalive() {
+        let config = |args: &[&str]| {
+            Config::try_from(Cli::parse_from(["permguard-trustplane"].iter().chain(args)))
+        };
+
+        let defaults = config(&[]).unwrap();
+        assert_eq!(defaults.grpc_http2_keepalive_interval, Duration::from_secs(30));
+        assert_eq!(defaults.grpc_http2_keepalive_timeout, Duration::from_secs(20));
+        assert!(defaults.grpc_max_connection_age.is_zero());
+
+        let tuned = config(&["--grpc-max-connection-age", "1800"]).unwrap();
+        assert_eq!(tuned.grpc_max_connection_age, Duration::from_secs(1800));
+
+        // A zero timeout is only meaningful when no pings are sent
+        assert!(config(&["--grpc-http2-keepalive-timeout", "0"]).is_err());
+        let args = ["--grpc-http2-keepalive-interval", "0", "--grpc-http2-keepalive-timeout", "0"];
+        assert!(config(&args).is_ok());
+    }
+
+    #[test]
+    fn test_did_web_validation() {
+        let config = |args: &[&str]| {
+            Config::try_from(Cli::parse_from(
+                ["permguard-trustplane"].iter().chain(args),
+            ))
+        };
+        assert!(config(&["--did", "did:web:example.com%3A8080:tenant:acme"]).is_ok());
+        assert!(matches!(
+            config(&["--did", "did:web:example.com%3Ax"]),
+            Err(Error::Config(_))
+        ));
+
+        let tenants = config(&[
+            "--tenant-dids",
+            "did:web:acme.example.com,did:web:globex.example.com",
+        ]);
+        assert_eq!(tenants.unwrap().tenant_dids.len(), 2);
+        assert!(config(&["--tenant-dids", "did:key:z6Mk"]).is_err());
+    }
+
+    #[test]
+    fn test_did_host_check() {
+        let config = |did: &str, url: &str, strict: bool| {
+            let mut args = vec!["permguard-trustplane", "--did", did, "--public-base-url", url];
+            if strict {
+                args.push("--strict-did-host");
+            }
+            Config::try_from(Cli::parse_from(args))
+        };
+        let ok = config("did:web:trust.acme.com", "https://Trust.acme.com:443/", true).unwrap();
+        assert_eq!(ok.did_host_mismatch(), None);
+        let port = config("did:web:trust.acme.com%3A8443", "https://trust.acme.com:8443", true);
+        assert!(port.is_ok());
+
+        // Warned about by default, refused when strict
+        let typo = config("did:web:trust.acme.co", "https://trust.acme.com", false).unwrap();
+        assert!(typo.did_host_mismatch().unwrap().contains("trust.acme.co"));
+        let strict = config("did:web:trust.acme.co", "https://trust.acme.com", true);
+        assert!(matches!(strict, Err(Error::Config(_))));
+
+        // Only did:web DIDs name a host
+        assert!(config("did:key:z6Mk", "https://trust.acme.com", true).is_ok());
+        assert!(matches!(
+            config("did:web:trust.acme.com", "trust.acme.com", false),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_credential_subject() {
+        let config = |subject: &str| {
+            Config::try_from(Cli::parse_from([
+                "permguard-trustplane",
+                "--credential-subject",
+                subject,
+            ]))
+        };
+        let subject = config(r#"{"jurisdiction":"IT"}"#).unwrap().credential_subject;
+        assert_eq!(subject["jurisdiction"], "IT");
+        assert!(config("").unwrap().credential_subject.is_empty());
+        assert!(matches!(config(r#"["IT"]"#), Err(Error::Config(_))));
+        assert!(matches!(config("{"), Err(Error::Config(_))));
+        assert!(matches!(config(r#"{"id":"did:web:evil"}"#), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JSON request bodies.
+//!
+//! Every HTTP body the gateway accepts is JSON. A request that carries a
+//! body of any other media type is refused up front with a JSON `415`
+//! (`unsupported_media_type`), instead of the plain-text rejection of the
+//! `Json` extractor. Body-less requests (e.g. `POST .../enable`) pass.
+
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+/// Middleware refusing non-JSON request bodies
+pub(crate) async fn require_json(request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    if !has_body(headers) || is_json(headers) {
+        return next.run(request).await;
+    }
+    let body = serde_json::json!({
+        "error": "request body must be application/json",
+        "code": "unsupported_media_type",
+    });
+    (StatusCode::UNSUPPORTED_MEDIA_TYPE, Json(body)).into_response()
+}
+
+/// Whether the request announces a body
+fn has_body(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::TRANSFER_ENCODING)
+        || headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|len| len != "0")
+}
+
+/// Whether the content type is `application/json` or `application/*+json`
+fn is_json(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    essence == "application/json"
+        || essence.strip_prefix("application/").is_some_and(|subtype| subtype.ends_with("+json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::Service;
+
+    #[tokio::test]
+    async fn test_require_json() {
+        let mut router = Router::new()
+            .route("/echo", post(|Json(v): Json<serde_json::Value>| async { Json(v) }))
+            .route("/enable", post(|| async { "ok" }))
+            .layer(middleware::from_fn(require_json));
+        let mut call = |uri: &str, content_type: Option<&str>, body: &'static str| {
+            let mut request = Request::post(uri).header(header::CONTENT_LENGTH, body.len());
+            if let Some(content_type) = content_type {
+                request = request.header(header::CONTENT_TYPE, content_type);
+            }
+            router.call(request.body(Body::from(body)).unwrap())
+        };
+
+        for content_type in [
+            "application/json",
+            "application/json; charset=utf-8",
+            "application/merge+json",
+        ] {
+            let response = call("/echo", Some(content_type), "{}").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", content_type);
+        }
+        for content_type in [Some("text/plain"), Some("application/x-www-form-urlencoded"), None] {
+            let response = call("/echo", content_type, "{}").await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["code"], "unsupported_media_type");
+        }
+
+        // No body, no content type needed
+        let response = call("/enable", None, "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
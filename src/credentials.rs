@@ -34,14 +34,20 @@
 mod provider;
 mod did;
 mod keys;
+mod tenants;
+#[cfg(test)]
+pub(crate) mod testing;
 
-pub use provider::{CredentialProvider, InMemoryProvider, FileProvider};
-pub use did::DidDocument;
-pub use keys::KeyPair;
+pub use provider::{check_subject_fields, CredentialProvider, InMemoryProvider, FileProvider};
+pub use did::{DidDocument, DidWebLocation, KeyFormat, VerificationMethod, WELL_KNOWN_DID_PATH};
+pub use keys::{public_key_from_jwk, verify_signature, KeyPair};
+pub use tenants::TenantCredentials;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::watch;
+use tracing::warn;
 
 /// Trust Plane credentials: DID, keys, and self-issued credential
 #[derive(Clone, Debug)]
@@ -63,12 +69,159 @@ pub struct TrustPlaneCredentials {
     
     /// Self-issued credential
     pub credential: serde_json::Value,
+
+    /// When these keys were issued or last rotated
+    pub issued_at: DateTime<Utc>,
+
+    /// CAT key replaced by the last rotation, still accepted on PCAs
+    pub retired_cat_kid: Option<String>,
+}
+
+/// Default domain-separation context PCA signatures are made under.
+///
+/// Changing the context invalidates every PCA signed under the old one:
+/// verifiers reject them until they adopt the new context.
+pub const DEFAULT_PCA_SIGNING_CONTEXT: &str = "permguard-pca-v1";
+
+/// Bytes a PCA signature covers: the context, a NUL separator, then the
+/// encoded PCA. A signature made for one context never verifies in another.
+pub fn pca_signing_input(context: &str, pca: &[u8]) -> Vec<u8> {
+    let mut input = Vec::with_capacity(context.len() + 1 + pca.len());
+    input.extend_from_slice(context.as_bytes());
+    input.push(0);
+    input.extend_from_slice(pca);
+    input
+}
+
+/// Fail unless `context` can be used as a PCA signing context
+pub fn check_pca_signing_context(context: &str) -> Result<()> {
+    if context.is_empty() || context.contains('\0') {
+        return Err(Error::Config(format!(
+            "PCA signing context {:?} must be non-empty and contain no NUL",
+            context
+        )));
+    }
+    Ok(())
+}
+
+/// Encoded PCA with the kid of the key that signed it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedPca {
+    /// PCA (CBOR)
+    pub pca: Vec<u8>,
+    /// Verification method ID of the signing key
+    pub signing_kid: String,
+}
+
+/// Key generation a signature verified against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Current issuer or CAT key
+    Current,
+    /// Key replaced by a rotation but still published in the DID document
+    SignedByRetiredKey,
+}
+
+impl SignatureStatus {
+    /// Machine-readable outcome code
+    pub fn code(&self) -> &'static str {
+        match self {
+            SignatureStatus::Current => "signed_by_current_key",
+            SignatureStatus::SignedByRetiredKey => "signed_by_retired_key",
+        }
+    }
+}
+
+impl TrustPlaneCredentials {
+    /// Verify a signature made by one of this Trust Plane's keys.
+    ///
+    /// Keys retired by a rotation stay published until the next one, so
+    /// signatures made just before a rotation still verify; those are
+    /// reported as [`SignatureStatus::SignedByRetiredKey`] and logged at
+    /// warning level.
+    pub fn verify(&self, kid: &str, message: &[u8], signature: &[u8]) -> Result<SignatureStatus> {
+        let method = self
+            .did_document
+            .verification_method(kid)
+            .ok_or_else(|| Error::Crypto(format!("Key {} is not published by {}", kid, self.did)))?;
+        keys::verify_signature(&method.public_key_bytes()?, message, signature)?;
+
+        if kid == self.issuer_key.kid() || kid == self.cat_key.kid() {
+            return Ok(SignatureStatus::Current);
+        }
+        warn!(
+            kid = %kid,
+            outcome = SignatureStatus::SignedByRetiredKey.code(),
+            "Signature made with a retired key - accepted until the next rotation"
+        );
+        Ok(SignatureStatus::SignedByRetiredKey)
+    }
+
+    /// Sign an encoded PCA with the CAT key under a signing context
+    pub fn sign_pca(&self, context: &str, pca: &[u8]) -> Vec<u8> {
+        self.cat_key.sign(&pca_signing_input(context, pca))
+    }
+
+    /// Verify a PCA signature made under `context` by the current or the
+    /// retired CAT key (see [`verify`](Self::verify)).
+    ///
+    /// The issuer key is published in the same DID document but never signs
+    /// PCAs, so its signatures are refused here.
+    ///
+    /// TODO: the encoded PCA does not carry its signing context yet; until
+    /// PCA encoding lands, verifiers must be configured with the context.
+    pub fn verify_pca(
+        &self,
+        kid: &str,
+        context: &str,
+        pca: &[u8],
+        signature: &[u8],
+    ) -> Result<SignatureStatus> {
+        if kid != self.cat_key.kid() && self.retired_cat_kid.as_deref() != Some(kid) {
+            return Err(Error::Crypto(format!("Key {} is not a CAT key of {}", kid, self.did)));
+        }
+        self.verify(kid, &pca_signing_input(context, pca), signature)
+    }
+
+    /// Sign and verify sample payloads with both keys.
+    ///
+    /// Signatures are checked against the public keys published in the DID
+    /// document, so a key that does not match its document fails here
+    /// rather than at the first verifier.
+    pub fn self_test(&self) -> Result<()> {
+        // TODO: sign a real PCA once PCA encoding lands
+        let checks = [
+            (&self.cat_key, b"permguard-selftest-pca".as_slice()),
+            (&self.issuer_key, b"permguard-selftest-credential".as_slice()),
+        ];
+
+        for (key, payload) in checks {
+            let method = self
+                .did_document
+                .verification_method(key.kid())
+                .ok_or_else(|| {
+                    Error::Crypto(format!("Key {} not found in DID document", key.kid()))
+                })?;
+            let public_key = method
+                .public_key_bytes()
+                .map_err(|e| Error::Crypto(format!("Key {}: {}", key.kid(), e)))?;
+            let signature = key.sign(payload);
+            keys::verify_signature(&public_key, payload, &signature)
+                .map_err(|e| Error::Crypto(format!("Self-test failed for {}: {}", key.kid(), e)))?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Manages credentials lifecycle with hot-reload support
 pub struct CredentialsManager {
     current: watch::Sender<Arc<TrustPlaneCredentials>>,
     receiver: watch::Receiver<Arc<TrustPlaneCredentials>>,
+    /// Source for on-demand rotation
+    provider: Option<Box<dyn CredentialProvider>>,
+    /// Serializes rotations
+    rotating: std::sync::Mutex<()>,
 }
 
 impl CredentialsManager {
@@ -78,6 +231,8 @@ impl CredentialsManager {
         Self {
             current: tx,
             receiver: rx,
+            provider: None,
+            rotating: std::sync::Mutex::new(()),
         }
     }
 
@@ -87,6 +242,30 @@ impl CredentialsManager {
         Ok(Self::new(credentials))
     }
 
+    /// Create from a provider kept for on-demand rotation
+    pub fn with_provider(provider: Box<dyn CredentialProvider>) -> Result<Self> {
+        let credentials = provider.load()?;
+        Ok(Self {
+            provider: Some(provider),
+            ..Self::new(credentials)
+        })
+    }
+
+    /// Rotate keys through the provider.
+    ///
+    /// The new credentials must pass the signing self-test before they
+    /// replace the current ones. Returns the credentials now in use.
+    pub fn rotate(&self) -> Result<Arc<TrustPlaneCredentials>> {
+        let provider = self.provider.as_ref().ok_or_else(|| {
+            Error::Config("credentials were not loaded from a provider".to_string())
+        })?;
+        let _rotating = self.rotating.lock().unwrap();
+        let rotated = provider.rotate(&self.current())?;
+        rotated.self_test()?;
+        self.update(rotated);
+        Ok(self.current())
+    }
+
     /// Get current credentials
     pub fn current(&self) -> Arc<TrustPlaneCredentials> {
         self.receiver.borrow().clone()
@@ -106,4 +285,37 @@ impl CredentialsManager {
     pub async fn start_watch(&self, provider: Box<dyn CredentialProvider>) -> Result<()> {
         provider.watch(self.current.clone()).await
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::testing::{test_credentials, TEST_DID};
+
+    #[test]
+    fn test_pca_signing_context() {
+        let creds = test_credentials(TEST_DID);
+        let kid = creds.cat_key.kid();
+        let signature = creds.sign_pca(DEFAULT_PCA_SIGNING_CONTEXT, b"pca");
+        let status = creds.verify_pca(kid, DEFAULT_PCA_SIGNING_CONTEXT, b"pca", &signature);
+        assert_eq!(status.unwrap(), SignatureStatus::Current);
+
+        // Not valid under another context, nor as a bare signature
+        assert!(creds.verify_pca(kid, "permguard-pca-v2", b"pca", &signature).is_err());
+        assert!(creds.verify(kid, b"pca", &signature).is_err());
+
+        // The issuer key is published but never signs PCAs
+        let issuer_kid = creds.issuer_key.kid();
+        let input = pca_signing_input(DEFAULT_PCA_SIGNING_CONTEXT, b"pca");
+        let signature = creds.issuer_key.sign(&input);
+        let status = creds.verify_pca(issuer_kid, DEFAULT_PCA_SIGNING_CONTEXT, b"pca", &signature);
+        assert!(matches!(status, Err(Error::Crypto(_))));
+
+        // The separator keeps context and PCA bytes apart
+        assert_ne!(pca_signing_input("ab", b"c"), pca_signing_input("a", b"bc"));
+
+        assert!(check_pca_signing_context(DEFAULT_PCA_SIGNING_CONTEXT).is_ok());
+        assert!(check_pca_signing_context("").is_err());
+        assert!(check_pca_signing_context("a\0b").is_err());
+    }
+}
@@ -17,7 +17,50 @@
 //! DID Document management.
 
 use crate::credentials::KeyPair;
+use crate::credentials::keys::{
+    public_key_from_jwk, public_key_from_multibase, public_key_to_jwk, public_key_to_multibase,
+};
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Public key encoding(s) emitted in verification methods
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// `publicKeyJwk` only
+    #[default]
+    Jwk,
+    /// `publicKeyMultibase` only
+    Multibase,
+    /// Both `publicKeyJwk` and `publicKeyMultibase`
+    Both,
+}
+
+impl FromStr for KeyFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "jwk" => Ok(KeyFormat::Jwk),
+            "multibase" => Ok(KeyFormat::Multibase),
+            "both" => Ok(KeyFormat::Both),
+            other => Err(Error::Config(format!(
+                "Invalid DID key format: {} (expected jwk, multibase or both)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyFormat::Jwk => write!(f, "jwk"),
+            KeyFormat::Multibase => write!(f, "multibase"),
+            KeyFormat::Both => write!(f, "both"),
+        }
+    }
+}
 
 /// DID Document
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -46,42 +89,121 @@ pub struct VerificationMethod {
     
     pub controller: String,
     
-    #[serde(rename = "publicKeyJwk")]
-    pub public_key_jwk: serde_json::Value,
+    #[serde(rename = "publicKeyJwk", default, skip_serializing_if = "Option::is_none")]
+    pub public_key_jwk: Option<serde_json::Value>,
+
+    #[serde(rename = "publicKeyMultibase", default, skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+}
+
+impl VerificationMethod {
+    /// Create an Ed25519 verification method for a key pair
+    pub fn new(controller: &str, key: &KeyPair, format: KeyFormat) -> Self {
+        let jwk = matches!(format, KeyFormat::Jwk | KeyFormat::Both);
+        let multibase = matches!(format, KeyFormat::Multibase | KeyFormat::Both);
+
+        Self {
+            id: key.kid().to_string(),
+            method_type: "Ed25519VerificationKey2020".to_string(),
+            controller: controller.to_string(),
+            public_key_jwk: jwk.then(|| key.public_jwk()),
+            public_key_multibase: multibase.then(|| key.public_multibase()),
+        }
+    }
+
+    /// Create an Ed25519 verification method from a public JWK carrying
+    /// its `kid`, as [`VerificationMethod::new`] would for the key pair
+    pub fn from_public_jwk(
+        controller: &str,
+        jwk: &serde_json::Value,
+        format: KeyFormat,
+    ) -> Result<Self> {
+        if jwk.get("d").is_some() {
+            return Err(Error::Invalid("JWK must be public, found private key (d)".into()));
+        }
+        let kid = jwk["kid"]
+            .as_str()
+            .filter(|kid| !kid.is_empty())
+            .ok_or_else(|| Error::Invalid("Missing kid in JWK".into()))?;
+        let public_key = public_key_from_jwk(jwk)?;
+        let with_jwk = matches!(format, KeyFormat::Jwk | KeyFormat::Both);
+        let multibase = matches!(format, KeyFormat::Multibase | KeyFormat::Both);
+
+        Ok(Self {
+            id: kid.to_string(),
+            method_type: "Ed25519VerificationKey2020".to_string(),
+            controller: controller.to_string(),
+            public_key_jwk: with_jwk.then(|| public_key_to_jwk(kid, &public_key)),
+            public_key_multibase: multibase.then(|| public_key_to_multibase(&public_key)),
+        })
+    }
+
+    /// Raw Ed25519 public key, from whichever encoding is present.
+    ///
+    /// When both are present they must agree.
+    pub fn public_key_bytes(&self) -> Result<[u8; 32]> {
+        let from_jwk = self.public_key_jwk.as_ref().map(public_key_from_jwk).transpose()?;
+        let from_multibase = self
+            .public_key_multibase
+            .as_deref()
+            .map(public_key_from_multibase)
+            .transpose()?;
+
+        match (from_jwk, from_multibase) {
+            (Some(a), Some(b)) if a != b => Err(Error::Invalid(format!(
+                "publicKeyJwk and publicKeyMultibase differ for {}",
+                self.id
+            ))),
+            (Some(key), _) | (None, Some(key)) => Ok(key),
+            (None, None) => Err(Error::Invalid(format!(
+                "Verification method {} has no public key",
+                self.id
+            ))),
+        }
+    }
 }
 
 impl DidDocument {
     /// Create DID Document for Trust Plane with issuer and CAT keys
     pub fn new(did: &str, issuer_key: &KeyPair, cat_key: &KeyPair) -> Self {
-        let issuer_method = VerificationMethod {
-            id: issuer_key.kid().to_string(),
-            method_type: "Ed25519VerificationKey2020".to_string(),
-            controller: did.to_string(),
-            public_key_jwk: issuer_key.public_jwk(),
-        };
+        Self::with_key_format(did, issuer_key, cat_key, KeyFormat::default())
+    }
 
-        let cat_method = VerificationMethod {
-            id: cat_key.kid().to_string(),
-            method_type: "Ed25519VerificationKey2020".to_string(),
-            controller: did.to_string(),
-            public_key_jwk: cat_key.public_jwk(),
-        };
+    /// Create DID Document emitting public keys in the given format
+    pub fn with_key_format(
+        did: &str,
+        issuer_key: &KeyPair,
+        cat_key: &KeyPair,
+        format: KeyFormat,
+    ) -> Self {
+        let issuer_method = VerificationMethod::new(did, issuer_key, format);
+        let cat_method = VerificationMethod::new(did, cat_key, format);
+        Self::from_methods(did, issuer_method, cat_method)
+    }
 
+    /// Preview the document [`DidDocument::with_key_format`] generates,
+    /// from the public JWKs (with their `kid`) of the issuer and CAT keys
+    pub fn from_public_jwks(
+        did: &str,
+        issuer_jwk: &serde_json::Value,
+        cat_jwk: &serde_json::Value,
+        format: KeyFormat,
+    ) -> Result<Self> {
+        let issuer_method = VerificationMethod::from_public_jwk(did, issuer_jwk, format)?;
+        let cat_method = VerificationMethod::from_public_jwk(did, cat_jwk, format)?;
+        Ok(Self::from_methods(did, issuer_method, cat_method))
+    }
+
+    fn from_methods(did: &str, issuer: VerificationMethod, cat: VerificationMethod) -> Self {
         Self {
             context: vec![
                 "https://www.w3.org/ns/did/v1".to_string(),
                 "https://w3id.org/security/suites/ed25519-2020/v1".to_string(),
             ],
             id: did.to_string(),
-            verification_method: vec![issuer_method, cat_method],
-            assertion_method: vec![
-                issuer_key.kid().to_string(),
-                cat_key.kid().to_string(),
-            ],
-            authentication: vec![
-                issuer_key.kid().to_string(),
-                cat_key.kid().to_string(),
-            ],
+            assertion_method: vec![issuer.id.clone(), cat.id.clone()],
+            authentication: vec![issuer.id.clone(), cat.id.clone()],
+            verification_method: vec![issuer, cat],
         }
     }
 
@@ -90,9 +212,193 @@ impl DidDocument {
         serde_json::to_value(self).unwrap()
     }
 
-    /// Load from JSON
-    pub fn from_json(json: &serde_json::Value) -> crate::error::Result<Self> {
-        serde_json::from_value(json.clone())
-            .map_err(|e| crate::error::Error::Invalid(format!("Invalid DID document: {}", e)))
+    /// Load from JSON, accepting `publicKeyJwk` and/or `publicKeyMultibase`
+    pub fn from_json(json: &serde_json::Value) -> Result<Self> {
+        let document: Self = serde_json::from_value(json.clone())
+            .map_err(|e| Error::Invalid(format!("Invalid DID document: {}", e)))?;
+        for method in &document.verification_method {
+            method.public_key_bytes()?;
+        }
+        Ok(document)
+    }
+
+    /// Find a verification method by ID
+    pub fn verification_method(&self, kid: &str) -> Option<&VerificationMethod> {
+        self.verification_method.iter().find(|m| m.id == kid)
+    }
+
+    /// Keep publishing `kids` from a replaced document as assertion methods,
+    /// so signatures made before a rotation still verify
+    pub fn keep_verifying(&mut self, previous: &DidDocument, kids: &[&str]) {
+        for kid in kids {
+            if self.verification_method(kid).is_some() {
+                continue;
+            }
+            if let Some(method) = previous.verification_method(kid) {
+                self.verification_method.push(method.clone());
+                self.assertion_method.push(kid.to_string());
+            }
+        }
+    }
+}
+
+/// Path of the DID document for a `did:web` DID without path segments
+pub const WELL_KNOWN_DID_PATH: &str = "/.well-known/did.json";
+
+/// Where a `did:web` DID document is published
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DidWebLocation {
+    /// Host, with port if the DID encodes one (e.g. `example.com:8080`)
+    pub authority: String,
+    /// Document path: [`WELL_KNOWN_DID_PATH`] or `/<segments>/did.json`
+    pub path: String,
+}
+
+impl DidWebLocation {
+    /// Decode a `did:web` method-specific ID.
+    ///
+    /// The first segment is the percent-encoded host (a port as `%3A`),
+    /// each following `:`-separated segment one path component.
+    pub fn parse(did: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Invalid(format!("Invalid did:web {}: {}", did, reason));
+
+        let id = did
+            .strip_prefix("did:web:")
+            .ok_or_else(|| invalid("not a did:web DID"))?;
+        let mut segments = id.split(':');
+
+        let authority = percent_decode(segments.next().unwrap_or_default())
+            .ok_or_else(|| invalid("bad percent-encoding in host"))?;
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority.as_str(), None),
+        };
+        if host.is_empty()
+            || !host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        {
+            return Err(invalid("bad host"));
+        }
+        if port.is_some_and(|p| p.parse::<u16>().is_err()) {
+            return Err(invalid("bad port"));
+        }
+
+        let mut path = String::new();
+        for segment in segments {
+            let segment = percent_decode(segment)
+                .filter(|s| !s.is_empty() && !s.contains('/') && s != "." && s != "..")
+                .ok_or_else(|| invalid("bad path segment"))?;
+            path.push('/');
+            path.push_str(&segment);
+        }
+        let path = if path.is_empty() {
+            WELL_KNOWN_DID_PATH.to_string()
+        } else {
+            path + "/did.json"
+        };
+
+        Ok(Self { authority, path })
+    }
+
+    /// HTTPS URL of the DID document
+    pub fn url(&self) -> String {
+        format!("https://{}{}", self.authority, self.path)
+    }
+}
+
+/// Decode `%XX` escapes; `None` if malformed or not UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
     }
-}
\ No newline at end of file
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::testing::{test_keys, TEST_DID};
+
+    #[test]
+    fn test_did_document_key_formats() {
+        let did = TEST_DID;
+        let (issuer, cat) = test_keys(did);
+
+        for format in [KeyFormat::Jwk, KeyFormat::Multibase, KeyFormat::Both] {
+            let json = DidDocument::with_key_format(did, &issuer, &cat, format).to_json();
+            let method = &json["verificationMethod"][0];
+            assert_eq!(method.get("publicKeyJwk").is_some(), format != KeyFormat::Multibase);
+            assert_eq!(method.get("publicKeyMultibase").is_some(), format != KeyFormat::Jwk);
+
+            let parsed = DidDocument::from_json(&json).unwrap();
+            let cat_method = parsed.verification_method(cat.kid()).unwrap();
+            assert_eq!(cat_method.public_key_bytes().unwrap(), cat.public_key_bytes());
+        }
+
+        assert!(issuer.public_multibase().starts_with("z6Mk"));
+    }
+
+    #[test]
+    fn test_did_document_from_public_jwks() {
+        let did = TEST_DID;
+        let (issuer, cat) = test_keys(did);
+
+        for format in [KeyFormat::Jwk, KeyFormat::Multibase, KeyFormat::Both] {
+            let preview =
+                DidDocument::from_public_jwks(did, &issuer.public_jwk(), &cat.public_jwk(), format)
+                    .unwrap();
+            let generated = DidDocument::with_key_format(did, &issuer, &cat, format);
+            assert_eq!(preview.to_json(), generated.to_json());
+        }
+
+        // Private keys and kid-less keys are refused
+        let private = issuer.private_jwk();
+        assert!(DidDocument::from_public_jwks(did, &private, &cat.public_jwk(), KeyFormat::Jwk)
+            .is_err());
+        let mut anonymous = cat.public_jwk();
+        anonymous.as_object_mut().unwrap().remove("kid");
+        assert!(
+            DidDocument::from_public_jwks(did, &issuer.public_jwk(), &anonymous, KeyFormat::Jwk)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_did_web_location() {
+        let location = DidWebLocation::parse("did:web:example.com%3A8080:tenant:acme").unwrap();
+        assert_eq!(location.authority, "example.com:8080");
+        assert_eq!(location.path, "/tenant/acme/did.json");
+        assert_eq!(location.url(), "https://example.com:8080/tenant/acme/did.json");
+
+        let location = DidWebLocation::parse("did:web:localhost%3a8443").unwrap();
+        assert_eq!(location.url(), "https://localhost:8443/.well-known/did.json");
+        assert_eq!(
+            DidWebLocation::parse(TEST_DID).unwrap().path,
+            WELL_KNOWN_DID_PATH
+        );
+
+        for invalid in [
+            "did:key:z6Mk",
+            "did:web:",
+            "did:web:example.com%3Ahttps",
+            "did:web:example.com%3A99999",
+            "did:web:example.com%2",
+            "did:web:ex%2Fample.com",
+            "did:web:example.com::acme",
+            "did:web:example.com:%2E%2E",
+            "did:web:example.com:a%2Fb",
+        ] {
+            assert!(DidWebLocation::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -16,11 +16,12 @@
 
 //! Credential providers.
 
-use crate::credentials::{DidDocument, KeyPair, TrustPlaneCredentials};
+use crate::credentials::{DidDocument, KeyFormat, KeyPair, TrustPlaneCredentials};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
-use chrono::Utc;
-use std::path::PathBuf;
+use chrono::{DateTime, Datelike, Utc};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::watch;
 use tracing::{info, warn};
@@ -33,38 +34,78 @@ pub trait CredentialProvider: Send + Sync {
 
     /// Watch for credential changes (hot-reload)
     async fn watch(&self, tx: watch::Sender<Arc<TrustPlaneCredentials>>) -> Result<()>;
+
+    /// Credentials replacing `current` on an on-demand rotation.
+    ///
+    /// Providers that cannot mint keys reload from their source, picking up
+    /// keys rotated there.
+    fn rotate(&self, current: &TrustPlaneCredentials) -> Result<TrustPlaneCredentials> {
+        let _ = current;
+        self.load()
+    }
 }
 
 /// In-memory provider: generates ephemeral keys at startup
 pub struct InMemoryProvider {
     pub did: String,
     pub organization: String,
+    pub key_format: KeyFormat,
+    /// Fixed issuance date for the self-credential and kid dates (default: now)
+    pub issuance_date: Option<DateTime<Utc>>,
+    /// Extra `credentialSubject` fields for the self-credential
+    pub subject_fields: serde_json::Map<String, serde_json::Value>,
 }
 
 #[async_trait]
 impl CredentialProvider for InMemoryProvider {
     fn load(&self) -> Result<TrustPlaneCredentials> {
         warn!("Using in-memory credential provider - keys are ephemeral!");
+        self.generate(self.issuance_date, None)
+    }
+
+    async fn watch(&self, _tx: watch::Sender<Arc<TrustPlaneCredentials>>) -> Result<()> {
+        // No refresh for in-memory
+        Ok(())
+    }
+
+    /// Fresh key pairs; the DID document keeps the replaced keys so PCAs
+    /// they signed still verify until the next rotation
+    fn rotate(&self, current: &TrustPlaneCredentials) -> Result<TrustPlaneCredentials> {
+        self.generate(None, Some(current))
+    }
+}
+
+impl InMemoryProvider {
+    fn generate(
+        &self,
+        issuance_date: Option<DateTime<Utc>>,
+        previous: Option<&TrustPlaneCredentials>,
+    ) -> Result<TrustPlaneCredentials> {
+        let issued_at = issuance_date.unwrap_or_else(Utc::now);
+        let kid = |role: &str| fresh_kid(&self.did, role, issued_at, previous);
+        let issuer_key = KeyPair::generate(kid("issuer"));
+        let cat_key = KeyPair::generate(kid("cat"));
         
-        let date = Utc::now().format("%Y%m");
-        let issuer_kid = format!("{}#issuer-key-{}", self.did, date);
-        let cat_kid = format!("{}#cat-key-{}", self.did, date);
-        
-        let issuer_key = KeyPair::generate(&issuer_kid);
-        let cat_key = KeyPair::generate(&cat_kid);
-        
-        let did_document = DidDocument::new(&self.did, &issuer_key, &cat_key);
+        let mut did_document =
+            DidDocument::with_key_format(&self.did, &issuer_key, &cat_key, self.key_format);
+        if let Some(previous) = previous {
+            did_document.keep_verifying(
+                &previous.did_document,
+                &[previous.issuer_key.kid(), previous.cat_key.kid()],
+            );
+        }
         
-        let credential = create_self_credential(
-            &self.did,
-            &self.organization,
-            &issuer_key,
-        );
+        check_subject_fields(&self.subject_fields)?;
+        let mut credential =
+            create_self_credential(&self.did, &self.organization, issuance_date);
+        if let Some(subject) = credential["credentialSubject"].as_object_mut() {
+            subject.extend(self.subject_fields.clone());
+        }
         
         info!(
             did = %self.did,
-            issuer_kid = %issuer_kid,
-            cat_kid = %cat_kid,
+            issuer_kid = %issuer_key.kid(),
+            cat_kid = %cat_key.kid(),
             "Generated ephemeral credentials"
         );
         
@@ -75,13 +116,32 @@ impl CredentialProvider for InMemoryProvider {
             cat_key,
             did_document,
             credential,
+            issued_at,
+            retired_cat_kid: previous.map(|p| p.cat_key.kid().to_string()),
         })
     }
+}
 
-    async fn watch(&self, _tx: watch::Sender<Arc<TrustPlaneCredentials>>) -> Result<()> {
-        // No refresh for in-memory
-        Ok(())
+/// Kid for a new `role` key: `#<role>-key-YYYYMM`, numbered
+/// (`#<role>-key-2-YYYYMM`, ...) when a key of the same month is still in
+/// the previous DID document. The date stays last for [`kid_month`].
+fn fresh_kid(
+    did: &str,
+    role: &str,
+    issued_at: DateTime<Utc>,
+    previous: Option<&TrustPlaneCredentials>,
+) -> String {
+    let date = issued_at.format("%Y%m");
+    let taken = |kid: &str| {
+        previous.is_some_and(|p| p.did_document.verification_method(kid).is_some())
+    };
+    let mut kid = format!("{}#{}-key-{}", did, role, date);
+    let mut n = 2;
+    while taken(&kid) {
+        kid = format!("{}#{}-key-{}-{}", did, role, n, date);
+        n += 1;
     }
+    kid
 }
 
 /// File provider: loads from disk
@@ -90,6 +150,12 @@ pub struct FileProvider {
     pub cat_key_path: PathBuf,
     pub did_doc_path: PathBuf,
     pub credential_path: PathBuf,
+    /// Accept the same key material for issuer and CAT keys (warns instead of failing)
+    pub allow_shared_keys: bool,
+    /// Warn about keys whose kid date (`-YYYYMM`) is older than this many months
+    pub warn_key_age_months: Option<u32>,
+    /// Fail instead of warning about stale keys
+    pub fail_on_stale_keys: bool,
 }
 
 #[async_trait]
@@ -101,32 +167,39 @@ impl CredentialProvider for FileProvider {
             "Loading credentials from files"
         );
         
-        // Load issuer key
-        let issuer_jwk: serde_json::Value = serde_json::from_str(
-            &std::fs::read_to_string(&self.issuer_key_path)
-                .map_err(|e| Error::Io(e))?
-        ).map_err(|e| Error::Invalid(format!("Invalid issuer key JSON: {}", e)))?;
-        let issuer_key = KeyPair::from_jwk(&issuer_jwk)?;
-        
-        // Load CAT key
-        let cat_jwk: serde_json::Value = serde_json::from_str(
-            &std::fs::read_to_string(&self.cat_key_path)
-                .map_err(|e| Error::Io(e))?
-        ).map_err(|e| Error::Invalid(format!("Invalid CAT key JSON: {}", e)))?;
-        let cat_key = KeyPair::from_jwk(&cat_jwk)?;
-        
         // Load DID document
         let did_doc_json: serde_json::Value = serde_json::from_str(
-            &std::fs::read_to_string(&self.did_doc_path)
-                .map_err(|e| Error::Io(e))?
+            &std::fs::read_to_string(&self.did_doc_path)?
         ).map_err(|e| Error::Invalid(format!("Invalid DID document JSON: {}", e)))?;
         let did_document = DidDocument::from_json(&did_doc_json)?;
-        
+
+        // Load keys
+        let issuer_key = load_key(&self.issuer_key_path, "issuer", &did_document)?;
+        let cat_key = load_key(&self.cat_key_path, "CAT", &did_document)?;
+        check_published_keys(&did_document, &[("issuer", &issuer_key), ("CAT", &cat_key)])?;
+        if let Some(max_months) = self.warn_key_age_months {
+            let today = Utc::now();
+            for (name, key) in [("issuer", &issuer_key), ("CAT", &cat_key)] {
+                self.check_key_age(name, key, max_months, (today.year(), today.month()))?;
+            }
+        }
+
+        // One key for both roles collapses the boundary between issuing
+        // credentials and transitioning authority
+        if issuer_key.public_key_bytes() == cat_key.public_key_bytes() {
+            if !self.allow_shared_keys {
+                return Err(Error::Config(format!(
+                    "issuer key {} and CAT key {} are the same key material \
+                     (use --allow-shared-keys to accept)",
+                    self.issuer_key_path.display(),
+                    self.cat_key_path.display()
+                )));
+            }
+            warn!("Issuer and CAT keys share key material - issuance and transition are not separated");
+        }
+
         // Load credential
-        let credential: serde_json::Value = serde_json::from_str(
-            &std::fs::read_to_string(&self.credential_path)
-                .map_err(|e| Error::Io(e))?
-        ).map_err(|e| Error::Invalid(format!("Invalid credential JSON: {}", e)))?;
+        let credential = load_credential(&self.credential_path)?;
         
         let did = did_document.id.clone();
         let organization = credential["credentialSubject"]["organization"]
@@ -148,25 +221,261 @@ impl CredentialProvider for FileProvider {
             cat_key,
             did_document,
             credential,
+            issued_at: self.modified_at(),
+            // Files carry no rotation history: only the current CAT key signs
+            retired_cat_kid: None,
         })
     }
 
-    async fn watch(&self, tx: watch::Sender<Arc<TrustPlaneCredentials>>) -> Result<()> {
+    async fn watch(&self, _tx: watch::Sender<Arc<TrustPlaneCredentials>>) -> Result<()> {
         // TODO: Implement file watching with notify crate
         // For now, no hot-reload
         Ok(())
     }
 }
 
-/// Create self-issued Trust Plane credential
+impl FileProvider {
+    /// Latest modification time of the credential files, which moves on
+    /// rotation (now, if the filesystem does not report it)
+    fn modified_at(&self) -> DateTime<Utc> {
+        [
+            &self.issuer_key_path,
+            &self.cat_key_path,
+            &self.did_doc_path,
+            &self.credential_path,
+        ]
+        .into_iter()
+        .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+        .map_or_else(Utc::now, DateTime::from)
+    }
+
+    /// Flag a key whose kid date is more than `max_months` before `today`.
+    ///
+    /// Kids without a date suffix are not checked.
+    fn check_key_age(
+        &self,
+        name: &str,
+        key: &KeyPair,
+        max_months: u32,
+        today: (i32, u32),
+    ) -> Result<()> {
+        let Some((year, month)) = kid_month(key.kid()) else {
+            return Ok(());
+        };
+        let age = (today.0 * 12 + today.1 as i32) - (year * 12 + month as i32);
+        if age <= max_months as i32 {
+            return Ok(());
+        }
+        if self.fail_on_stale_keys {
+            return Err(Error::Config(format!(
+                "{} key {} is {} months old (limit {}); rotate it",
+                name,
+                key.kid(),
+                age,
+                max_months
+            )));
+        }
+        warn!(
+            kid = %key.kid(),
+            age_months = age,
+            max_months,
+            "{} key is older than the configured maximum - rotation may have been missed",
+            name
+        );
+        Ok(())
+    }
+}
+
+/// Year and month from a kid's `-YYYYMM` fragment suffix
+fn kid_month(kid: &str) -> Option<(i32, u32)> {
+    let (_, fragment) = kid.rsplit_once('#')?;
+    let (_, suffix) = fragment.rsplit_once('-')?;
+    if suffix.len() != 6 || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year = suffix[..4].parse().ok()?;
+    let month = suffix[4..].parse().ok().filter(|m| (1..=12).contains(m))?;
+    Some((year, month))
+}
+
+/// Load a private key file, JWK or PKCS#8 PEM.
+///
+/// PEM is detected by `.pem` extension or PEM armor. PEM carries no key ID,
+/// so the kid is taken from the DID document verification method with the
+/// same public key.
+fn load_key(path: &Path, name: &str, did_document: &DidDocument) -> Result<KeyPair> {
+    let contents = std::fs::read_to_string(path)?;
+    let is_pem = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pem"))
+        || contents.trim_start().starts_with("-----BEGIN");
+
+    if !is_pem {
+        let jwk: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| Error::Invalid(format!("Invalid {} key JSON: {}", name, e)))?;
+        return KeyPair::from_jwk(&jwk);
+    }
+
+    let key = KeyPair::from_pkcs8_pem("", &contents)?;
+    let public_key = key.public_key_bytes();
+    let kid = did_document
+        .verification_method
+        .iter()
+        .find(|m| m.public_key_bytes().is_ok_and(|k| k == public_key))
+        .map(|m| m.id.clone())
+        .ok_or_else(|| {
+            Error::Crypto(format!(
+                "{} key {} does not match any DID document verification method",
+                name,
+                path.display()
+            ))
+        })?;
+    KeyPair::from_pkcs8_pem(kid, &contents)
+}
+
+/// Load the credential served in `trustplane.json`, rejecting files that
+/// are not UTF-8 JSON or lack a required credential field
+fn load_credential(path: &Path) -> Result<serde_json::Value> {
+    let contents = String::from_utf8(std::fs::read(path)?).map_err(|e| {
+        Error::Invalid(format!("Credential {} is not valid UTF-8: {}", path.display(), e))
+    })?;
+    let credential: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+        Error::Invalid(format!("Invalid credential JSON in {}: {}", path.display(), e))
+    })?;
+    check_credential(&credential)?;
+    Ok(credential)
+}
+
+/// Check the structure of a verifiable credential: `@context`, `type`,
+/// `issuer` and `credentialSubject` must be present and well-formed
+fn check_credential(credential: &serde_json::Value) -> Result<()> {
+    use serde_json::Value;
+
+    let invalid = |field: &str, reason: &str| {
+        Err(Error::Invalid(format!("credential field {} {}", field, reason)))
+    };
+    let Some(credential) = credential.as_object() else {
+        return invalid("(root)", "must be a JSON object");
+    };
+    let non_empty_strings = |value: &Value| match value {
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => {
+            !items.is_empty() && items.iter().all(|i| i.as_str().is_some_and(|s| !s.is_empty()))
+        }
+        _ => false,
+    };
+
+    match credential.get("@context") {
+        None => return invalid("@context", "is required"),
+        Some(context) if !non_empty_strings(context) => {
+            return invalid("@context", "must be a URI or a non-empty array of URIs");
+        }
+        Some(_) => {}
+    }
+    match credential.get("type") {
+        None => return invalid("type", "is required"),
+        Some(types) if !non_empty_strings(types) => {
+            return invalid("type", "must be a string or a non-empty array of strings");
+        }
+        Some(_) => {}
+    }
+    match credential.get("issuer") {
+        None => return invalid("issuer", "is required"),
+        Some(Value::String(issuer)) if !issuer.is_empty() => {}
+        Some(Value::Object(issuer)) if issuer.get("id").is_some_and(Value::is_string) => {}
+        Some(_) => return invalid("issuer", "must be a URI or an object with an id"),
+    }
+    match credential.get("credentialSubject") {
+        None => return invalid("credentialSubject", "is required"),
+        Some(Value::Object(_)) => {}
+        Some(_) => return invalid("credentialSubject", "must be a JSON object"),
+    }
+    Ok(())
+}
+
+/// Check that each key is published in the DID document under its kid.
+///
+/// A key missing from the document, or published with different key
+/// material, would sign PCAs and credentials nobody can verify.
+fn check_published_keys(did_document: &DidDocument, keys: &[(&str, &KeyPair)]) -> Result<()> {
+    let mut mismatches = Vec::new();
+    for (name, key) in keys {
+        match did_document.verification_method(key.kid()) {
+            None => mismatches.push(format!(
+                "{} key {} is not a verification method of {}",
+                name,
+                key.kid(),
+                did_document.id
+            )),
+            Some(method) if method.public_key_bytes()? != key.public_key_bytes() => {
+                mismatches.push(format!(
+                    "{} key {} does not match the published public key",
+                    name,
+                    key.kid()
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    if !mismatches.is_empty() {
+        return Err(Error::Invalid(format!(
+            "Keys do not match DID document: {}",
+            mismatches.join("; ")
+        )));
+    }
+    Ok(())
+}
+
+/// `credentialSubject` fields set by the Trust Plane itself
+const RESERVED_SUBJECT_FIELDS: &[&str] = &["id", "type", "organization"];
+
+/// Check extra `credentialSubject` fields: none may override a reserved
+/// field, and names must be non-empty
+pub fn check_subject_fields(fields: &serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    for name in fields.keys() {
+        if name.trim().is_empty() {
+            return Err(Error::Config("credential subject field names cannot be empty".into()));
+        }
+        if RESERVED_SUBJECT_FIELDS.contains(&name.as_str()) || name.starts_with('@') {
+            return Err(Error::Config(format!("credential subject field {} is reserved", name)));
+        }
+    }
+    Ok(())
+}
+
+/// Create self-issued Trust Plane credential, issued now unless
+/// `issuance_date` is given.
+///
+/// A fixed date also derives the credential ID from the DID and date, so the
+/// credential is byte-stable across runs.
 fn create_self_credential(
     did: &str,
     organization: &str,
-    issuer_key: &KeyPair,
+    issuance_date: Option<DateTime<Utc>>,
+) -> serde_json::Value {
+    let (issued_at, id) = match issuance_date {
+        Some(date) => {
+            let digest = Sha256::new()
+                .chain_update(did.as_bytes())
+                .chain_update([0])
+                .chain_update(date.to_rfc3339().as_bytes())
+                .finalize();
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&digest[..16]);
+            (date, uuid::Builder::from_random_bytes(bytes).into_uuid())
+        }
+        None => (Utc::now(), uuid::Uuid::new_v4()),
+    };
+    let credential_id = format!("urn:uuid:{}", id);
+    self_credential(did, organization, &credential_id, &issued_at.to_rfc3339())
+}
+
+/// Self-issued credential with a given ID and issuance date
+pub(super) fn self_credential(
+    did: &str,
+    organization: &str,
+    credential_id: &str,
+    issuance_date: &str,
 ) -> serde_json::Value {
-    let now = Utc::now().to_rfc3339();
-    let credential_id = format!("urn:uuid:{}", uuid::Uuid::new_v4());
-    
     // Note: In production, this should be properly signed
     // For now, we create the structure without cryptographic proof
     serde_json::json!({
@@ -177,11 +486,261 @@ fn create_self_credential(
         "id": credential_id,
         "type": ["VerifiableCredential", "TrustPlaneCredential"],
         "issuer": did,
-        "issuanceDate": now,
+        "issuanceDate": issuance_date,
         "credentialSubject": {
             "id": did,
             "type": "TrustPlane",
             "organization": organization
         }
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::testing::{test_keys, TEST_DID};
+    use crate::credentials::{CredentialsManager, SignatureStatus};
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, spki::der::pem::LineEnding};
+
+    fn to_pem(key: &KeyPair) -> String {
+        ed25519_dalek::SigningKey::from_bytes(&key.private_key_bytes())
+            .to_pkcs8_pem(LineEnding::LF)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_file_provider_pem_and_jwk_keys() {
+        let dir = std::env::temp_dir().join(format!("permguard-keys-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let did = TEST_DID;
+        let (issuer_key, cat_key) = test_keys(did);
+        let did_document = DidDocument::new(did, &issuer_key, &cat_key);
+
+        let write = |name: &str, contents: String| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let did_doc_path = write("did.json", did_document.to_json().to_string());
+        let credential_path = write(
+            "credential.json",
+            create_self_credential(did, "Acme", None).to_string(),
+        );
+        let issuer_jwk = write("issuer.jwk", issuer_key.private_jwk().to_string());
+        let cat_jwk = write("cat.jwk", cat_key.private_jwk().to_string());
+        let issuer_pem = write("issuer.pem", to_pem(&issuer_key));
+        // PEM armor is detected without the extension
+        let cat_pem = write("cat.key", to_pem(&cat_key));
+
+        let provider = |issuer_key_path: &PathBuf, cat_key_path: &PathBuf| FileProvider {
+            issuer_key_path: issuer_key_path.clone(),
+            cat_key_path: cat_key_path.clone(),
+            did_doc_path: did_doc_path.clone(),
+            credential_path: credential_path.clone(),
+            allow_shared_keys: false,
+            warn_key_age_months: Some(12),
+            fail_on_stale_keys: true,
+        };
+        let from_jwk = provider(&issuer_jwk, &cat_jwk).load().unwrap();
+        let from_pem = provider(&issuer_pem, &cat_pem).load().unwrap();
+
+        // Shared issuer/CAT key material is refused unless explicitly allowed
+        let shared = provider(&issuer_jwk, &issuer_pem);
+        assert!(matches!(shared.load(), Err(Error::Config(_))));
+        let shared = FileProvider {
+            allow_shared_keys: true,
+            ..shared
+        };
+        assert!(shared.load().is_ok());
+
+        for (a, b) in [
+            (&from_jwk.issuer_key, &from_pem.issuer_key),
+            (&from_jwk.cat_key, &from_pem.cat_key),
+        ] {
+            assert_eq!(a.kid(), b.kid());
+            assert_eq!(a.public_key_bytes(), b.public_key_bytes());
+            assert_eq!(a.public_jwk(), b.public_jwk());
+        }
+        assert_eq!(from_pem.cat_key.kid(), cat_key.kid());
+
+        // Keys must be published in the DID document under their kid
+        let rogue = KeyPair::from_bytes(cat_key.kid(), &[9; 32]).unwrap();
+        let rogue_jwk = write("rogue.jwk", rogue.private_jwk().to_string());
+        let unknown = KeyPair::from_bytes(format!("{}#old-issuer-key", did), &[8; 32]).unwrap();
+        let unknown_jwk = write("unknown.jwk", unknown.private_jwk().to_string());
+        let Err(Error::Invalid(msg)) = provider(&unknown_jwk, &rogue_jwk).load() else {
+            panic!("mismatched keys were accepted");
+        };
+        assert!(msg.contains("issuer key did:web:") && msg.contains("#old-issuer-key"), "{}", msg);
+        assert!(msg.contains("CAT key") && msg.contains("published public key"), "{}", msg);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_credential_structure() {
+        let credential = create_self_credential(TEST_DID, "Acme", None);
+        assert!(check_credential(&credential).is_ok());
+
+        for field in ["@context", "type", "issuer", "credentialSubject"] {
+            let mut missing = credential.clone();
+            missing.as_object_mut().unwrap().remove(field);
+            let Err(Error::Invalid(msg)) = check_credential(&missing) else {
+                panic!("credential without {} was accepted", field);
+            };
+            assert_eq!(msg, format!("credential field {} is required", field));
+        }
+
+        // Present but malformed
+        for (field, value) in [
+            ("@context", serde_json::json!([])),
+            ("type", serde_json::json!(42)),
+            ("issuer", serde_json::json!({ "name": "Acme" })),
+            ("credentialSubject", serde_json::json!("did:web:example.com")),
+        ] {
+            let mut malformed = credential.clone();
+            malformed[field] = value;
+            let err = check_credential(&malformed).unwrap_err();
+            assert!(err.to_string().contains(field), "{}", err);
+        }
+        let issuer_object = serde_json::json!({ "id": TEST_DID, "name": "Acme" });
+        let mut with_object = credential.clone();
+        with_object["issuer"] = issuer_object;
+        assert!(check_credential(&with_object).is_ok());
+        assert!(check_credential(&serde_json::json!([credential])).is_err());
+
+        // Files that are not UTF-8 are reported as such
+        let path = std::env::temp_dir()
+            .join(format!("permguard-credential-{}.json", std::process::id()));
+        std::fs::write(&path, b"{\"issuer\": \"\xff\"}").unwrap();
+        let err = load_credential(&path).unwrap_err();
+        assert!(err.to_string().contains("not valid UTF-8"), "{}", err);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_key_age_from_kid() {
+        assert_eq!(kid_month("did:web:example.com#cat-key-202601"), Some((2026, 1)));
+        assert_eq!(kid_month("did:web:example.com#cat-key"), None);
+        assert_eq!(kid_month("did:web:example.com#cat-key-202613"), None);
+        assert_eq!(kid_month("did:web:example.com:2024-202401"), None);
+
+        let provider = |fail_on_stale_keys| FileProvider {
+            issuer_key_path: PathBuf::new(),
+            cat_key_path: PathBuf::new(),
+            did_doc_path: PathBuf::new(),
+            credential_path: PathBuf::new(),
+            allow_shared_keys: false,
+            warn_key_age_months: Some(12),
+            fail_on_stale_keys,
+        };
+        let key = KeyPair::from_bytes(format!("{}#cat-key-202501", TEST_DID), &[3; 32]).unwrap();
+        let today = (2026, 1);
+
+        assert!(provider(true).check_key_age("CAT", &key, 12, today).is_ok());
+        assert!(provider(false).check_key_age("CAT", &key, 11, today).is_ok());
+        let err = provider(true).check_key_age("CAT", &key, 11, today).unwrap_err();
+        assert!(matches!(err, Error::Config(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_fixed_issuance_date() {
+        let provider = |issuance_date| InMemoryProvider {
+            did: TEST_DID.to_string(),
+            organization: "Acme".to_string(),
+            key_format: KeyFormat::Jwk,
+            issuance_date,
+            subject_fields: Default::default(),
+        };
+        let date: DateTime<Utc> = "2025-06-01T00:00:00Z".parse().unwrap();
+        let a = provider(Some(date)).load().unwrap();
+        let b = provider(Some(date)).load().unwrap();
+        assert_eq!(a.credential, b.credential);
+        assert_eq!(a.credential["issuanceDate"], "2025-06-01T00:00:00+00:00");
+        assert!(a.cat_key.kid().ends_with("-202506"));
+        assert_eq!(a.issued_at, date);
+
+        // Without one, each run gets a fresh credential ID
+        let c = provider(None).load().unwrap();
+        let d = provider(None).load().unwrap();
+        assert_ne!(c.credential["id"], d.credential["id"]);
+    }
+
+    #[test]
+    fn test_in_memory_rotation() {
+        let provider = InMemoryProvider {
+            did: TEST_DID.to_string(),
+            organization: "Acme".to_string(),
+            key_format: KeyFormat::Jwk,
+            issuance_date: None,
+            subject_fields: Default::default(),
+        };
+        let manager = CredentialsManager::with_provider(Box::new(provider)).unwrap();
+        let first = manager.current();
+
+        let second = manager.rotate().unwrap();
+        assert_ne!(second.cat_key.kid(), first.cat_key.kid());
+        assert_ne!(second.cat_key.public_key_bytes(), first.cat_key.public_key_bytes());
+        assert!(kid_month(second.cat_key.kid()).is_some());
+        // Replaced keys stay verifiable for one rotation
+        let document = &second.did_document;
+        assert_eq!(document.verification_method.len(), 4);
+        assert!(document.verification_method(first.cat_key.kid()).is_some());
+        assert!(document.assertion_method.iter().any(|kid| kid == first.issuer_key.kid()));
+
+        // Signatures by the replaced keys verify, flagged as retired
+        let old_signature = first.issuer_key.sign(b"credential");
+        let status = second.verify(first.issuer_key.kid(), b"credential", &old_signature);
+        assert_eq!(status.unwrap(), SignatureStatus::SignedByRetiredKey);
+        assert_eq!(SignatureStatus::SignedByRetiredKey.code(), "signed_by_retired_key");
+        let signature = second.cat_key.sign(b"pca");
+        let status = second.verify(second.cat_key.kid(), b"pca", &signature).unwrap();
+        assert_eq!(status, SignatureStatus::Current);
+        assert!(second.verify(second.cat_key.kid(), b"other", &signature).is_err());
+
+        // PCAs signed by the replaced CAT key verify, the replaced issuer key never signs them
+        assert_eq!(second.retired_cat_kid.as_deref(), Some(first.cat_key.kid()));
+        let context = crate::credentials::DEFAULT_PCA_SIGNING_CONTEXT;
+        let old_pca = first.sign_pca(context, b"pca");
+        let status = second.verify_pca(first.cat_key.kid(), context, b"pca", &old_pca);
+        assert_eq!(status.unwrap(), SignatureStatus::SignedByRetiredKey);
+        assert!(second.verify_pca(first.issuer_key.kid(), context, b"pca", &old_pca).is_err());
+
+        let third = manager.rotate().unwrap();
+        assert_eq!(third.did_document.verification_method.len(), 4);
+        assert!(third.did_document.verification_method(second.cat_key.kid()).is_some());
+        assert!(third.did_document.verification_method(first.cat_key.kid()).is_none());
+        assert!(third.verify(first.issuer_key.kid(), b"credential", &old_signature).is_err());
+        assert_eq!(manager.current().cat_key.kid(), third.cat_key.kid());
+
+        // Credentials built without a provider cannot rotate
+        let fixed = CredentialsManager::new((*first).clone());
+        assert!(matches!(fixed.rotate(), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_subject_fields() {
+        let fields = |json: serde_json::Value| json.as_object().unwrap().clone();
+        let provider = InMemoryProvider {
+            did: TEST_DID.to_string(),
+            organization: "Acme".to_string(),
+            key_format: KeyFormat::Jwk,
+            issuance_date: None,
+            subject_fields: fields(serde_json::json!({
+                "jurisdiction": "IT",
+                "contact": { "email": "security@acme.example.com" },
+            })),
+        };
+        let subject = &provider.load().unwrap().credential["credentialSubject"];
+        assert_eq!(subject["jurisdiction"], "IT");
+        assert_eq!(subject["contact"]["email"], "security@acme.example.com");
+        assert_eq!(subject["organization"], "Acme");
+
+        for reserved in ["id", "organization", "@context", " "] {
+            let fields = fields(serde_json::json!({ reserved: "x" }));
+            assert!(matches!(check_subject_fields(&fields), Err(Error::Config(_))));
+        }
+    }
+}
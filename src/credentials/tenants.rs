@@ -0,0 +1,163 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Multi-tenant credentials.
+//!
+//! One instance may host several DIDs, each with its own keys. Discovery
+//! requests select a tenant by the `did:web` host and path they were sent
+//! to; anything that matches no tenant is served by the default one.
+
+use super::{CredentialsManager, DidWebLocation, WELL_KNOWN_DID_PATH};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Hosted tenant
+struct Tenant {
+    credentials: Arc<CredentialsManager>,
+    /// Where the tenant's DID document is published, for `did:web` DIDs
+    location: Option<DidWebLocation>,
+}
+
+/// Credentials of every DID served by this instance, keyed by DID
+pub struct TenantCredentials {
+    default_did: String,
+    tenants: HashMap<String, Tenant>,
+}
+
+impl TenantCredentials {
+    /// Single-tenant set around the default credentials
+    pub fn new(default: Arc<CredentialsManager>) -> Self {
+        let default_did = default.current().did.clone();
+        let mut tenants = HashMap::new();
+        tenants.insert(default_did.clone(), Tenant::new(default));
+        Self {
+            default_did,
+            tenants,
+        }
+    }
+
+    /// Host another DID
+    pub fn insert(&mut self, credentials: Arc<CredentialsManager>) -> Result<()> {
+        let did = credentials.current().did.clone();
+        if self.tenants.contains_key(&did) {
+            return Err(Error::Config(format!("DID {} is hosted twice", did)));
+        }
+        self.tenants.insert(did, Tenant::new(credentials));
+        Ok(())
+    }
+
+    /// Credentials of the default tenant
+    pub fn default_tenant(&self) -> &Arc<CredentialsManager> {
+        &self.tenants[&self.default_did].credentials
+    }
+
+    /// Credentials of a hosted DID
+    pub fn get(&self, did: &str) -> Option<&Arc<CredentialsManager>> {
+        self.tenants.get(did).map(|t| &t.credentials)
+    }
+
+    /// Hosted DIDs, sorted
+    pub fn dids(&self) -> Vec<&str> {
+        let mut dids: Vec<&str> = self.tenants.keys().map(String::as_str).collect();
+        dids.sort();
+        dids
+    }
+
+    /// Tenant whose DID document is published at `host` + `path`.
+    ///
+    /// Hosts compare case-insensitively; with no match (including requests
+    /// without a `Host`), the default tenant answers.
+    pub fn for_request(&self, host: Option<&str>, path: &str) -> &Arc<CredentialsManager> {
+        host.and_then(|host| {
+            self.tenants.values().find(|t| {
+                t.location.as_ref().is_some_and(|l| {
+                    l.authority.eq_ignore_ascii_case(host) && l.path == path
+                })
+            })
+        })
+        .map_or_else(|| self.default_tenant(), |t| &t.credentials)
+    }
+
+    /// Every path a hosted DID document is served at, sorted
+    pub fn document_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .tenants
+            .values()
+            .map(|t| {
+                t.location
+                    .as_ref()
+                    .map_or_else(|| WELL_KNOWN_DID_PATH.to_string(), |l| l.path.clone())
+            })
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+impl Tenant {
+    fn new(credentials: Arc<CredentialsManager>) -> Self {
+        let location = DidWebLocation::parse(&credentials.current().did).ok();
+        Self {
+            credentials,
+            location,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::testing::{test_credentials_manager, TEST_DID};
+
+    #[test]
+    fn test_tenant_selection() {
+        let mut tenants = TenantCredentials::new(test_credentials_manager(TEST_DID));
+        tenants
+            .insert(test_credentials_manager("did:web:acme.example.com"))
+            .unwrap();
+        tenants
+            .insert(test_credentials_manager("did:web:example.com%3A8443:tenant:globex"))
+            .unwrap();
+        assert!(tenants.insert(test_credentials_manager(TEST_DID)).is_err());
+
+        let did = |host: Option<&str>, path: &str| {
+            tenants.for_request(host, path).current().did.clone()
+        };
+        assert_eq!(
+            did(Some("ACME.example.com"), WELL_KNOWN_DID_PATH),
+            "did:web:acme.example.com"
+        );
+        assert_eq!(
+            did(Some("example.com:8443"), "/tenant/globex/did.json"),
+            "did:web:example.com%3A8443:tenant:globex"
+        );
+
+        // Unknown hosts, wrong paths and host-less requests get the default
+        assert_eq!(did(Some("127.0.0.1:9000"), WELL_KNOWN_DID_PATH), TEST_DID);
+        assert_eq!(did(Some("example.com"), "/tenant/globex/did.json"), TEST_DID);
+        assert_eq!(did(None, WELL_KNOWN_DID_PATH), TEST_DID);
+
+        assert_eq!(
+            tenants.document_paths(),
+            [WELL_KNOWN_DID_PATH, "/tenant/globex/did.json"]
+        );
+        assert_eq!(tenants.dids().len(), 3);
+        assert!(tenants.get("did:web:acme.example.com").is_some());
+        assert_eq!(tenants.default_tenant().current().did, TEST_DID);
+    }
+}
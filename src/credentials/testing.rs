@@ -0,0 +1,81 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Deterministic credentials for tests.
+//!
+//! Keys are derived from fixed seeds and the self-credential has a fixed ID
+//! and issuance date, so signatures and serialized documents are identical
+//! across runs.
+
+use super::provider::self_credential;
+use super::{CredentialsManager, DidDocument, KeyPair, TrustPlaneCredentials};
+use std::sync::Arc;
+
+/// DID used by most tests
+pub(crate) const TEST_DID: &str = "did:web:trustplane.example.com";
+
+/// Organization of the test credentials
+pub(crate) const TEST_ORGANIZATION: &str = "Acme";
+
+const ISSUER_KEY_SEED: [u8; 32] = [1; 32];
+const CAT_KEY_SEED: [u8; 32] = [2; 32];
+
+/// Issuer and CAT keys for `did`, always the same key material
+pub(crate) fn test_keys(did: &str) -> (KeyPair, KeyPair) {
+    let issuer_key = KeyPair::from_bytes(format!("{}#issuer-key", did), &ISSUER_KEY_SEED).unwrap();
+    let cat_key = KeyPair::from_bytes(format!("{}#cat-key", did), &CAT_KEY_SEED).unwrap();
+    (issuer_key, cat_key)
+}
+
+/// Credentials for `did` with deterministic keys and self-credential
+pub(crate) fn test_credentials(did: &str) -> TrustPlaneCredentials {
+    let (issuer_key, cat_key) = test_keys(did);
+    TrustPlaneCredentials {
+        did: did.to_string(),
+        organization: TEST_ORGANIZATION.to_string(),
+        did_document: DidDocument::new(did, &issuer_key, &cat_key),
+        credential: self_credential(
+            did,
+            TEST_ORGANIZATION,
+            "urn:uuid:00000000-0000-0000-0000-000000000000",
+            "2026-01-01T00:00:00+00:00",
+        ),
+        issuer_key,
+        cat_key,
+        issued_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+        retired_cat_kid: None,
+    }
+}
+
+/// Credentials manager over [`test_credentials`]
+pub(crate) fn test_credentials_manager(did: &str) -> Arc<CredentialsManager> {
+    Arc::new(CredentialsManager::new(test_credentials(did)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_are_deterministic() {
+        let a = test_credentials(TEST_DID);
+        let b = test_credentials(TEST_DID);
+        a.self_test().unwrap();
+        assert_eq!(a.did_document.to_json(), b.did_document.to_json());
+        assert_eq!(a.credential, b.credential);
+        assert_eq!(a.cat_key.sign(b"pca"), b.cat_key.sign(b"pca"));
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! gRPC deadline propagation.
+//!
+//! Clients send their deadline as the `grpc-timeout` header. Work done on
+//! their behalf is bounded by it, so a slow upstream fails fast with
+//! `DEADLINE_EXCEEDED` instead of piling up requests nobody waits for.
+
+use std::future::Future;
+use std::time::Duration;
+use tonic::metadata::MetadataMap;
+use tonic::Status;
+
+const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Remaining budget from the request's `grpc-timeout` header, if any
+pub(crate) fn grpc_timeout(metadata: &MetadataMap) -> Option<Duration> {
+    parse_grpc_timeout(metadata.get(GRPC_TIMEOUT_HEADER)?.to_str().ok()?)
+}
+
+/// Run `work` within the client's budget
+pub(crate) async fn within<T>(
+    timeout: Option<Duration>,
+    work: impl Future<Output = T>,
+) -> Result<T, Status> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, work).await.map_err(|_| {
+            Status::deadline_exceeded(format!("deadline of {:?} exceeded", timeout))
+        }),
+        None => Ok(work.await),
+    }
+}
+
+/// Parse `TimeoutValue TimeoutUnit` (at most 8 digits, unit one of `HMSmun`)
+fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("250m"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_grpc_timeout("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("123456789S"), None);
+        assert_eq!(parse_grpc_timeout("10x"), None);
+
+        let slow = tokio::time::sleep(Duration::from_secs(5));
+        let err = within(Some(Duration::from_millis(10)), slow).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::DeadlineExceeded);
+        assert_eq!(within(None, async { 7 }).await.unwrap(), 7);
+    }
+}
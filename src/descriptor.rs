@@ -0,0 +1,151 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Staleness check of the reflection descriptor.
+//!
+//! gRPC reflection serves `proto/descriptor.bin`, while requests are routed
+//! by the generated service code. Both come from the same `.proto` files,
+//! but a descriptor left over from before a proto change makes reflection
+//! advertise methods the server does not route, or hide ones it does. At
+//! startup the method paths of both are compared and any drift is logged.
+
+use crate::error::{Error, Result};
+use prost::Message;
+use prost_types::FileDescriptorSet;
+use std::collections::BTreeSet;
+use tracing::{debug, warn};
+
+/// Generated service code, scanned for the method paths it routes
+const GENERATED_SOURCES: &[&str] = &[
+    include_str!("proto/permguard.trustplane.cat.v1.rs"),
+    include_str!("proto/permguard.trustplane.bridge.v1.rs"),
+    include_str!("proto/permguard.trustplane.bridge_admin.v1.rs"),
+    include_str!("proto/permguard.trustplane.info.v1.rs"),
+];
+
+/// Prefix shared by the method paths of every Trust Plane service
+const METHOD_PATH_PREFIX: &str = "\"/permguard.trustplane.";
+
+/// Method paths on one side only
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Drift {
+    /// Routed by the service code, missing from the descriptor
+    undescribed: Vec<String>,
+    /// In the descriptor, not routed by the service code
+    unrouted: Vec<String>,
+}
+
+/// Warn when `descriptor` does not describe the compiled services
+pub(crate) fn warn_if_stale(descriptor: &[u8]) {
+    match drift(descriptor, GENERATED_SOURCES) {
+        Ok(drift) if drift == Drift::default() => {
+            debug!("gRPC reflection descriptor matches the compiled services");
+        }
+        Ok(drift) => warn!(
+            undescribed = ?drift.undescribed,
+            unrouted = ?drift.unrouted,
+            "gRPC reflection descriptor is stale: rebuild to regenerate proto/descriptor.bin"
+        ),
+        Err(e) => warn!(error = %e, "Could not check the gRPC reflection descriptor"),
+    }
+}
+
+fn drift(descriptor: &[u8], sources: &[&str]) -> Result<Drift> {
+    let described = described_methods(descriptor)?;
+    let routed = routed_methods(sources);
+    Ok(Drift {
+        undescribed: routed.difference(&described).cloned().collect(),
+        unrouted: described.difference(&routed).cloned().collect(),
+    })
+}
+
+/// `/package.Service/Method` paths in an encoded file descriptor set
+fn described_methods(descriptor: &[u8]) -> Result<BTreeSet<String>> {
+    let set = FileDescriptorSet::decode(descriptor)
+        .map_err(|e| Error::Internal(format!("Invalid file descriptor set: {}", e)))?;
+    let mut methods = BTreeSet::new();
+    for file in &set.file {
+        for service in &file.service {
+            for method in &service.method {
+                methods.insert(format!(
+                    "/{}.{}/{}",
+                    file.package(),
+                    service.name(),
+                    method.name()
+                ));
+            }
+        }
+    }
+    Ok(methods)
+}
+
+/// Method path literals in generated service code
+fn routed_methods(sources: &[&str]) -> BTreeSet<String> {
+    let mut methods = BTreeSet::new();
+    for source in sources {
+        for (start, _) in source.match_indices(METHOD_PATH_PREFIX) {
+            let path = &source[start + 1..];
+            if let Some(end) = path.find('"') {
+                methods.insert(path[..end].to_string());
+            }
+        }
+    }
+    methods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{FileDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto};
+
+    fn descriptor(methods: &[&str]) -> Vec<u8> {
+        let service = ServiceDescriptorProto {
+            name: Some("CatService".into()),
+            method: methods
+                .iter()
+                .map(|name| MethodDescriptorProto {
+                    name: Some(name.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            package: Some("permguard.trustplane.cat.v1".into()),
+            service: vec![service],
+            ..Default::default()
+        };
+        FileDescriptorSet { file: vec![file] }.encode_to_vec()
+    }
+
+    #[test]
+    fn test_descriptor_drift() {
+        let source = r#"
+            "/permguard.trustplane.cat.v1.CatService/Transition" => {
+            "/permguard.trustplane.cat.v1.CatService/TransitionStream",
+        "#;
+
+        let fresh = descriptor(&["Transition", "TransitionStream"]);
+        assert_eq!(drift(&fresh, &[source]).unwrap(), Drift::default());
+
+        let stale = descriptor(&["Transition", "Revoke"]);
+        let found = drift(&stale, &[source]).unwrap();
+        assert_eq!(found.undescribed, ["/permguard.trustplane.cat.v1.CatService/TransitionStream"]);
+        assert_eq!(found.unrouted, ["/permguard.trustplane.cat.v1.CatService/Revoke"]);
+
+        assert!(drift(b"\xff\xff", &[source]).is_err());
+    }
+}
@@ -16,7 +16,16 @@
 
 //! Error types.
 
+use std::collections::HashMap;
 use std::fmt;
+use tonic_types::{ErrorDetails, StatusExt};
+
+/// `google.rpc.ErrorInfo` domain for Trust Plane errors
+const ERROR_DOMAIN: &str = "trustplane.permguard.com";
+
+/// Where progress on the features behind [`Error::NotImplemented`] is tracked
+pub const NOT_IMPLEMENTED_TRACKING_URL: &str =
+    "https://github.com/permguard/permguard-trustplane/issues";
 
 /// Result type alias
 pub type Result<T> = std::result::Result<T, Error>;
@@ -30,9 +39,76 @@ pub enum Error {
     /// Resource not found
     NotFound(String),
 
+    /// Exchange named a bridge that does not exist
+    BridgeNotFound(String),
+
+    /// Exchange named a bridge that exists but is disabled
+    BridgeDisabled(String),
+
+    /// Exchange named a bridge that was removed and awaits deletion
+    BridgeDecommissioning(String),
+
     /// Invalid input
     Invalid(String),
 
+    /// Invalid request field
+    InvalidField { field: String, reason: String },
+
+    /// Request refused by policy
+    Denied(String),
+
+    /// Token signed with an algorithm the bridge does not accept
+    AlgorithmNotAllowed(String),
+
+    /// Encrypted credential could not be decrypted
+    DecryptFailed(String),
+
+    /// Credential exceeds configured size limits
+    CredentialTooLarge(String),
+
+    /// Token was issued longer ago than the bridge accepts
+    TokenTooOld(String),
+
+    /// Token has no `aud` claim but the bridge requires one
+    MissingAudience(String),
+
+    /// Token `nonce` is missing or differs from the nonce the client presented
+    NonceMismatch(String),
+
+    /// PCA₀ would lack an attribute the bridge requires at issuance
+    MissingRequiredAttribute(String),
+
+    /// Proof-of-possession is missing, or not signed by the token's
+    /// confirmation key for this exchange
+    PopVerificationFailed(String),
+
+    /// Subject is on the revocation list
+    SubjectRevoked(String),
+
+    /// PCA is bound to a different audience than the caller expects
+    AudienceMismatch(String),
+
+    /// PCA exceeds configured size limits
+    PcaTooLarge(String),
+
+    /// Successor sequence does not follow its predecessor's
+    SequenceRollback(String),
+
+    /// PCA transport encoding (base64) could not be decoded
+    InvalidBase64(String),
+
+    /// PCA bytes are not well-formed CBOR
+    InvalidCbor(String),
+
+    /// PCA is well-formed CBOR but not a valid PCA
+    InvalidPcaStructure(String),
+
+    /// Subject exceeded its transition quota
+    QuotaExceeded(String),
+
+    /// Too many concurrent requests of this kind
+    Overloaded(String),
+
     /// Crypto error
     Crypto(String),
 
@@ -42,6 +118,13 @@ pub enum Error {
     /// Transport error
     Transport(String),
 
+    /// Request refused by a peer Trust Plane, with the peer's error code
+    Remote { code: String, message: String },
+
+    /// Feature not implemented yet, named by its stable feature id
+    /// (`cat_transition`, `jwt_exchange`, `jwe_decryption`)
+    NotImplemented(String),
+
     /// Internal error
     Internal(String),
 }
@@ -51,15 +134,128 @@ impl fmt::Display for Error {
         match self {
             Error::Config(msg) => write!(f, "configuration error: {}", msg),
             Error::NotFound(id) => write!(f, "not found: {}", id),
+            Error::BridgeNotFound(id) => write!(f, "bridge_not_found: {}", id),
+            Error::BridgeDisabled(msg) => write!(f, "bridge_disabled: {}", msg),
+            Error::BridgeDecommissioning(msg) => write!(f, "bridge_decommissioning: {}", msg),
             Error::Invalid(msg) => write!(f, "invalid: {}", msg),
+            Error::InvalidField { field, reason } => write!(f, "invalid: {}: {}", field, reason),
+            Error::Denied(msg) => write!(f, "denied: {}", msg),
+            Error::AlgorithmNotAllowed(alg) => write!(f, "algorithm_not_allowed: {}", alg),
+            Error::DecryptFailed(msg) => write!(f, "decrypt_failed: {}", msg),
+            Error::CredentialTooLarge(msg) => write!(f, "credential_too_large: {}", msg),
+            Error::TokenTooOld(msg) => write!(f, "token_too_old: {}", msg),
+            Error::MissingAudience(msg) => write!(f, "missing_audience: {}", msg),
+            Error::NonceMismatch(msg) => write!(f, "nonce_mismatch: {}", msg),
+            Error::MissingRequiredAttribute(name) => {
+                write!(f, "missing_required_attribute: {}", name)
+            }
+            Error::PopVerificationFailed(msg) => write!(f, "pop_verification_failed: {}", msg),
+            Error::SubjectRevoked(subject) => write!(f, "subject_revoked: {}", subject),
+            Error::AudienceMismatch(msg) => write!(f, "audience_mismatch: {}", msg),
+            Error::PcaTooLarge(msg) => write!(f, "pca_too_large: {}", msg),
+            Error::SequenceRollback(msg) => write!(f, "sequence_rollback: {}", msg),
+            Error::InvalidBase64(msg) => write!(f, "invalid_base64: {}", msg),
+            Error::InvalidCbor(msg) => write!(f, "invalid_cbor: {}", msg),
+            Error::InvalidPcaStructure(msg) => write!(f, "invalid_pca_structure: {}", msg),
+            Error::QuotaExceeded(msg) => write!(f, "quota_exceeded: {}", msg),
+            Error::Overloaded(msg) => write!(f, "overloaded: {}", msg),
             Error::Crypto(msg) => write!(f, "crypto error: {}", msg),
             Error::Io(e) => write!(f, "io error: {}", e),
             Error::Transport(msg) => write!(f, "transport error: {}", msg),
+            Error::Remote { code, message } => write!(f, "remote error: {}: {}", code, message),
+            Error::NotImplemented(feature) => write!(f, "not implemented: {}", feature),
             Error::Internal(msg) => write!(f, "internal error: {}", msg),
         }
     }
 }
 
+impl Error {
+    /// Invalid request field
+    pub fn invalid_field(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Error::InvalidField {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "configuration_error",
+            Error::NotFound(_) => "not_found",
+            Error::BridgeNotFound(_) => "bridge_not_found",
+            Error::BridgeDisabled(_) => "bridge_disabled",
+            Error::BridgeDecommissioning(_) => "bridge_decommissioning",
+            Error::Invalid(_) | Error::InvalidField { .. } => "invalid_argument",
+            Error::Denied(_) => "denied",
+            Error::AlgorithmNotAllowed(_) => "algorithm_not_allowed",
+            Error::DecryptFailed(_) => "decrypt_failed",
+            Error::CredentialTooLarge(_) => "credential_too_large",
+            Error::TokenTooOld(_) => "token_too_old",
+            Error::MissingAudience(_) => "missing_audience",
+            Error::NonceMismatch(_) => "nonce_mismatch",
+            Error::MissingRequiredAttribute(_) => "missing_required_attribute",
+            Error::PopVerificationFailed(_) => "pop_verification_failed",
+            Error::SubjectRevoked(_) => "subject_revoked",
+            Error::AudienceMismatch(_) => "audience_mismatch",
+            Error::PcaTooLarge(_) => "pca_too_large",
+            Error::SequenceRollback(_) => "sequence_rollback",
+            Error::InvalidBase64(_) => "invalid_base64",
+            Error::InvalidCbor(_) => "invalid_cbor",
+            Error::InvalidPcaStructure(_) => "invalid_pca_structure",
+            Error::QuotaExceeded(_) => "quota_exceeded",
+            Error::Overloaded(_) => "overloaded",
+            Error::Crypto(_) => "crypto_error",
+            Error::Io(_) => "io_error",
+            Error::Transport(_) => "transport_error",
+            Error::Remote { .. } => "remote_error",
+            Error::NotImplemented(_) => "not_implemented",
+            Error::Internal(_) => "internal",
+        }
+    }
+
+    /// HTTP status code for this error
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            Error::NotFound(_) | Error::BridgeNotFound(_) => StatusCode::NOT_FOUND,
+            Error::BridgeDisabled(_) => StatusCode::CONFLICT,
+            Error::BridgeDecommissioning(_) => StatusCode::GONE,
+            Error::Invalid(_)
+            | Error::InvalidField { .. }
+            | Error::InvalidBase64(_)
+            | Error::InvalidCbor(_)
+            | Error::InvalidPcaStructure(_) => StatusCode::BAD_REQUEST,
+            Error::CredentialTooLarge(_) | Error::PcaTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::DecryptFailed(_)
+            | Error::AlgorithmNotAllowed(_)
+            | Error::TokenTooOld(_)
+            | Error::MissingAudience(_)
+            | Error::NonceMismatch(_)
+            | Error::MissingRequiredAttribute(_) => StatusCode::BAD_REQUEST,
+            Error::PopVerificationFailed(_) => StatusCode::UNAUTHORIZED,
+            Error::Denied(_)
+            | Error::SubjectRevoked(_)
+            | Error::AudienceMismatch(_)
+            | Error::SequenceRollback(_) => StatusCode::FORBIDDEN,
+            Error::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::Overloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Error::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            Error::Remote { .. } => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Feature id of a [`Error::NotImplemented`], for clients to tell the
+    /// pending features apart while they roll out
+    pub fn feature(&self) -> Option<&str> {
+        match self {
+            Error::NotImplemented(feature) => Some(feature),
+            _ => None,
+        }
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -87,13 +283,56 @@ impl From<tonic_reflection::server::Error> for Error {
     }
 }
 
+/// Carries `google.rpc.ErrorInfo` (reason = [`Error::code`]) and, for
+/// field errors, `google.rpc.BadRequest` in the status details.
 impl From<Error> for tonic::Status {
     fn from(e: Error) -> Self {
-        match e {
-            Error::NotFound(msg) => tonic::Status::not_found(msg),
-            Error::Invalid(msg) => tonic::Status::invalid_argument(msg),
-            Error::Config(msg) => tonic::Status::failed_precondition(msg),
-            _ => tonic::Status::internal(e.to_string()),
+        use tonic::Code;
+
+        let code = match &e {
+            Error::NotFound(_) | Error::BridgeNotFound(_) => Code::NotFound,
+            Error::BridgeDisabled(_) | Error::BridgeDecommissioning(_) => Code::FailedPrecondition,
+            Error::Invalid(_)
+            | Error::InvalidField { .. }
+            | Error::CredentialTooLarge(_)
+            | Error::PcaTooLarge(_)
+            | Error::InvalidBase64(_)
+            | Error::InvalidCbor(_)
+            | Error::InvalidPcaStructure(_)
+            | Error::DecryptFailed(_)
+            | Error::AlgorithmNotAllowed(_)
+            | Error::TokenTooOld(_)
+            | Error::MissingAudience(_)
+            | Error::NonceMismatch(_)
+            | Error::MissingRequiredAttribute(_) => Code::InvalidArgument,
+            Error::PopVerificationFailed(_) => Code::Unauthenticated,
+            Error::Denied(_)
+            | Error::SubjectRevoked(_)
+            | Error::AudienceMismatch(_)
+            | Error::SequenceRollback(_) => Code::PermissionDenied,
+            Error::QuotaExceeded(_) | Error::Overloaded(_) => Code::ResourceExhausted,
+            Error::Config(_) => Code::FailedPrecondition,
+            Error::NotImplemented(_) => Code::Unimplemented,
+            _ => Code::Internal,
+        };
+        let message = match &e {
+            Error::NotFound(msg)
+            | Error::Invalid(msg)
+            | Error::Denied(msg)
+            | Error::Config(msg) => msg.clone(),
+            _ => e.to_string(),
+        };
+
+        let mut metadata = HashMap::new();
+        if let Some(feature) = e.feature() {
+            metadata.insert("feature".to_string(), feature.to_string());
+            metadata.insert("tracking_url".to_string(), NOT_IMPLEMENTED_TRACKING_URL.to_string());
         }
+        let mut details = ErrorDetails::with_error_info(e.code(), ERROR_DOMAIN, metadata);
+        if let Error::InvalidField { field, reason } = &e {
+            details.add_bad_request_violation(field, reason);
+        }
+
+        tonic::Status::with_error_details(code, message, details)
     }
-}
\ No newline at end of file
+}
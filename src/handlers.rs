@@ -18,38 +18,139 @@
 //!
 //! Exposes all services as REST API on the HTTP port.
 
-use crate::bridge::BridgeRegistry;
-use crate::credentials::CredentialsManager;
+use crate::bridge::{BridgeConfig, BridgeRegistry, BridgeServiceImpl, BridgeType, MappingTrace};
+use crate::cat::{CatServiceImpl, SubjectQuota};
+use crate::credentials::{
+    DidDocument, DidWebLocation, KeyFormat, TenantCredentials, TrustPlaneCredentials,
+    WELL_KNOWN_DID_PATH,
+};
+use crate::error::{Error, NOT_IMPLEMENTED_TRACKING_URL};
+use crate::info::{BuildInfo, Capabilities};
+use crate::redact::redact;
+use crate::revocation::RevocationStore;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Response},
     Json,
 };
 use base64::Engine;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
-    pub credentials: Arc<CredentialsManager>,
+    pub credentials: Arc<TenantCredentials>,
     pub registry: Arc<BridgeRegistry>,
+    pub bridge: Arc<BridgeServiceImpl>,
+    pub cat: Arc<CatServiceImpl>,
+    pub revocations: Arc<RevocationStore>,
+    pub quota: Arc<SubjectQuota>,
+    pub capabilities: Arc<Capabilities>,
+    /// OpenAPI document of the enabled routes
+    pub openapi: Arc<utoipa::openapi::OpenApi>,
 }
 
 // ============================================================================
 // Discovery Handlers
 // ============================================================================
 
-/// GET /.well-known/did.json
-pub async fn did_document(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let creds = state.credentials.current();
-    Json(creds.did_document.to_json())
+/// GET /.well-known/did.json, or the `did:web` path of a hosted DID
+#[utoipa::path(
+    get,
+    path = "/.well-known/did.json",
+    tag = "discovery",
+    responses(
+        (status = 200, description = "DID document", body = Object),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag"),
+    )
+)]
+pub async fn did_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    let creds = state.credentials.for_request(host(&headers), uri.path()).current();
+    cacheable_json(&headers, &creds.did_document.to_json(), creds.issued_at)
 }
 
 /// GET /.well-known/trustplane.json
-pub async fn trustplane_metadata(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let creds = state.credentials.current();
-    Json(serde_json::json!({
+#[utoipa::path(
+    get,
+    path = "/.well-known/trustplane.json",
+    tag = "discovery",
+    responses(
+        (status = 200, description = "Trust Plane metadata", body = Object),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag"),
+    )
+)]
+pub async fn trustplane_metadata(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let creds = state.credentials.for_request(host(&headers), WELL_KNOWN_DID_PATH).current();
+    cacheable_json(&headers, &metadata(&creds), creds.issued_at)
+}
+
+/// How long resolvers may reuse a discovery document before revalidating
+const DISCOVERY_MAX_AGE_SECS: u64 = 300;
+
+/// JSON document with an ETag (hash of its serialization) and Last-Modified.
+///
+/// Answers `304 Not Modified` when `If-None-Match` names the current ETag,
+/// so resolvers re-fetch only after a rotation changes the document.
+fn cacheable_json(
+    headers: &HeaderMap,
+    document: &serde_json::Value,
+    modified: DateTime<Utc>,
+) -> Response {
+    let body = document.to_string();
+    let etag = format!("\"{}\"", hex(&Sha256::digest(body.as_bytes())));
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (
+            header::LAST_MODIFIED,
+            modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ),
+        (
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", DISCOVERY_MAX_AGE_SECS),
+        ),
+    ];
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag)
+        });
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+    (
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, "application/json")],
+        body,
+    )
+        .into_response()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Request `Host` header, which selects the tenant
+fn host(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::HOST)?.to_str().ok()
+}
+
+/// Trust Plane metadata document
+fn metadata(creds: &TrustPlaneCredentials) -> serde_json::Value {
+    serde_json::json!({
         "did": creds.did,
         "organization": creds.organization,
         "issuer_kid": creds.issuer_key.kid(),
@@ -57,7 +158,7 @@ pub async fn trustplane_metadata(State(state): State<AppState>) -> Json<serde_js
         "issuer_public_key": creds.issuer_key.public_jwk(),
         "cat_public_key": creds.cat_key.public_jwk(),
         "credential": creds.credential,
-    }))
+    })
 }
 
 // ============================================================================
@@ -65,150 +166,380 @@ pub async fn trustplane_metadata(State(state): State<AppState>) -> Json<serde_js
 // ============================================================================
 
 /// GET /health
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Process is up", body = String))
+)]
 pub async fn health() -> &'static str {
     "OK"
 }
 
 /// GET /ready
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "health",
+    responses((status = 200, description = "Ready for traffic", body = String))
+)]
 pub async fn ready() -> &'static str {
     "OK"
 }
 
+/// GET /v1/version
+#[utoipa::path(
+    get,
+    path = "/v1/version",
+    tag = "info",
+    responses((status = 200, description = "Build information", body = BuildInfo))
+)]
+pub async fn version() -> Json<BuildInfo> {
+    Json(BuildInfo::current())
+}
+
+/// GET /v1/capabilities
+#[utoipa::path(
+    get,
+    path = "/v1/capabilities",
+    tag = "info",
+    responses((status = 200, description = "Supported and enabled features", body = Capabilities))
+)]
+pub async fn capabilities(State(state): State<AppState>) -> Json<Capabilities> {
+    Json((*state.capabilities).clone())
+}
+
+/// GET /openapi.json
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "info",
+    responses((status = 200, description = "This document", body = Object))
+)]
+pub async fn openapi(State(state): State<AppState>) -> Json<utoipa::openapi::OpenApi> {
+    Json((*state.openapi).clone())
+}
+
 /// GET /metrics
-pub async fn metrics() -> String {
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "info",
+    responses((status = 200, description = "Prometheus text exposition", body = String))
+)]
+pub async fn metrics(State(state): State<AppState>) -> String {
     // TODO: Implement Prometheus metrics
-    "# HELP trustplane_up Trust Plane is up\n# TYPE trustplane_up gauge\ntrustplane_up 1\n"
-        .to_string()
+    let mut out = format!(
+        "# HELP trustplane_up Trust Plane is up\n# TYPE trustplane_up gauge\ntrustplane_up 1\n\
+         # HELP trustplane_revoked_subjects Subjects on the revocation list\n\
+         # TYPE trustplane_revoked_subjects gauge\ntrustplane_revoked_subjects {}\n",
+        state.revocations.len()
+    );
+    // TODO: render CatMetrics once transition_pca mints PCAs; until then
+    // the minting counter and chain depth histogram could never move
+    state.bridge.render_metrics(&mut out);
+    out
 }
 
 // ============================================================================
 // CAT HTTP Handlers
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CatTransitionRequest {
     /// Base64-encoded PCA
     pub pca: String,
+    /// Audience the PCA must be bound to (omitted: any audience). Not
+    /// checked until PCAs record their audience
+    #[serde(default)]
+    pub expected_audience: String,
 }
 
-#[derive(Debug, Serialize)]
+impl std::fmt::Debug for CatTransitionRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CatTransitionRequest")
+            .field("pca", &redact(self.pca.as_bytes()))
+            .field("expected_audience", &self.expected_audience)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CatTransitionResponse {
     /// Base64-encoded new PCA (empty on error)
     #[serde(skip_serializing_if = "String::is_empty")]
     pub pca: String,
+    /// Kid of the CAT key that signed the successor (empty on error)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub signing_kid: String,
     /// Error message (empty on success)
     #[serde(skip_serializing_if = "String::is_empty")]
     pub error: String,
+    /// Machine-readable error code (empty on success)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub code: String,
+    /// Feature not implemented yet, with code `not_implemented`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    /// Where progress on `feature` is tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<String>,
+}
+
+impl CatTransitionResponse {
+    fn failure(e: &Error) -> (StatusCode, Json<Self>) {
+        let (feature, tracking_url) = pending_feature(e);
+        (
+            e.status_code(),
+            Json(Self {
+                pca: String::new(),
+                signing_kid: String::new(),
+                error: e.to_string(),
+                code: e.code().to_string(),
+                feature,
+                tracking_url,
+            }),
+        )
+    }
+}
+
+/// Feature id and tracking link of a [`Error::NotImplemented`]
+fn pending_feature(e: &Error) -> (Option<String>, Option<String>) {
+    match e.feature() {
+        Some(feature) => (
+            Some(feature.to_string()),
+            Some(NOT_IMPLEMENTED_TRACKING_URL.to_string()),
+        ),
+        None => (None, None),
+    }
 }
 
 /// POST /v1/cat/transition
+#[utoipa::path(
+    post,
+    path = "/v1/cat/transition",
+    tag = "cat",
+    request_body = CatTransitionRequest,
+    responses(
+        (status = 200, description = "Successor PCA", body = CatTransitionResponse),
+        (status = 400, description = "Invalid PCA", body = CatTransitionResponse),
+        (status = 403, description = "Transition denied", body = CatTransitionResponse),
+        (status = 413, description = "PCA too large", body = CatTransitionResponse),
+        (status = 501, description = "Not implemented yet", body = CatTransitionResponse),
+        (status = 503, description = "Transitions at capacity", body = CatTransitionResponse),
+    )
+)]
 pub async fn cat_transition(
     State(state): State<AppState>,
     Json(req): Json<CatTransitionRequest>,
 ) -> (StatusCode, Json<CatTransitionResponse>) {
     if req.pca.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(CatTransitionResponse {
-                pca: String::new(),
-                error: "pca is required".to_string(),
-            }),
-        );
+        return CatTransitionResponse::failure(&Error::invalid_field("pca", "pca is required"));
     }
 
-    // Decode base64
-    let pca_bytes = match base64::engine::general_purpose::STANDARD.decode(&req.pca) {
+    let pca_bytes = match decode_pca(&req.pca) {
         Ok(b) => b,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(CatTransitionResponse {
-                    pca: String::new(),
-                    error: format!("Invalid base64: {}", e),
-                }),
-            );
-        }
+        Err(e) => return CatTransitionResponse::failure(&e),
     };
 
-    // TODO: Implement actual CAT transition
-    let _credentials = state.credentials.current();
-    let _ = pca_bytes;
+    match state.cat.transition_pca(&pca_bytes, &req.expected_audience).await {
+        Ok(signed) => (
+            StatusCode::OK,
+            Json(CatTransitionResponse {
+                pca: base64::engine::general_purpose::STANDARD.encode(signed.pca),
+                signing_kid: signed.signing_kid,
+                error: String::new(),
+                code: String::new(),
+                feature: None,
+                tracking_url: None,
+            }),
+        ),
+        Err(e) => CatTransitionResponse::failure(&e),
+    }
+}
 
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(CatTransitionResponse {
-            pca: String::new(),
-            error: "Not implemented".to_string(),
-        }),
-    )
+/// Decode the base64 transport encoding of a PCA.
+///
+/// Failures here are `invalid_base64`, so a client's encoding bug is told
+/// apart from a malformed PCA (`invalid_cbor`, `invalid_pca_structure`).
+fn decode_pca(encoded: &str) -> Result<Vec<u8>, Error> {
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| Error::InvalidBase64(format!("pca is not valid base64: {}", e)))
 }
 
 // ============================================================================
 // Bridge HTTP Handlers
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct BridgeExchangeRequest {
-    /// Bridge configuration ID
+    /// Bridge configuration ID (omitted: the configured default bridge)
+    #[serde(default)]
     pub bridge_id: String,
     /// Base64-encoded credential
     pub credential: String,
+    /// Resource audience to bind the PCA₀ to (omitted: the bridge default).
+    /// Not recorded until PCA₀ minting lands
+    #[serde(default)]
+    pub audience: String,
+    /// Nonce the credential's `nonce` claim must match (OIDC ID tokens)
+    #[serde(default)]
+    pub nonce: String,
 }
 
-#[derive(Debug, Serialize)]
+impl std::fmt::Debug for BridgeExchangeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BridgeExchangeRequest")
+            .field("bridge_id", &self.bridge_id)
+            .field("credential", &redact(self.credential.as_bytes()))
+            .field("audience", &self.audience)
+            .field("nonce", &self.nonce)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BridgeExchangeResponse {
     /// Base64-encoded PCA₀ (empty on error)
     #[serde(skip_serializing_if = "String::is_empty")]
     pub pca: String,
+    /// Kid of the CAT key that signed the PCA₀ (empty on error)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub signing_kid: String,
     /// Error message (empty on success)
     #[serde(skip_serializing_if = "String::is_empty")]
     pub error: String,
+    /// Machine-readable error code (empty on success)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub code: String,
+    /// Feature not implemented yet, with code `not_implemented`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    /// Where progress on `feature` is tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<String>,
+    /// How claims were mapped (only with --bridge-mapping-trace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping_trace: Option<Vec<MappingTrace>>,
 }
 
+/// Header carrying the optional exchange idempotency key
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header carrying the optional proof-of-possession of the credential
+const DPOP_HEADER: &str = "dpop";
+
 /// POST /v1/bridge/exchange
+#[utoipa::path(
+    post,
+    path = "/v1/bridge/exchange",
+    tag = "bridge",
+    request_body = BridgeExchangeRequest,
+    params(
+        (
+            "Idempotency-Key" = Option<String>,
+            Header,
+            description = "Replays the same PCA₀ for a retried exchange"
+        ),
+        (
+            "DPoP" = Option<String>,
+            Header,
+            description = "Proof of possession of the credential's `cnf.jwk` key"
+        ),
+    ),
+    responses(
+        (status = 200, description = "PCA₀", body = BridgeExchangeResponse),
+        (status = 400, description = "Invalid credential", body = BridgeExchangeResponse),
+        (status = 401, description = "Proof-of-possession failed", body = BridgeExchangeResponse),
+        (status = 404, description = "Bridge not found", body = BridgeExchangeResponse),
+        (status = 409, description = "Bridge disabled", body = BridgeExchangeResponse),
+        (status = 410, description = "Bridge being removed", body = BridgeExchangeResponse),
+        (status = 501, description = "Not implemented yet", body = BridgeExchangeResponse),
+    )
+)]
 pub async fn bridge_exchange(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<BridgeExchangeRequest>,
 ) -> (StatusCode, Json<BridgeExchangeResponse>) {
-    if req.bridge_id.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
+    let error = |e: Error| {
+        let (feature, tracking_url) = pending_feature(&e);
+        (
+            e.status_code(),
             Json(BridgeExchangeResponse {
                 pca: String::new(),
-                error: "bridge_id is required".to_string(),
+                signing_kid: String::new(),
+                error: e.to_string(),
+                code: e.code().to_string(),
+                feature,
+                tracking_url,
+                mapping_trace: None,
             }),
-        );
-    }
+        )
+    };
 
-    if req.credential.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(BridgeExchangeResponse {
-                pca: String::new(),
-                error: "credential is required".to_string(),
-            }),
-        );
-    }
+    let credential = match base64::engine::general_purpose::STANDARD.decode(&req.credential) {
+        Ok(credential) => credential,
+        Err(e) => {
+            return error(Error::invalid_field(
+                "credential",
+                format!("credential is not valid base64: {}", e),
+            ))
+        }
+    };
 
-    // Check bridge exists and is enabled
-    match state.registry.get_enabled(&req.bridge_id) {
-        Some(_bridge) => {
-            // TODO: Implement actual bridge exchange
-            (
-                StatusCode::NOT_IMPLEMENTED,
-                Json(BridgeExchangeResponse {
-                    pca: String::new(),
-                    error: "Not implemented".to_string(),
-                }),
-            )
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER).map(|v| v.to_str()) {
+        None => "",
+        Some(Ok(key)) => key,
+        Some(Err(_)) => {
+            return error(Error::invalid_field(
+                "Idempotency-Key",
+                "Idempotency-Key header is not valid ASCII",
+            ))
         }
-        None => (
-            StatusCode::NOT_FOUND,
+    };
+
+    let pop = match headers.get(DPOP_HEADER).map(|v| v.to_str()) {
+        None => "",
+        Some(Ok(proof)) => proof,
+        Some(Err(_)) => {
+            return error(Error::PopVerificationFailed(
+                "DPoP header is not valid ASCII".to_string(),
+            ))
+        }
+    };
+
+    let result = state
+        .bridge
+        .exchange_credential(
+            &req.bridge_id,
+            &credential,
+            &req.audience,
+            &req.nonce,
+            pop,
+            idempotency_key,
+        )
+        .await;
+    let mapping_trace = state.bridge.mapping_trace(&req.bridge_id, &credential);
+
+    match result {
+        Ok(signed) => (
+            StatusCode::OK,
             Json(BridgeExchangeResponse {
-                pca: String::new(),
-                error: format!("Bridge not found or disabled: {}", req.bridge_id),
+                pca: base64::engine::general_purpose::STANDARD.encode(signed.pca),
+                signing_kid: signed.signing_kid,
+                error: String::new(),
+                code: String::new(),
+                feature: None,
+                tracking_url: None,
+                mapping_trace,
             }),
         ),
+        Err(e) => {
+            let (status, Json(response)) = error(e);
+            (status, Json(BridgeExchangeResponse { mapping_trace, ..response }))
+        }
     }
 }
 
@@ -216,19 +547,26 @@ pub async fn bridge_exchange(
 // Bridge Admin HTTP Handlers
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BridgeInfo {
     pub id: String,
     pub bridge_type: String,
     pub enabled: bool,
+    pub version: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ListBridgesResponse {
     pub bridges: Vec<BridgeInfo>,
 }
 
 /// GET /v1/bridge-admin/bridges
+#[utoipa::path(
+    get,
+    path = "/v1/bridge-admin/bridges",
+    tag = "bridge-admin",
+    responses((status = 200, description = "Configured bridges", body = ListBridgesResponse))
+)]
 pub async fn list_bridges(State(state): State<AppState>) -> Json<ListBridgesResponse> {
     let bridges = state
         .registry
@@ -238,6 +576,7 @@ pub async fn list_bridges(State(state): State<AppState>) -> Json<ListBridgesResp
             id: b.id,
             bridge_type: format!("{:?}", b.bridge_type),
             enabled: b.enabled,
+            version: b.version,
         })
         .collect();
 
@@ -245,36 +584,689 @@ pub async fn list_bridges(State(state): State<AppState>) -> Json<ListBridgesResp
 }
 
 /// GET /v1/bridge-admin/bridges/:id
-pub async fn get_bridge(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> (StatusCode, Json<serde_json::Value>) {
+///
+/// The `ETag` carries the bridge version, for use as `If-Match` on writes.
+#[utoipa::path(
+    get,
+    path = "/v1/bridge-admin/bridges/{id}",
+    tag = "bridge-admin",
+    params(("id" = String, Path, description = "Bridge ID")),
+    responses(
+        (status = 200, description = "Bridge summary; the ETag carries its version", body = Object),
+        (status = 404, description = "Bridge not found", body = Object),
+    )
+)]
+pub async fn get_bridge(State(state): State<AppState>, Path(id): Path<String>) -> Response {
     match state.registry.get(&id) {
         Some(b) => (
             StatusCode::OK,
+            [(header::ETAG, format!("\"{}\"", b.version))],
             Json(serde_json::json!({
                 "id": b.id,
                 "type": format!("{:?}", b.bridge_type),
                 "enabled": b.enabled,
+                "disabled_reason": b.disabled_reason,
+                "version": b.version,
             })),
-        ),
+        )
+            .into_response(),
         None => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({ "error": format!("Bridge not found: {}", id) })),
-        ),
+        )
+            .into_response(),
+    }
+}
+
+/// Expected bridge version from `If-Match` (absent or `*`: any version)
+fn if_match(headers: &HeaderMap) -> Result<Option<u64>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(None);
     }
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .and_then(|v| v.parse().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "If-Match is not a bridge version ETag" })),
+            )
+        })
 }
 
 /// DELETE /v1/bridge-admin/bridges/:id
+#[utoipa::path(
+    delete,
+    path = "/v1/bridge-admin/bridges/{id}",
+    tag = "bridge-admin",
+    params(
+        ("id" = String, Path, description = "Bridge ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected bridge version ETag"),
+    ),
+    responses(
+        (status = 200, description = "Removed, or marked for deletion", body = Object),
+        (status = 400, description = "Version conflict", body = Object),
+        (status = 404, description = "Bridge not found", body = Object),
+    )
+)]
 pub async fn remove_bridge(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let expected_version = match if_match(&headers) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+    match state.registry.remove(&id, expected_version) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({ "success": true }))),
+        Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct DisableBridgeRequest {
+    /// Why the bridge is being disabled
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// POST /v1/bridge-admin/bridges/:id/enable
+#[utoipa::path(
+    post,
+    path = "/v1/bridge-admin/bridges/{id}/enable",
+    tag = "bridge-admin",
+    params(
+        ("id" = String, Path, description = "Bridge ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected bridge version ETag"),
+    ),
+    responses(
+        (status = 200, description = "Enabled", body = Object),
+        (status = 404, description = "Bridge not found", body = Object),
+    )
+)]
+pub async fn enable_bridge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    set_bridge_enabled(&state, &id, true, None, &headers)
+}
+
+/// POST /v1/bridge-admin/bridges/:id/disable
+///
+/// The body (`{"reason": "..."}`) is optional.
+#[utoipa::path(
+    post,
+    path = "/v1/bridge-admin/bridges/{id}/disable",
+    tag = "bridge-admin",
+    request_body(content = Option<DisableBridgeRequest>, description = "Optional reason"),
+    params(
+        ("id" = String, Path, description = "Bridge ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected bridge version ETag"),
+    ),
+    responses(
+        (status = 200, description = "Disabled", body = Object),
+        (status = 404, description = "Bridge not found", body = Object),
+    )
+)]
+pub async fn disable_bridge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<DisableBridgeRequest>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let reason = body.and_then(|Json(req)| req.reason);
+    set_bridge_enabled(&state, &id, false, reason, &headers)
+}
+
+fn set_bridge_enabled(
+    state: &AppState,
+    id: &str,
+    enabled: bool,
+    reason: Option<String>,
+    headers: &HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let expected_version = match if_match(headers) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+    match state.registry.set_enabled(id, enabled, reason, expected_version) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({ "success": true }))),
+        Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+/// POST /v1/bridge-admin/bridges/:id/undelete
+///
+/// Cancels the deletion of a removed bridge still within its grace.
+#[utoipa::path(
+    post,
+    path = "/v1/bridge-admin/bridges/{id}/undelete",
+    tag = "bridge-admin",
+    params(
+        ("id" = String, Path, description = "Bridge ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected bridge version ETag"),
+    ),
+    responses(
+        (status = 200, description = "Deletion cancelled", body = Object),
+        (status = 400, description = "Bridge not pending deletion", body = Object),
+        (status = 404, description = "Bridge not found", body = Object),
+    )
+)]
+pub async fn undelete_bridge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    match state.registry.remove(&id) {
+    let expected_version = match if_match(&headers) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+    match state.registry.undelete(&id, expected_version) {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({ "success": true }))),
         Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+/// GET /v1/bridge-admin/bridge-types
+///
+/// Supported bridge types, each with the JSON Schema of its `config.<type>`.
+#[utoipa::path(
+    get,
+    path = "/v1/bridge-admin/bridge-types",
+    tag = "bridge-admin",
+    responses((
+        status = 200,
+        description = "Bridge types with their config schemas",
+        body = [Object]
+    ))
+)]
+pub async fn list_bridge_types() -> Json<Vec<serde_json::Value>> {
+    Json(
+        BridgeType::all()
+            .iter()
+            .map(|t| serde_json::json!({ "type": t, "schema": t.config_schema() }))
+            .collect(),
+    )
+}
+
+/// GET /v1/bridge-admin/bridges/export
+#[utoipa::path(
+    get,
+    path = "/v1/bridge-admin/bridges/export",
+    tag = "bridge-admin",
+    responses((status = 200, description = "Full bridge configurations", body = [Object]))
+)]
+pub async fn export_bridges(State(state): State<AppState>) -> Json<Vec<BridgeConfig>> {
+    Json(state.registry.export())
+}
+
+/// POST /v1/bridge-admin/bridges/import
+#[utoipa::path(
+    post,
+    path = "/v1/bridge-admin/bridges/import",
+    tag = "bridge-admin",
+    request_body(content = [Object], description = "Bridge configurations, as exported"),
+    responses(
+        (status = 200, description = "Imported", body = Object),
+        (status = 400, description = "Invalid batch; nothing applied", body = Object),
+    )
+)]
+pub async fn import_bridges(
+    State(state): State<AppState>,
+    Json(bridges): Json<Vec<BridgeConfig>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match state.registry.import(bridges) {
+        Ok(count) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "imported": count })),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+// ============================================================================
+// Admin HTTP Handlers
+// ============================================================================
+
+/// POST /v1/admin/revocations/:subject
+#[utoipa::path(
+    post,
+    path = "/v1/admin/revocations/{subject}",
+    tag = "admin",
+    params(("subject" = String, Path, description = "Subject to revoke")),
+    responses((status = 200, description = "Revoked", body = Object))
+)]
+pub async fn revoke_subject(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match state.revocations.revoke(&subject) {
+        Ok(created) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "created": created })),
+        ),
+        Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+/// DELETE /v1/admin/revocations/:subject
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/revocations/{subject}",
+    tag = "admin",
+    params(("subject" = String, Path, description = "Revoked subject")),
+    responses(
+        (status = 200, description = "Revocation lifted", body = Object),
+        (status = 404, description = "Subject not revoked", body = Object),
+    )
+)]
+pub async fn unrevoke_subject(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match state.revocations.unrevoke(&subject) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({ "success": true }))),
+        Ok(false) => (
             StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Subject not revoked: {}", subject) })),
+        ),
+        Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RotateKeysRequest {
+    /// Hosted DID whose keys to rotate (default: the Trust Plane DID)
+    #[serde(default)]
+    pub did: Option<String>,
+}
+
+/// POST /v1/admin/keys/rotate
+#[utoipa::path(
+    post,
+    path = "/v1/admin/keys/rotate",
+    tag = "admin",
+    request_body(content = Option<RotateKeysRequest>, description = "Optional hosted DID"),
+    responses(
+        (status = 200, description = "New and previous key IDs", body = Object),
+        (status = 404, description = "DID not hosted", body = Object),
+    )
+)]
+pub async fn rotate_keys(
+    State(state): State<AppState>,
+    body: Option<Json<RotateKeysRequest>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let did = body.and_then(|Json(req)| req.did);
+    let credentials = match did.as_deref() {
+        None => state.credentials.default_tenant(),
+        Some(did) => match state.credentials.get(did) {
+            Some(credentials) => credentials,
+            None => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({ "error": format!("DID not hosted: {}", did) })),
+                );
+            }
+        },
+    };
+
+    let previous = credentials.current();
+    match credentials.rotate() {
+        Ok(rotated) => {
+            tracing::info!(
+                did = %rotated.did,
+                issuer_kid = %rotated.issuer_key.kid(),
+                cat_kid = %rotated.cat_key.kid(),
+                previous_issuer_kid = %previous.issuer_key.kid(),
+                previous_cat_kid = %previous.cat_key.kid(),
+                "Keys rotated on demand"
+            );
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "did": rotated.did,
+                    "issuer_kid": rotated.issuer_key.kid(),
+                    "cat_kid": rotated.cat_key.kid(),
+                    "previous_issuer_kid": previous.issuer_key.kid(),
+                    "previous_cat_kid": previous.cat_key.kid(),
+                })),
+            )
+        }
+        Err(e) => (
+            e.status_code(),
             Json(serde_json::json!({ "error": e.to_string() })),
         ),
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DidPreviewRequest {
+    /// DID the document is for
+    pub did: String,
+    /// Public JWK of the issuer key, with its `kid`
+    #[schema(value_type = Object)]
+    pub issuer_jwk: serde_json::Value,
+    /// Public JWK of the CAT key, with its `kid`
+    #[schema(value_type = Object)]
+    pub cat_jwk: serde_json::Value,
+    /// Public key encoding: `jwk` (default), `multibase` or `both`
+    #[serde(default)]
+    pub key_format: Option<String>,
+}
+
+/// POST /v1/admin/did-preview
+///
+/// Renders the DID document the given keys would produce, without touching
+/// the hosted DIDs or their keys.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/did-preview",
+    tag = "admin",
+    request_body = DidPreviewRequest,
+    responses(
+        (status = 200, description = "DID document", body = Object),
+        (status = 400, description = "Invalid DID, JWK or key format", body = Object),
+    )
+)]
+pub async fn did_preview(
+    Json(req): Json<DidPreviewRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let preview = req
+        .key_format
+        .as_deref()
+        .map_or(Ok(KeyFormat::default()), str::parse)
+        .and_then(|format| {
+            DidWebLocation::parse(&req.did)?;
+            DidDocument::from_public_jwks(&req.did, &req.issuer_jwk, &req.cat_jwk, format)
+        });
+    match preview {
+        Ok(document) => (StatusCode::OK, Json(document.to_json())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SubjectQuotaRequest {
+    /// Transitions per minute (0: unlimited)
+    pub per_minute: u32,
+}
+
+/// GET /v1/admin/quotas/:subject
+#[utoipa::path(
+    get,
+    path = "/v1/admin/quotas/{subject}",
+    tag = "admin",
+    params(("subject" = String, Path, description = "Subject")),
+    responses((status = 200, description = "Effective transitions per minute", body = Object))
+)]
+pub async fn get_subject_quota(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "subject": subject,
+        "per_minute": state.quota.limit(&subject),
+    }))
+}
+
+/// PUT /v1/admin/quotas/:subject
+#[utoipa::path(
+    put,
+    path = "/v1/admin/quotas/{subject}",
+    tag = "admin",
+    request_body = SubjectQuotaRequest,
+    params(("subject" = String, Path, description = "Subject")),
+    responses((
+        status = 200,
+        description = "Override set; not enforced until CAT transitions decode PCAs",
+        body = Object
+    ))
+)]
+pub async fn set_subject_quota(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+    Json(req): Json<SubjectQuotaRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match state.quota.set_override(&subject, req.per_minute) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({ "success": true }))),
+        Err(e) => (
+            e.status_code(),
+            Json(serde_json::json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+/// DELETE /v1/admin/quotas/:subject
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/quotas/{subject}",
+    tag = "admin",
+    params(("subject" = String, Path, description = "Subject")),
+    responses(
+        (status = 200, description = "Override cleared", body = Object),
+        (status = 404, description = "No override", body = Object),
+    )
+)]
+pub async fn clear_subject_quota(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.quota.clear_override(&subject) {
+        (StatusCode::OK, Json(serde_json::json!({ "success": true })))
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("No quota override for: {}", subject) })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::BridgeOptions;
+    use crate::cat::PcaLimits;
+    use crate::cli::Cli;
+    use crate::config::Config;
+    use crate::credentials::testing::{test_credentials, test_credentials_manager, TEST_DID};
+    use clap::Parser;
+    use std::path::PathBuf;
+
+    fn app_state(cat: CatServiceImpl) -> AppState {
+        let credentials = test_credentials_manager(TEST_DID);
+        let registry = Arc::new(BridgeRegistry::new());
+        let revocations = Arc::new(RevocationStore::in_memory());
+        let bridge = BridgeServiceImpl::new(
+            credentials.clone(),
+            registry.clone(),
+            revocations.clone(),
+            BridgeOptions::default(),
+        );
+        let config = Config::try_from(Cli::parse_from(["permguard-trustplane"])).unwrap();
+        AppState {
+            credentials: Arc::new(TenantCredentials::new(credentials)),
+            registry,
+            bridge: Arc::new(bridge),
+            cat: Arc::new(cat),
+            revocations,
+            quota: Arc::new(SubjectQuota::default()),
+            capabilities: Arc::new(Capabilities::new(&config)),
+            openapi: Arc::new(crate::openapi::document(&config)),
+        }
+    }
+
+    /// Compare `actual` with a checked-in document under `testdata/golden`.
+    ///
+    /// Run with `PERMGUARD_UPDATE_GOLDEN=1` to rewrite the file after an
+    /// intended change to a published document.
+    fn assert_golden(name: &str, actual: &serde_json::Value) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/golden")
+            .join(name);
+        if std::env::var_os("PERMGUARD_UPDATE_GOLDEN").is_some() {
+            let json = serde_json::to_string_pretty(actual).unwrap();
+            std::fs::write(&path, json + "\n").unwrap();
+        }
+        let expected: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(
+            actual,
+            &expected,
+            "{} drifted from {}",
+            name,
+            path.display()
+        );
+    }
+
+    #[test]
+    fn test_discovery_documents_golden() {
+        let creds = test_credentials(TEST_DID);
+        assert_golden("did.json", &creds.did_document.to_json());
+        assert_golden("trustplane.json", &metadata(&creds));
+    }
+
+    #[test]
+    fn test_cacheable_json() {
+        let creds = test_credentials(TEST_DID);
+        let document = creds.did_document.to_json();
+        let response = cacheable_json(&HeaderMap::new(), &document, creds.issued_at);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Thu, 01 Jan 2026 00:00:00 GMT"
+        );
+        let etag = response.headers()[header::ETAG].clone();
+
+        // Stable for the same document
+        let again = cacheable_json(&HeaderMap::new(), &document, creds.issued_at);
+        assert_eq!(again.headers()[header::ETAG], etag);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let response = cacheable_json(&headers, &document, creds.issued_at);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // A rotated document gets a new ETag and is sent in full
+        let rotated = test_credentials("did:web:rotated.example.com");
+        let response = cacheable_json(&headers, &rotated.did_document.to_json(), creds.issued_at);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+    }
+
+    #[tokio::test]
+    async fn test_trustplane_metadata_etag() {
+        let state = app_state(CatServiceImpl::new(test_credentials_manager(TEST_DID)));
+        let response = trustplane_metadata(State(state.clone()), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, response.headers()[header::ETAG].clone());
+        let response = trustplane_metadata(State(state), headers).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn test_decode_pca() {
+        assert_eq!(decode_pca("AQID").unwrap(), vec![1, 2, 3]);
+        for invalid in ["AQI$", "AQI", "AQID=", "AQ=D"] {
+            let err = decode_pca(invalid).unwrap_err();
+            assert_eq!(err.code(), "invalid_base64", "{}", invalid);
+            assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        }
+
+        // Decoding failures past the transport encoding keep their own codes
+        let cbor = Error::InvalidCbor("truncated map".into());
+        let structure = Error::InvalidPcaStructure("missing sequence".into());
+        assert!(cbor.to_string().starts_with("invalid_cbor: "));
+        assert!(structure.to_string().starts_with("invalid_pca_structure: "));
+        assert_eq!(structure.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_cat_transition_through_service() {
+        let cat = CatServiceImpl::new(test_credentials_manager(TEST_DID)).with_limits(PcaLimits {
+            max_attributes: 4,
+            max_bytes: 4,
+        });
+        let state = app_state(cat);
+        let transition = |pca: &[u8]| {
+            let request: CatTransitionRequest = serde_json::from_value(serde_json::json!({
+                "pca": base64::engine::general_purpose::STANDARD.encode(pca),
+            }))
+            .unwrap();
+            cat_transition(State(state.clone()), Json(request))
+        };
+
+        // The service's limits apply to HTTP transitions too
+        let (status, Json(response)) = transition(&[0u8; 5]).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.code, "pca_too_large");
+
+        let (status, Json(response)) = transition(&[1]).await;
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(response.feature.as_deref(), Some("cat_transition"));
+    }
+
+    #[test]
+    fn test_not_implemented_response() {
+        let (status, Json(response)) =
+            CatTransitionResponse::failure(&Error::NotImplemented("cat_transition".into()));
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        let body = serde_json::to_value(response).unwrap();
+        assert_eq!(body["code"], "not_implemented");
+        assert_eq!(body["feature"], "cat_transition");
+        assert_eq!(body["tracking_url"], NOT_IMPLEMENTED_TRACKING_URL);
+
+        // Other failures carry their code, no feature
+        let (status, Json(response)) =
+            CatTransitionResponse::failure(&Error::invalid_field("pca", "pca is required"));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let body = serde_json::to_value(response).unwrap();
+        assert_eq!(body["code"], "invalid_argument");
+        assert!(body.get("feature").is_none() && body.get("tracking_url").is_none());
+    }
+
+    #[test]
+    fn test_if_match() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_MATCH, value.parse().unwrap());
+            headers
+        };
+        assert_eq!(if_match(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(if_match(&headers("*")).unwrap(), None);
+        assert_eq!(if_match(&headers("\"7\"")).unwrap(), Some(7));
+        for invalid in ["7", "W/\"7\"", "\"seven\""] {
+            assert!(if_match(&headers(invalid)).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Info gRPC service.
+
+use crate::proto::info::{
+    info_service_server::{InfoService, InfoServiceServer},
+    GetVersionRequest, GetVersionResponse, VersionRange as ProtoVersionRange,
+};
+use crate::bridge::BridgeType;
+use crate::cat::SUPPORTED_PCA_VERSIONS;
+use crate::config::Config;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tonic::{Request, Response, Status};
+use utoipa::ToSchema;
+
+/// Build information of the running binary
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BuildInfo {
+    /// Crate version
+    pub version: String,
+    /// Git commit the binary was built from
+    pub git_commit: String,
+    /// Build time (RFC 3339)
+    pub build_timestamp: String,
+    /// permguard-pic version
+    pub pic_version: String,
+    /// PCA format versions transitions accept
+    pub pca_versions: VersionRange,
+}
+
+impl BuildInfo {
+    /// Build information of this binary
+    pub fn current() -> Self {
+        let build_timestamp = env!("PERMGUARD_BUILD_TIMESTAMP")
+            .parse()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+
+        Self {
+            version: crate::version().to_string(),
+            git_commit: env!("PERMGUARD_GIT_COMMIT").to_string(),
+            build_timestamp,
+            pic_version: permguard_pic::cat_version().to_string(),
+            pca_versions: VersionRange::supported(),
+        }
+    }
+}
+
+/// What this instance supports and has enabled, for clients adapting to it.
+///
+/// Carries no secrets, so it is safe to serve unauthenticated.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Capabilities {
+    /// Crate version
+    pub version: String,
+    /// Optional features, by name, and whether each is enabled
+    pub features: BTreeMap<&'static str, bool>,
+    /// Bridge types exchanges can be configured with
+    pub bridge_types: &'static [BridgeType],
+    /// JWS algorithms of the keys PCAs and credentials are signed with
+    pub key_algorithms: &'static [&'static str],
+    /// PCA format versions transitions accept
+    pub pca_versions: VersionRange,
+}
+
+/// Inclusive version range
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct VersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl VersionRange {
+    /// PCA format versions this build accepts
+    pub fn supported() -> Self {
+        Self {
+            min: *SUPPORTED_PCA_VERSIONS.start(),
+            max: *SUPPORTED_PCA_VERSIONS.end(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Capabilities of this binary running with `config`
+    pub fn new(config: &Config) -> Self {
+        let features = BTreeMap::from([
+            ("admin", config.admin_enabled),
+            ("bridge_admin", config.bridge_admin_enabled),
+            ("did_document", config.serve_did_document),
+            ("exchange_cache", config.cache_exchanges),
+            ("grpc_reflection", true),
+            ("http_compression", config.http_compression),
+            ("mapping_trace", config.bridge_mapping_trace),
+            ("metrics", config.metrics_enabled),
+            ("openapi", config.serve_openapi),
+            ("tls_client", cfg!(feature = "tls")),
+            ("trustplane_metadata", config.serve_trustplane_metadata),
+        ]);
+        Self {
+            version: crate::version().to_string(),
+            features,
+            bridge_types: BridgeType::all(),
+            key_algorithms: &["EdDSA"],
+            pca_versions: VersionRange::supported(),
+        }
+    }
+}
+
+/// Info gRPC service implementation
+pub struct InfoServiceImpl;
+
+impl InfoServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_server(self) -> InfoServiceServer<Self> {
+        InfoServiceServer::new(self)
+    }
+}
+
+impl Default for InfoServiceImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tonic::async_trait]
+impl InfoService for InfoServiceImpl {
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> std::result::Result<Response<GetVersionResponse>, Status> {
+        let info = BuildInfo::current();
+
+        Ok(Response::new(GetVersionResponse {
+            version: info.version,
+            git_commit: info.git_commit,
+            build_timestamp: info.build_timestamp,
+            pic_version: info.pic_version,
+            pca_versions: Some(ProtoVersionRange {
+                min: info.pca_versions.min,
+                max: info.pca_versions.max,
+            }),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::Parser;
+
+    #[test]
+    fn test_capabilities() {
+        let cli = Cli::parse_from(["permguard-trustplane"]);
+        let mut config = Config::try_from(cli).unwrap();
+        config.metrics_enabled = false;
+        let json = serde_json::to_value(Capabilities::new(&config)).unwrap();
+        assert_eq!(json["features"]["metrics"], false);
+        assert_eq!(json["features"]["grpc_reflection"], true);
+        assert_eq!(json["bridge_types"], serde_json::json!(["jwt"]));
+        assert_eq!(json["key_algorithms"], serde_json::json!(["EdDSA"]));
+        assert_eq!(json["pca_versions"], serde_json::json!({ "min": 1, "max": 1 }));
+    }
+
+    #[tokio::test]
+    async fn test_version_pca_versions() {
+        let json = serde_json::to_value(BuildInfo::current()).unwrap();
+        assert_eq!(json["pca_versions"], serde_json::json!({ "min": 1, "max": 1 }));
+
+        let response = InfoServiceImpl::new()
+            .get_version(Request::new(GetVersionRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.pca_versions, Some(ProtoVersionRange { min: 1, max: 1 }));
+    }
+}
@@ -22,10 +22,17 @@ pub mod pic {
 }
 
 // Core modules
+pub mod cache;
 pub mod cli;
+pub mod client;
+pub mod concurrency;
 pub mod config;
 pub mod error;
 pub mod handlers;
+pub mod metrics;
+pub mod openapi;
+pub mod redact;
+pub mod revocation;
 
 // Credentials management
 pub mod credentials;
@@ -34,18 +41,26 @@ pub mod credentials;
 pub mod bridge;
 pub mod bridge_admin;
 pub mod cat;
+pub mod info;
 
 // Server
 pub mod server;
 
+mod catch_panic;
+mod content_type;
+mod deadline;
+mod descriptor;
 mod proto;
+mod systemd;
 
 
 // Public API
 pub use cli::Cli;
-pub use config::Config;
+pub use config::{Config, RuntimeFlavor};
 pub use error::{Error, Result};
-pub use credentials::{TrustPlaneCredentials, CredentialProvider, CredentialsManager};
+pub use credentials::{
+    CredentialProvider, CredentialsManager, SignatureStatus, SignedPca, TrustPlaneCredentials,
+};
 pub use server::Server;
 
 /// Returns the crate version.
@@ -15,7 +15,7 @@
  */
 
 use clap::Parser;
-use permguard_trustplane::{Cli, Config, Server, version};
+use permguard_trustplane::{Cli, Config, RuntimeFlavor, Server, version};
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 
@@ -41,12 +41,15 @@ fn main() {
             .init();
     }
 
-    // Print banner
-    println!("{}", ART);
-    println!();
-    println!("The official Permguard TrustPlane v{}", version());
-    println!("Copyright © 2026 Nitro Agility S.r.l.");
-    println!();
+    // Print banner (stdout stays pure JSON with --print-config)
+    let print_config = cli.print_config;
+    if !print_config {
+        println!("{}", ART);
+        println!();
+        println!("The official Permguard TrustPlane v{}", version());
+        println!("Copyright © 2026 Nitro Agility S.r.l.");
+        println!();
+    }
 
     // Convert CLI to Config
     let config = match Config::try_from(cli) {
@@ -57,17 +60,40 @@ fn main() {
         }
     };
 
+    if print_config {
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                error!(error = %e, "Failed to serialize configuration");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     info!(version = version(), debug = config.debug, did = %config.did, "Starting Permguard Trust Plane");
 
+    // Build runtime
+    let mut builder = match config.runtime {
+        RuntimeFlavor::MultiThread => tokio::runtime::Builder::new_multi_thread(),
+        RuntimeFlavor::SingleThread => tokio::runtime::Builder::new_current_thread(),
+    };
+    if let Some(worker_threads) = config.worker_threads {
+        builder.worker_threads(worker_threads);
+    }
+    let runtime = builder.enable_all().build().expect("Failed to create Tokio runtime");
+
+    info!(
+        runtime = %config.runtime,
+        worker_threads = runtime.metrics().num_workers(),
+        "Tokio runtime configured"
+    );
+
     // Build and run server
-    let result = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .expect("Failed to create Tokio runtime")
-        .block_on(async {
-            let server = Server::new(config).await?;
-            server.run().await
-        });
+    let result = runtime.block_on(async {
+        let server = Server::new(config).await?;
+        server.run().await
+    });
 
     if let Err(e) = result {
         error!(error = %e, "Server error");
@@ -0,0 +1,353 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Prometheus metrics.
+//!
+//! Counters and histograms are kept in memory and rendered in the text
+//! exposition format by the `/metrics` endpoint.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Labels whose values are per-request (subjects, token IDs), and would
+/// create a series per request. [`LabeledCounter`] refuses them.
+const UNBOUNDED_LABELS: &[&str] = &["subject", "sub", "jti", "token", "credential"];
+
+/// Series a [`LabeledCounter`] keeps before counting new label values under
+/// an `overflow` series, however its labels are chosen
+pub const MAX_SERIES: usize = 1000;
+
+/// Label value of the series new label values overflow into
+const OVERFLOW: &str = "overflow";
+
+/// Upper bounds of the latency buckets, in seconds. Fine-grained at the low
+/// end, where signing and claim checks land; network fetches fill the rest.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Latency histogram with one series per value of a single label
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    series: Mutex<BTreeMap<&'static str, Series>>,
+}
+
+/// Observations of one label value
+#[derive(Debug)]
+struct Series {
+    /// Per-bucket (not cumulative) counts, plus one for `+Inf`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    pub fn new(name: &'static str, help: &'static str, label: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            label,
+            series: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record a duration under a label value
+    pub fn observe(&self, value: &'static str, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        let mut series = self.series.lock().unwrap();
+        let series = series.entry(value).or_insert_with(|| Series {
+            buckets: vec![0; LATENCY_BUCKETS.len() + 1],
+            sum: 0.0,
+            count: 0,
+        });
+        series.buckets[bucket] += 1;
+        series.sum += seconds;
+        series.count += 1;
+    }
+
+    /// Append the histogram in Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        let (name, label) = (self.name, self.label);
+        let _ = writeln!(out, "# HELP {} {}", name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (value, series) in self.series.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&series.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{{}=\"{}\",le=\"{}\"}} {}",
+                    name, label, value, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}=\"{}\",le=\"+Inf\"}} {}",
+                name, label, value, series.count
+            );
+            let _ = writeln!(out, "{}_sum{{{}=\"{}\"}} {}", name, label, value, series.sum);
+            let _ = writeln!(out, "{}_count{{{}=\"{}\"}} {}", name, label, value, series.count);
+        }
+    }
+}
+
+/// Monotonic counter
+#[derive(Debug)]
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            value: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inc(&self) {
+        self.value.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Append the counter in Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} counter", self.name);
+        let _ = writeln!(out, "{} {}", self.name, self.get());
+    }
+}
+
+/// Counter with one series per combination of label values.
+///
+/// Cardinality is bounded twice: per-request labels such as `subject` are
+/// refused when the counter is created, and past [`MAX_SERIES`] series new
+/// values are counted under `overflow`.
+#[derive(Debug)]
+pub struct LabeledCounter {
+    name: &'static str,
+    help: &'static str,
+    labels: &'static [&'static str],
+    series: Mutex<BTreeMap<Vec<String>, u64>>,
+}
+
+impl LabeledCounter {
+    /// Create a counter with the given label names.
+    ///
+    /// # Panics
+    ///
+    /// If a label is one of the per-request labels never allowed on a
+    /// metric, such as `subject` or `jti`.
+    pub fn new(name: &'static str, help: &'static str, labels: &'static [&'static str]) -> Self {
+        if let Some(label) = labels.iter().find(|label| UNBOUNDED_LABELS.contains(label)) {
+            panic!("metric {} must not be labelled by {}: its values are unbounded", name, label);
+        }
+        Self {
+            name,
+            help,
+            labels,
+            series: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Increment the series of `values`, given in label order
+    pub fn inc(&self, values: &[&str]) {
+        debug_assert_eq!(values.len(), self.labels.len(), "label values of {}", self.name);
+        let key: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let mut series = self.series.lock().unwrap();
+        let key = if series.len() >= MAX_SERIES && !series.contains_key(&key) {
+            vec![OVERFLOW.to_string(); self.labels.len()]
+        } else {
+            key
+        };
+        *series.entry(key).or_insert(0) += 1;
+    }
+
+    /// Number of series recorded so far
+    pub fn series_count(&self) -> usize {
+        self.series.lock().unwrap().len()
+    }
+
+    /// Append the counter in Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} counter", self.name);
+        for (values, count) in self.series.lock().unwrap().iter() {
+            let labels: Vec<String> = self
+                .labels
+                .iter()
+                .zip(values)
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape_label_value(value)))
+                .collect();
+            let _ = writeln!(out, "{}{{{}}} {}", self.name, labels.join(","), count);
+        }
+    }
+}
+
+/// Escape a label value for the text exposition format
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Unlabelled histogram of integer values (e.g. chain depths), with the
+/// bucket bounds given at creation
+#[derive(Debug)]
+pub struct ValueHistogram {
+    name: &'static str,
+    help: &'static str,
+    bounds: &'static [u64],
+    series: Mutex<Series>,
+}
+
+impl ValueHistogram {
+    pub fn new(name: &'static str, help: &'static str, bounds: &'static [u64]) -> Self {
+        Self {
+            name,
+            help,
+            bounds,
+            series: Mutex::new(Series {
+                buckets: vec![0; bounds.len() + 1],
+                sum: 0.0,
+                count: 0,
+            }),
+        }
+    }
+
+    pub fn observe(&self, value: u64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        let mut series = self.series.lock().unwrap();
+        series.buckets[bucket] += 1;
+        series.sum += value as f64;
+        series.count += 1;
+    }
+
+    /// Append the histogram in Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        let name = self.name;
+        let _ = writeln!(out, "# HELP {} {}", name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let series = self.series.lock().unwrap();
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&series.buckets) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, series.count);
+        let _ = writeln!(out, "{}_sum {}", name, series.sum);
+        let _ = writeln!(out, "{}_count {}", name, series.count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram() {
+        let histogram = LatencyHistogram::new("test_seconds", "Test latency", "phase");
+        histogram.observe("sign", Duration::from_micros(50));
+        histogram.observe("sign", Duration::from_millis(3));
+        histogram.observe("decode", Duration::from_secs(60));
+
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.starts_with("# HELP test_seconds Test latency\n"));
+        assert!(out.contains("# TYPE test_seconds histogram\n"));
+        assert!(out.contains("test_seconds_bucket{phase=\"sign\",le=\"0.0001\"} 1\n"));
+        assert!(out.contains("test_seconds_bucket{phase=\"sign\",le=\"0.005\"} 2\n"));
+        assert!(out.contains("test_seconds_bucket{phase=\"sign\",le=\"+Inf\"} 2\n"));
+        assert!(out.contains("test_seconds_count{phase=\"sign\"} 2\n"));
+        assert!(out.contains("test_seconds_bucket{phase=\"decode\",le=\"10\"} 0\n"));
+        assert!(out.contains("test_seconds_bucket{phase=\"decode\",le=\"+Inf\"} 1\n"));
+        assert!(out.contains("test_seconds_sum{phase=\"decode\"} 60\n"));
+
+        // Series are rendered in label order
+        assert!(out.find("phase=\"decode\"").unwrap() < out.find("phase=\"sign\"").unwrap());
+    }
+
+    #[test]
+    fn test_counter_and_value_histogram() {
+        let counter = Counter::new("test_total", "Test events");
+        counter.inc();
+        counter.inc();
+        let mut out = String::new();
+        counter.render(&mut out);
+        assert_eq!(out, "# HELP test_total Test events\n# TYPE test_total counter\ntest_total 2\n");
+
+        let histogram = ValueHistogram::new("test_depth", "Test depth", &[1, 2, 4]);
+        for depth in [1, 1, 3, 9] {
+            histogram.observe(depth);
+        }
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.contains("# TYPE test_depth histogram\n"));
+        assert!(out.contains("test_depth_bucket{le=\"1\"} 2\n"));
+        assert!(out.contains("test_depth_bucket{le=\"2\"} 2\n"));
+        assert!(out.contains("test_depth_bucket{le=\"4\"} 3\n"));
+        assert!(out.contains("test_depth_bucket{le=\"+Inf\"} 4\n"));
+        assert!(out.contains("test_depth_sum 14\n"));
+        assert!(out.contains("test_depth_count 4\n"));
+    }
+
+    #[test]
+    fn test_labeled_counter_is_bounded() {
+        let counter = LabeledCounter::new("test_total", "Test events", &["bridge_id", "outcome"]);
+        counter.inc(&["idp", "success"]);
+        counter.inc(&["idp", "success"]);
+        counter.inc(&["a\"b", "denied"]);
+        let mut out = String::new();
+        counter.render(&mut out);
+        assert!(out.contains("test_total{bridge_id=\"idp\",outcome=\"success\"} 2\n"));
+        assert!(out.contains("test_total{bridge_id=\"a\\\"b\",outcome=\"denied\"} 1\n"));
+
+        // Past the cap, new values share one series
+        for i in 0..MAX_SERIES * 2 {
+            counter.inc(&[&format!("bridge-{}", i), "success"]);
+        }
+        assert_eq!(counter.series_count(), MAX_SERIES + 1);
+        counter.inc(&["idp", "success"]);
+        let mut out = String::new();
+        counter.render(&mut out);
+        assert!(out.contains("test_total{bridge_id=\"idp\",outcome=\"success\"} 3\n"));
+        assert!(out.contains("test_total{bridge_id=\"overflow\",outcome=\"overflow\"} 1002\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be labelled by subject")]
+    fn test_labeled_counter_refuses_unbounded_labels() {
+        LabeledCounter::new("test_total", "Test events", &["bridge_id", "subject"]);
+    }
+}
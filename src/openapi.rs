@@ -0,0 +1,153 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! OpenAPI document of the HTTP gateway.
+//!
+//! Paths come from the `#[utoipa::path]` annotations on the handlers, so
+//! the document follows their signatures. Route groups are merged in only
+//! when the configuration enables them, matching what the gateway serves.
+
+use crate::bridge::{BridgeType, MappingTrace};
+use crate::config::Config;
+use crate::handlers::{self, *};
+use crate::info::{BuildInfo, Capabilities, VersionRange};
+use utoipa::openapi::OpenApi as OpenApiDocument;
+use utoipa::OpenApi;
+
+/// Routes served by every instance
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Permguard Trust Plane HTTP Gateway"),
+    paths(
+        handlers::health,
+        handlers::ready,
+        handlers::version,
+        handlers::capabilities,
+        handlers::cat_transition,
+        handlers::bridge_exchange,
+    ),
+    components(schemas(
+        BuildInfo,
+        Capabilities,
+        VersionRange,
+        BridgeType,
+        CatTransitionRequest,
+        CatTransitionResponse,
+        BridgeExchangeRequest,
+        BridgeExchangeResponse,
+        MappingTrace,
+    ))
+)]
+struct CoreApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(handlers::did_document))]
+struct DidDocumentApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(handlers::trustplane_metadata))]
+struct TrustPlaneMetadataApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(handlers::metrics))]
+struct MetricsApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(handlers::openapi))]
+struct OpenApiApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handlers::list_bridges,
+        handlers::list_bridge_types,
+        handlers::export_bridges,
+        handlers::import_bridges,
+        handlers::get_bridge,
+        handlers::remove_bridge,
+        handlers::enable_bridge,
+        handlers::disable_bridge,
+        handlers::undelete_bridge,
+    ),
+    components(schemas(BridgeInfo, ListBridgesResponse, DisableBridgeRequest))
+)]
+struct BridgeAdminApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handlers::revoke_subject,
+        handlers::unrevoke_subject,
+        handlers::rotate_keys,
+        handlers::did_preview,
+        handlers::get_subject_quota,
+        handlers::set_subject_quota,
+        handlers::clear_subject_quota,
+    ),
+    components(schemas(RotateKeysRequest, DidPreviewRequest, SubjectQuotaRequest))
+)]
+struct AdminApi;
+
+/// OpenAPI document for the routes enabled by `config`
+pub fn document(config: &Config) -> OpenApiDocument {
+    let mut doc = CoreApi::openapi();
+    doc.info.version = crate::version().to_string();
+    let groups = [
+        (config.serve_did_document, DidDocumentApi::openapi as fn() -> OpenApiDocument),
+        (config.serve_trustplane_metadata, TrustPlaneMetadataApi::openapi),
+        (config.metrics_enabled, MetricsApi::openapi),
+        (config.serve_openapi, OpenApiApi::openapi),
+        (config.bridge_admin_enabled, BridgeAdminApi::openapi),
+        (config.admin_enabled, AdminApi::openapi),
+    ];
+    for (enabled, group) in groups {
+        if enabled {
+            doc.merge(group());
+        }
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::Parser;
+
+    #[test]
+    fn test_document_follows_config() {
+        let mut config = Config::try_from(Cli::parse_from(["permguard-trustplane"])).unwrap();
+        config.bridge_admin_enabled = true;
+        config.admin_enabled = true;
+        let doc = serde_json::to_value(document(&config)).unwrap();
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        assert_eq!(doc["info"]["version"], crate::version());
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/v1/cat/transition"));
+        assert!(paths.contains_key("/v1/bridge-admin/bridges/{id}/undelete"));
+        assert!(paths["/v1/bridge-admin/bridges/{id}"]["delete"].is_object());
+        let schemas = &doc["components"]["schemas"];
+        assert!(schemas["BridgeExchangeRequest"]["properties"]["credential"].is_object());
+
+        config.bridge_admin_enabled = false;
+        config.admin_enabled = false;
+        config.metrics_enabled = false;
+        let doc = serde_json::to_value(document(&config)).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/v1/bridge/exchange"));
+        assert!(!paths.keys().any(|path| path.contains("admin") || path == "/metrics"));
+    }
+}
@@ -8,3 +8,6 @@ pub mod bridge;
 
 #[path = "permguard.trustplane.bridge_admin.v1.rs"]
 pub mod bridge_admin;
+
+#[path = "permguard.trustplane.info.v1.rs"]
+pub mod info;
@@ -1,12 +1,24 @@
 // This file is @generated by prost-build.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExchangeRequest {
-    /// Bridge configuration ID to use
+    /// Bridge configuration ID to use (empty: configured default bridge)
     #[prost(string, tag = "1")]
     pub bridge_id: ::prost::alloc::string::String,
     /// Raw credential (JWT, etc.)
     #[prost(bytes = "vec", tag = "2")]
     pub credential: ::prost::alloc::vec::Vec<u8>,
+    /// Optional: retries with the same key return the same PCA0
+    #[prost(string, tag = "3")]
+    pub idempotency_key: ::prost::alloc::string::String,
+    /// Optional: resource audience to bind the PCA0 to (default: bridge pca_audience; not recorded until minting lands)
+    #[prost(string, tag = "4")]
+    pub audience: ::prost::alloc::string::String,
+    /// Optional: must match the credential's nonce claim (required by require_nonce bridges)
+    #[prost(string, tag = "5")]
+    pub nonce: ::prost::alloc::string::String,
+    /// Optional: DPoP-style proof signed by the credential's cnf.jwk key (required by require_pop bridges)
+    #[prost(string, tag = "6")]
+    pub pop: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExchangeResponse {
@@ -16,6 +28,132 @@ pub struct ExchangeResponse {
     /// Error message if failed (empty on success)
     #[prost(string, tag = "2")]
     pub error: ::prost::alloc::string::String,
+    /// Machine-readable error code, e.g. subject_revoked (empty on success)
+    #[prost(string, tag = "3")]
+    pub error_code: ::prost::alloc::string::String,
+    /// Kid of the CAT key that signed pca (empty on error)
+    #[prost(string, tag = "4")]
+    pub signing_kid: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod bridge_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct BridgeServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl BridgeServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> BridgeServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> BridgeServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            BridgeServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Exchange external credential for initial PCA0
+        pub async fn exchange(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExchangeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ExchangeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge.v1.BridgeService/Exchange",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("permguard.trustplane.bridge.v1.BridgeService", "Exchange"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
 }
 /// Generated server implementations.
 pub mod bridge_service_server {
@@ -7,6 +7,21 @@ pub struct BridgeConfig {
     pub r#type: i32,
     #[prost(bool, tag = "3")]
     pub enabled: bool,
+    /// Optional: issuer DID for minted PCA0s (default: Trust Plane DID)
+    #[prost(string, tag = "4")]
+    pub pca_issuer: ::prost::alloc::string::String,
+    /// Why the bridge was disabled (empty when enabled)
+    #[prost(string, tag = "5")]
+    pub disabled_reason: ::prost::alloc::string::String,
+    /// Output only: revision, incremented on every write
+    #[prost(uint64, tag = "6")]
+    pub version: u64,
+    /// Optional: audience minted PCA0s are bound to when the request names none
+    #[prost(string, tag = "7")]
+    pub pca_audience: ::prost::alloc::string::String,
+    /// Output only: RFC 3339 time a removed bridge is deleted (empty unless removed)
+    #[prost(string, tag = "8")]
+    pub pending_deletion_at: ::prost::alloc::string::String,
     #[prost(oneof = "bridge_config::Config", tags = "10")]
     pub config: ::core::option::Option<bridge_config::Config>,
 }
@@ -22,17 +37,46 @@ pub mod bridge_config {
 pub struct JwtBridgeConfig {
     #[prost(string, tag = "1")]
     pub wellknown_url: ::prost::alloc::string::String,
+    /// Single issuer (kept for compatibility, merged into issuers)
     #[prost(string, tag = "2")]
     pub issuer: ::prost::alloc::string::String,
     #[prost(string, repeated, tag = "3")]
     pub audiences: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(message, optional, tag = "4")]
     pub mapping: ::core::option::Option<MappingConfig>,
+    /// Accepted issuers; the token's iss must match one
+    #[prost(string, repeated, tag = "5")]
+    pub issuers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Accepted JWS algorithms (empty: any asymmetric); none/HS* always rejected
+    #[prost(string, repeated, tag = "6")]
+    pub allowed_algorithms: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Tolerance for exp and nbf (unset: 60)
+    #[prost(uint64, optional, tag = "7")]
+    pub max_clock_skew_secs: ::core::option::Option<u64>,
+    /// Reject tokens older than this by iat (unset: no limit)
+    #[prost(uint64, optional, tag = "8")]
+    pub max_token_age_secs: ::core::option::Option<u64>,
+    /// Reject tokens without an aud claim
+    #[prost(bool, tag = "9")]
+    pub require_audience: bool,
+    /// RFC 7638 thumbprints of the only JWKS keys to trust (empty: all; refused until JWKS keys are fetched)
+    #[prost(string, repeated, tag = "10")]
+    pub pinned_thumbprints: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Exchanges must present the token's nonce claim
+    #[prost(bool, tag = "11")]
+    pub require_nonce: bool,
+    /// Exchanges must prove possession of the token's cnf.jwk key (refused until JWKS keys are fetched)
+    #[prost(bool, tag = "12")]
+    pub require_pop: bool,
+    /// PCA0 attributes every exchange must produce
+    #[prost(string, repeated, tag = "13")]
+    pub required_pca_attributes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MappingConfig {
     #[prost(string, tag = "1")]
     pub subject_claim: ::prost::alloc::string::String,
+    /// Single claim (kept for compatibility, merged into organization_claims)
     #[prost(string, tag = "2")]
     pub organization_claim: ::prost::alloc::string::String,
     #[prost(map = "string, string", tag = "3")]
@@ -40,6 +84,30 @@ pub struct MappingConfig {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    /// Only these claims may be mapped (empty: all)
+    #[prost(string, repeated, tag = "4")]
+    pub allowed_claims: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// These claims are never mapped
+    #[prost(string, repeated, tag = "5")]
+    pub denied_claims: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Candidate organization claims, first present wins
+    #[prost(string, repeated, tag = "6")]
+    pub organization_claims: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Optional: organization when no candidate claim is present
+    #[prost(string, tag = "7")]
+    pub default_organization: ::prost::alloc::string::String,
+    /// Static attributes set on every PCA0
+    #[prost(map = "string, string", tag = "8")]
+    pub initial_attributes: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// Prefix claim attributes with "claims.", static ones with "config."
+    #[prost(bool, tag = "9")]
+    pub namespace_attributes: bool,
+    /// Copy every permitted claim except exp, nbf, iat and jti
+    #[prost(bool, tag = "10")]
+    pub copy_all_claims: bool,
 }
 /// List
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -80,6 +148,9 @@ pub struct AddBridgeResponse {
 pub struct UpdateBridgeRequest {
     #[prost(message, optional, tag = "1")]
     pub bridge: ::core::option::Option<BridgeConfig>,
+    /// Optional: fail if the bridge was written since this version
+    #[prost(uint64, optional, tag = "2")]
+    pub expected_version: ::core::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateBridgeResponse {
@@ -93,6 +164,9 @@ pub struct UpdateBridgeResponse {
 pub struct RemoveBridgeRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
+    /// Optional: fail if the bridge was written since this version
+    #[prost(uint64, optional, tag = "2")]
+    pub expected_version: ::core::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RemoveBridgeResponse {
@@ -101,6 +175,43 @@ pub struct RemoveBridgeResponse {
     #[prost(string, tag = "2")]
     pub error: ::prost::alloc::string::String,
 }
+/// Enable / disable
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetBridgeEnabledRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub enabled: bool,
+    /// Optional: recorded as disabled_reason when disabling
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+    /// Optional: fail if the bridge was written since this version
+    #[prost(uint64, optional, tag = "4")]
+    pub expected_version: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetBridgeEnabledResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
+}
+/// Undelete
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UndeleteBridgeRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    /// Optional: fail if the bridge was written since this version
+    #[prost(uint64, optional, tag = "2")]
+    pub expected_version: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UndeleteBridgeResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
+}
 /// Bridge Types
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -136,6 +247,309 @@ impl BridgeType {
         }
     }
 }
+/// Generated client implementations.
+pub mod bridge_admin_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct BridgeAdminServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl BridgeAdminServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> BridgeAdminServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> BridgeAdminServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            BridgeAdminServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// List all configured bridges
+        pub async fn list_bridges(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListBridgesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListBridgesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/ListBridges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "ListBridges",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get a specific bridge configuration
+        pub async fn get_bridge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetBridgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/GetBridge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "GetBridge",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Add a new bridge configuration
+        pub async fn add_bridge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddBridgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/AddBridge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "AddBridge",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Update an existing bridge configuration
+        pub async fn update_bridge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateBridgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/UpdateBridge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "UpdateBridge",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Remove a bridge configuration
+        pub async fn remove_bridge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveBridgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/RemoveBridge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "RemoveBridge",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Enable or disable a bridge without replacing its configuration
+        pub async fn set_bridge_enabled(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetBridgeEnabledRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetBridgeEnabledResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/SetBridgeEnabled",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "SetBridgeEnabled",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancel the pending deletion of a removed bridge
+        pub async fn undelete_bridge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UndeleteBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UndeleteBridgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/UndeleteBridge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.bridge_admin.v1.BridgeAdminService",
+                        "UndeleteBridge",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
 /// Generated server implementations.
 pub mod bridge_admin_service_server {
     #![allow(
@@ -189,6 +603,22 @@ pub mod bridge_admin_service_server {
             tonic::Response<super::RemoveBridgeResponse>,
             tonic::Status,
         >;
+        /// Enable or disable a bridge without replacing its configuration
+        async fn set_bridge_enabled(
+            &self,
+            request: tonic::Request<super::SetBridgeEnabledRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetBridgeEnabledResponse>,
+            tonic::Status,
+        >;
+        /// Cancel the pending deletion of a removed bridge
+        async fn undelete_bridge(
+            &self,
+            request: tonic::Request<super::UndeleteBridgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UndeleteBridgeResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct BridgeAdminServiceServer<T> {
@@ -494,6 +924,96 @@ pub mod bridge_admin_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/SetBridgeEnabled" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetBridgeEnabledSvc<T: BridgeAdminService>(pub Arc<T>);
+                    impl<
+                        T: BridgeAdminService,
+                    > tonic::server::UnaryService<super::SetBridgeEnabledRequest>
+                    for SetBridgeEnabledSvc<T> {
+                        type Response = super::SetBridgeEnabledResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetBridgeEnabledRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as BridgeAdminService>::set_bridge_enabled(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetBridgeEnabledSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/permguard.trustplane.bridge_admin.v1.BridgeAdminService/UndeleteBridge" => {
+                    #[allow(non_camel_case_types)]
+                    struct UndeleteBridgeSvc<T: BridgeAdminService>(pub Arc<T>);
+                    impl<
+                        T: BridgeAdminService,
+                    > tonic::server::UnaryService<super::UndeleteBridgeRequest>
+                    for UndeleteBridgeSvc<T> {
+                        type Response = super::UndeleteBridgeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UndeleteBridgeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as BridgeAdminService>::undelete_bridge(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UndeleteBridgeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -4,6 +4,9 @@ pub struct TransitionRequest {
     /// Current PCA (CBOR)
     #[prost(bytes = "vec", tag = "1")]
     pub pca: ::prost::alloc::vec::Vec<u8>,
+    /// Optional: audience the PCA must be bound to (not checked until PCAs record it)
+    #[prost(string, tag = "2")]
+    pub expected_audience: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransitionResponse {
@@ -13,6 +16,165 @@ pub struct TransitionResponse {
     /// Error message if failed (empty on success)
     #[prost(string, tag = "2")]
     pub error: ::prost::alloc::string::String,
+    /// Machine-readable error code, e.g. subject_revoked (empty on success)
+    #[prost(string, tag = "3")]
+    pub error_code: ::prost::alloc::string::String,
+    /// Kid of the CAT key that signed pca (empty on error)
+    #[prost(string, tag = "4")]
+    pub signing_kid: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod cat_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct CatServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl CatServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> CatServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> CatServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            CatServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Transition: PCA_{n} → PCA_{n+1}
+        pub async fn transition(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TransitionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TransitionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.cat.v1.CatService/Transition",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("permguard.trustplane.cat.v1.CatService", "Transition"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// TransitionStream: one successor per PCA, in order, on a long-lived stream.
+        /// Per-message failures are reported in-band and do not end the stream.
+        pub async fn transition_stream(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::TransitionRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::TransitionResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/permguard.trustplane.cat.v1.CatService/TransitionStream",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "permguard.trustplane.cat.v1.CatService",
+                        "TransitionStream",
+                    ),
+                );
+            self.inner.streaming(req, path, codec).await
+        }
+    }
 }
 /// Generated server implementations.
 pub mod cat_service_server {
@@ -35,6 +197,21 @@ pub mod cat_service_server {
             tonic::Response<super::TransitionResponse>,
             tonic::Status,
         >;
+        /// Server streaming response type for the TransitionStream method.
+        type TransitionStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::TransitionResponse, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// TransitionStream: one successor per PCA, in order, on a long-lived stream.
+        /// Per-message failures are reported in-band and do not end the stream.
+        async fn transition_stream(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::TransitionRequest>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::TransitionStreamStream>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct CatServiceServer<T> {
@@ -157,6 +334,55 @@ pub mod cat_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/permguard.trustplane.cat.v1.CatService/TransitionStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct TransitionStreamSvc<T: CatService>(pub Arc<T>);
+                    impl<
+                        T: CatService,
+                    > tonic::server::StreamingService<super::TransitionRequest>
+                    for TransitionStreamSvc<T> {
+                        type Response = super::TransitionResponse;
+                        type ResponseStream = T::TransitionStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::TransitionRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CatService>::transition_stream(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = TransitionStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -0,0 +1,114 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Redaction of secrets in logs.
+//!
+//! Credentials, JWTs and PCAs are logged only through [`redact`], which
+//! shows their length and a short SHA-256 prefix: enough to correlate log
+//! lines about the same token, never enough to replay it. JWKs, private
+//! ones included, are logged through [`redact_jwk`]: kid and thumbprint.
+
+use crate::bridge::jwk_thumbprint;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Hex digits of the SHA-256 prefix shown for a redacted value
+const FINGERPRINT_HEX_LEN: usize = 12;
+
+/// Secret bytes that format as their length and fingerprint only
+#[derive(Clone, Copy)]
+pub struct Redacted<'a>(&'a [u8]);
+
+/// Wrap secret bytes (a credential, a PCA, key material) for logging
+pub fn redact(secret: &[u8]) -> Redacted<'_> {
+    Redacted(secret)
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digest = Sha256::digest(self.0);
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        write!(
+            f,
+            "[redacted {} bytes sha256:{}]",
+            self.0.len(),
+            &hex[..FINGERPRINT_HEX_LEN]
+        )
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// JWK that formats as its kid and RFC 7638 thumbprint only
+#[derive(Clone, Copy)]
+pub struct RedactedJwk<'a>(&'a Value);
+
+/// Wrap a JWK, possibly carrying private members, for logging
+pub fn redact_jwk(jwk: &Value) -> RedactedJwk<'_> {
+    RedactedJwk(jwk)
+}
+
+impl fmt::Display for RedactedJwk<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kid = self.0.get("kid").and_then(Value::as_str).unwrap_or("-");
+        let thumbprint = jwk_thumbprint(self.0).unwrap_or_else(|| "-".to_string());
+        write!(f, "[jwk kid={} thumbprint={}]", kid, thumbprint)
+    }
+}
+
+impl fmt::Debug for RedactedJwk<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let token = b"eyJhbGciOiJFZERTQSJ9.eyJzdWIiOiJhbGljZSJ9.c2ln";
+        let shown = redact(token).to_string();
+        assert!(shown.starts_with("[redacted 46 bytes sha256:"), "{}", shown);
+        assert!(!shown.contains("eyJ"));
+        assert_eq!(format!("{:?}", redact(token)), shown);
+
+        // Same token, same fingerprint; another token, another one
+        assert_eq!(redact(token).to_string(), shown);
+        assert_ne!(redact(b"other").to_string(), shown);
+    }
+
+    #[test]
+    fn test_redact_jwk() {
+        let key = crate::credentials::KeyPair::generate("did:web:example.com#key-1");
+        let mut jwk = key.private_jwk();
+        jwk["kid"] = key.kid().into();
+        let shown = redact_jwk(&jwk).to_string();
+        let thumbprint = jwk_thumbprint(&key.public_jwk()).unwrap();
+        assert_eq!(
+            shown,
+            format!("[jwk kid=did:web:example.com#key-1 thumbprint={}]", thumbprint)
+        );
+        assert!(!shown.contains(jwk["d"].as_str().unwrap()));
+        assert_eq!(redact_jwk(&serde_json::json!({})).to_string(), "[jwk kid=- thumbprint=-]");
+    }
+}
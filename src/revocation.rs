@@ -0,0 +1,195 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Subject revocation list.
+//!
+//! Revoked subjects (user IDs or DIDs) get no PCA₀ from the bridge and no
+//! successor PCA from CAT.
+
+use crate::error::{Error, Result};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::info;
+
+/// Revocation list file name under `appdata`
+pub const REVOCATIONS_FILE: &str = "revocations.json";
+
+/// Set of revoked subjects, optionally persisted as a JSON array
+#[derive(Debug, Default)]
+pub struct RevocationStore {
+    revoked: RwLock<HashSet<String>>,
+    path: Option<PathBuf>,
+}
+
+impl RevocationStore {
+    /// Create an empty, memory-only store
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open a file-backed store, starting empty if the file does not exist
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let revoked = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice::<Vec<String>>(&data)
+                .map_err(|e| {
+                    Error::Config(format!("Invalid revocation list {}: {}", path.display(), e))
+                })?
+                .into_iter()
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        info!(path = %path.display(), revoked = revoked.len(), "Loaded revocation list");
+
+        Ok(Self {
+            revoked: RwLock::new(revoked),
+            path: Some(path),
+        })
+    }
+
+    /// Whether the subject is revoked
+    pub fn is_revoked(&self, subject: &str) -> bool {
+        self.revoked.read().unwrap().contains(subject)
+    }
+
+    /// Fail with [`Error::SubjectRevoked`] if the subject is revoked
+    pub fn check(&self, subject: &str) -> Result<()> {
+        if self.is_revoked(subject) {
+            return Err(Error::SubjectRevoked(subject.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Revoke a subject. Returns false if it was already revoked.
+    pub fn revoke(&self, subject: &str) -> Result<bool> {
+        if subject.is_empty() {
+            return Err(Error::Invalid("subject is required".to_string()));
+        }
+        let mut revoked = self.revoked.write().unwrap();
+        if !revoked.insert(subject.to_string()) {
+            return Ok(false);
+        }
+        if let Err(e) = self.persist(&revoked) {
+            revoked.remove(subject);
+            return Err(e);
+        }
+        info!(subject = %subject, "Subject revoked");
+        Ok(true)
+    }
+
+    /// Lift a revocation. Returns false if the subject was not revoked.
+    pub fn unrevoke(&self, subject: &str) -> Result<bool> {
+        let mut revoked = self.revoked.write().unwrap();
+        if !revoked.remove(subject) {
+            return Ok(false);
+        }
+        if let Err(e) = self.persist(&revoked) {
+            revoked.insert(subject.to_string());
+            return Err(e);
+        }
+        info!(subject = %subject, "Subject revocation lifted");
+        Ok(true)
+    }
+
+    /// Number of revoked subjects
+    pub fn len(&self) -> usize {
+        self.revoked.read().unwrap().len()
+    }
+
+    /// Whether no subject is revoked
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Durably write the list to disk.
+    ///
+    /// Returns the backing file, or `None` for a memory-only store.
+    pub fn flush(&self) -> Result<Option<&Path>> {
+        let revoked = self.revoked.read().unwrap();
+        self.persist(&revoked)?;
+        Ok(self.path.as_deref())
+    }
+
+    /// Write the list atomically (temp file + fsync + rename)
+    fn persist(&self, revoked: &HashSet<String>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut subjects: Vec<&String> = revoked.iter().collect();
+        subjects.sort();
+        let data = serde_json::to_vec_pretty(&subjects)
+            .map_err(|e| Error::Internal(format!("Failed to encode revocation list: {}", e)))?;
+
+        let tmp = tmp_path(path);
+        {
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(&data)?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp, path)?;
+
+        // Make the rename itself durable
+        #[cfg(unix)]
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revocation_store_persists() {
+        let path = std::env::temp_dir().join(format!(
+            "permguard-revocations-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let store = RevocationStore::open(&path).unwrap();
+        assert!(store.is_empty());
+        assert!(store.revoke("did:web:mallory.example.com").unwrap());
+        assert!(!store.revoke("did:web:mallory.example.com").unwrap());
+        assert!(store.revoke("eve").unwrap());
+        assert!(store.unrevoke("eve").unwrap());
+        assert!(!store.unrevoke("eve").unwrap());
+
+        let reopened = RevocationStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert!(reopened.is_revoked("did:web:mallory.example.com"));
+        assert!(matches!(
+            reopened.check("did:web:mallory.example.com"),
+            Err(Error::SubjectRevoked(_))
+        ));
+        assert!(reopened.check("alice").is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -16,47 +16,157 @@
 
 //! Server orchestration.
 
-use crate::bridge::{BridgeRegistry, BridgeServiceImpl};
+use crate::bridge::{
+    BridgeDir, BridgeOptions, BridgeRegistry, BridgeServiceImpl, CredentialLimits,
+};
 use crate::bridge_admin::BridgeAdminServiceImpl;
-use crate::cat::CatServiceImpl;
-use crate::config::Config;
-use crate::credentials::{CredentialsManager, InMemoryProvider};
-use crate::error::Result;
+use crate::catch_panic;
+use crate::content_type;
+use crate::descriptor;
+use crate::concurrency::ConcurrencyLimit;
+use crate::cat::{
+    AllowAll, CatServiceImpl, PcaLimits, SubjectDenyList, SubjectQuota,
+    TransitionPolicy,
+};
+use crate::config::{Config, CredentialSource};
+use crate::credentials::{
+    CredentialProvider, CredentialsManager, FileProvider, InMemoryProvider, TenantCredentials,
+    DEFAULT_PCA_SIGNING_CONTEXT,
+};
+use crate::error::{Error, Result};
 use crate::handlers::{self, AppState};
+use crate::revocation::{RevocationStore, REVOCATIONS_FILE};
+use crate::info::{Capabilities, InfoServiceImpl};
+use crate::openapi;
+use crate::systemd;
+use crate::proto::{
+    bridge::bridge_service_server, bridge_admin::bridge_admin_service_server,
+    cat::cat_service_server, info::info_service_server,
+};
 use axum::{
     routing::{delete, get, post},
     Router,
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
 use tokio::signal;
+use tonic::transport::server::TcpIncoming;
 use tonic::transport::Server as TonicServer;
-use tracing::info;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+use tower_http::compression::CompressionLayer;
+use tracing::{info, warn};
 
 /// File descriptor for gRPC reflection
 const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("proto/descriptor.bin");
 
+/// How often bridges past their deletion grace are purged
+const BRIDGE_PURGE_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Trust Plane server
 pub struct Server {
     config: Config,
-    credentials: Arc<CredentialsManager>,
+    tenants: Arc<TenantCredentials>,
     bridge_registry: Arc<BridgeRegistry>,
+    bridge_dir: Option<Arc<BridgeDir>>,
+    revocations: Arc<RevocationStore>,
 }
 
 impl Server {
-    /// Create new server with default in-memory provider
+    /// Create new server with the configured credential provider
     pub async fn new(config: Config) -> Result<Self> {
-        let provider = InMemoryProvider {
-            did: config.did.clone(),
-            organization: config.organization.clone(),
+        let load = |did: &str| -> Result<Arc<CredentialsManager>> {
+            let provider: Box<dyn CredentialProvider> = match &config.credential_source {
+                CredentialSource::InMemory => Box::new(InMemoryProvider {
+                    did: did.to_string(),
+                    organization: config.organization.clone(),
+                    key_format: config.did_key_format,
+                    issuance_date: config.credential_issuance_date,
+                    subject_fields: config.credential_subject.clone(),
+                }),
+                CredentialSource::File {
+                    issuer_key_path,
+                    cat_key_path,
+                    did_doc_path,
+                    credential_path,
+                    allow_shared_keys,
+                    warn_key_age_months,
+                    fail_on_stale_keys,
+                } => Box::new(FileProvider {
+                    issuer_key_path: issuer_key_path.clone(),
+                    cat_key_path: cat_key_path.clone(),
+                    did_doc_path: did_doc_path.clone(),
+                    credential_path: credential_path.clone(),
+                    allow_shared_keys: *allow_shared_keys,
+                    warn_key_age_months: *warn_key_age_months,
+                    fail_on_stale_keys: *fail_on_stale_keys,
+                }),
+            };
+            let credentials = Arc::new(CredentialsManager::with_provider(provider)?);
+            let loaded = &credentials.current().did;
+            if loaded != did {
+                warn!(
+                    did = %loaded,
+                    configured = %did,
+                    "Loaded DID document is for another DID than --did"
+                );
+            }
+            if !config.skip_startup_selftest {
+                credentials.current().self_test()?;
+            }
+            Ok(credentials)
+        };
+
+        if let Some(mismatch) = config.did_host_mismatch() {
+            warn!("{} (use --strict-did-host to refuse to start)", mismatch);
+        }
+
+        let mut tenants = TenantCredentials::new(load(&config.did)?);
+        for did in &config.tenant_dids {
+            tenants.insert(load(did)?)?;
+        }
+        if !config.tenant_dids.is_empty() {
+            info!(dids = ?tenants.dids(), "Hosting multiple DIDs");
+        }
+
+        if config.skip_startup_selftest {
+            warn!("Startup signing self-test skipped");
+        } else {
+            info!("Startup signing self-test passed");
+        }
+        let bridge_registry =
+            Arc::new(BridgeRegistry::new().with_delete_grace(config.bridge_delete_grace));
+        let bridge_dir = match &config.bridges_dir {
+            Some(dir) => Some(Arc::new(BridgeDir::load(dir, bridge_registry.clone())?)),
+            None => None,
         };
+        let revocations = Arc::new(RevocationStore::open(
+            config.appdata.join(REVOCATIONS_FILE),
+        )?);
 
-        let credentials = Arc::new(CredentialsManager::from_provider(&provider)?);
-        let bridge_registry = Arc::new(BridgeRegistry::new());
+        if config.log_pca_attributes_values {
+            warn!("PCA attribute values will be logged at debug level - never enable this in production!");
+        }
+        // CAT transitions stop before decoding the input PCA, so checks on
+        // its contents cannot run yet
+        if config.strict_sequence {
+            warn_not_enforced("--strict-sequence");
+        }
+        if !config.cat_deny_subjects.is_empty() {
+            warn_not_enforced("--cat-deny-subjects");
+        }
+        if config.cat_subject_quota > 0 {
+            warn_not_enforced("--cat-subject-quota");
+        }
 
         Ok(Self {
             config,
-            credentials,
+            tenants: Arc::new(tenants),
             bridge_registry,
+            bridge_dir,
+            revocations,
         })
     }
 
@@ -65,62 +175,215 @@ impl Server {
         let http_addr = self.config.http_addr;
         let grpc_addr = self.config.grpc_addr;
 
+        // Bridge exchange is shared by gRPC and HTTP so idempotency keys
+        // are honoured across both front-ends
+        let bridge_service = BridgeServiceImpl::new(
+            self.tenants.default_tenant().clone(),
+            self.bridge_registry.clone(),
+            self.revocations.clone(),
+            BridgeOptions {
+                log_pca_values: self.config.log_pca_attributes_values,
+                idempotency_ttl: self.config.idempotency_ttl,
+                idempotency_capacity: self.config.idempotency_cache_size,
+                limits: CredentialLimits {
+                    max_token_bytes: self.config.max_credential_bytes,
+                    max_claims: self.config.max_credential_claims,
+                    max_value_len: self.config.max_claim_value_bytes,
+                },
+                mapping_trace: self.config.bridge_mapping_trace,
+                cache_exchanges: self.config.cache_exchanges,
+                default_bridge_id: self.config.default_bridge_id.clone(),
+                exchange_cache_ttl: self.config.exchange_cache_ttl,
+                exchange_cache_capacity: self.config.exchange_cache_size,
+                max_concurrent_exchanges: self.config.max_concurrent_exchanges,
+                // Fixed until PCAs record the context they are signed under
+                signing_context: DEFAULT_PCA_SIGNING_CONTEXT.to_string(),
+            },
+        )
+        .with_tenants(self.tenants.clone());
+        let bridge_service = Arc::new(bridge_service);
+
+        // CAT transition policy
+        let transition_policy: Arc<dyn TransitionPolicy> =
+            if self.config.cat_deny_subjects.is_empty() {
+                Arc::new(AllowAll)
+            } else {
+                Arc::new(SubjectDenyList::new(self.config.cat_deny_subjects.clone()))
+            };
+
+        // Per-subject quota, shared with the admin endpoints for overrides
+        let quota = Arc::new(SubjectQuota::new(self.config.cat_subject_quota));
+
+        // CAT transitions are shared by gRPC and HTTP so both front-ends
+        // draw on the same concurrency limit and enforce the same checks
+        let cat_service = Arc::new(
+            CatServiceImpl::new(self.tenants.default_tenant().clone())
+                .with_policy(transition_policy)
+                .with_strict_sequence(self.config.strict_sequence)
+                .with_revocations(self.revocations.clone())
+                .with_quota(quota.clone())
+                .with_concurrency(ConcurrencyLimit::new(
+                    "transition",
+                    self.config.max_concurrent_transitions,
+                ))
+                .with_limits(PcaLimits {
+                    max_attributes: self.config.max_pca_attributes,
+                    max_bytes: self.config.max_pca_bytes,
+                })
+                .with_tenants(self.tenants.clone()),
+        );
+
         // Shared state for HTTP handlers
         let state = AppState {
-            credentials: self.credentials.clone(),
+            credentials: self.tenants.clone(),
             registry: self.bridge_registry.clone(),
+            bridge: bridge_service.clone(),
+            cat: cat_service.clone(),
+            revocations: self.revocations.clone(),
+            quota: quota.clone(),
+            capabilities: Arc::new(Capabilities::new(&self.config)),
+            openapi: Arc::new(openapi::document(&self.config)),
         };
 
         // ====================================================================
         // HTTP Gateway
         // ====================================================================
         let mut http_router = Router::new()
-            // Discovery
-            .route("/.well-known/did.json", get(handlers::did_document))
-            .route(
-                "/.well-known/trustplane.json",
-                get(handlers::trustplane_metadata),
-            )
-            // Health
-            .route("/health", get(handlers::health))
-            .route("/ready", get(handlers::ready))
+            // Info
+            .route("/v1/version", get(handlers::version))
+            .route("/v1/capabilities", get(handlers::capabilities))
             // CAT
             .route("/v1/cat/transition", post(handlers::cat_transition))
             // Bridge
             .route("/v1/bridge/exchange", post(handlers::bridge_exchange));
 
+        // Discovery (optional, e.g. when a CDN serves static copies)
+        let did_document_paths = self.tenants.document_paths();
+        if self.config.serve_did_document {
+            for path in &did_document_paths {
+                http_router = http_router.route(path, get(handlers::did_document));
+            }
+        }
+        if self.config.serve_trustplane_metadata {
+            http_router = http_router.route(
+                "/.well-known/trustplane.json",
+                get(handlers::trustplane_metadata),
+            );
+        }
+
         // Metrics (optional)
         if self.config.metrics_enabled {
             http_router = http_router.route("/metrics", get(handlers::metrics));
         }
 
+        // OpenAPI document (optional)
+        if self.config.serve_openapi {
+            http_router = http_router.route("/openapi.json", get(handlers::openapi));
+        }
+
         // Bridge Admin (optional)
         if self.config.bridge_admin_enabled {
             http_router = http_router
                 .route("/v1/bridge-admin/bridges", get(handlers::list_bridges))
+                .route(
+                    "/v1/bridge-admin/bridge-types",
+                    get(handlers::list_bridge_types),
+                )
+                .route(
+                    "/v1/bridge-admin/bridges/export",
+                    get(handlers::export_bridges),
+                )
+                .route(
+                    "/v1/bridge-admin/bridges/import",
+                    post(handlers::import_bridges),
+                )
                 .route("/v1/bridge-admin/bridges/:id", get(handlers::get_bridge))
                 .route(
                     "/v1/bridge-admin/bridges/:id",
                     delete(handlers::remove_bridge),
+                )
+                .route(
+                    "/v1/bridge-admin/bridges/:id/enable",
+                    post(handlers::enable_bridge),
+                )
+                .route(
+                    "/v1/bridge-admin/bridges/:id/disable",
+                    post(handlers::disable_bridge),
+                )
+                .route(
+                    "/v1/bridge-admin/bridges/:id/undelete",
+                    post(handlers::undelete_bridge),
                 );
         }
 
-        let http_router = http_router.with_state(state);
+        // Admin (optional)
+        if self.config.admin_enabled {
+            http_router = http_router.route(
+                "/v1/admin/revocations/:subject",
+                post(handlers::revoke_subject).delete(handlers::unrevoke_subject),
+            )
+            .route("/v1/admin/keys/rotate", post(handlers::rotate_keys))
+            .route("/v1/admin/did-preview", post(handlers::did_preview))
+            .route(
+                "/v1/admin/quotas/:subject",
+                get(handlers::get_subject_quota)
+                    .put(handlers::set_subject_quota)
+                    .delete(handlers::clear_subject_quota),
+            );
+        }
+
+        // Compression wraps the routes above; health probes stay uncompressed
+        if self.config.http_compression {
+            http_router = http_router.layer(CompressionLayer::new());
+        }
+        http_router = http_router.layer(axum::middleware::from_fn(content_type::require_json));
+
+        let http_router = http_router
+            // Health
+            .route("/health", get(handlers::health))
+            .route("/ready", get(handlers::ready))
+            .with_state(state)
+            .layer(catch_panic::http_layer());
 
         // ====================================================================
         // gRPC Server with Reflection
         // ====================================================================
+        descriptor::warn_if_stale(FILE_DESCRIPTOR_SET);
         let reflection_service = tonic_reflection::server::Builder::configure()
             .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
             .build_v1()?;
 
-        let mut grpc_builder = TonicServer::builder()
+        // Health: every registered service is SERVING until shutdown starts
+        let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+        let mut grpc_services = vec![
+            cat_service_server::SERVICE_NAME,
+            bridge_service_server::SERVICE_NAME,
+            info_service_server::SERVICE_NAME,
+        ];
+        if self.config.bridge_admin_enabled {
+            grpc_services.push(bridge_admin_service_server::SERVICE_NAME);
+        }
+        for service in &grpc_services {
+            health_reporter
+                .set_service_status(service, ServingStatus::Serving)
+                .await;
+        }
+
+        let enabled = |duration: Duration| (!duration.is_zero()).then_some(duration);
+        let mut grpc_server = TonicServer::builder()
+            .http2_keepalive_interval(enabled(self.config.grpc_http2_keepalive_interval))
+            .http2_keepalive_timeout(Some(self.config.grpc_http2_keepalive_timeout));
+        if let Some(age) = enabled(self.config.grpc_max_connection_age) {
+            grpc_server = grpc_server.max_connection_age(age);
+        }
+        let mut grpc_builder = grpc_server
+            .layer(catch_panic::grpc_layer())
             .add_service(reflection_service)
-            .add_service(CatServiceImpl::new(self.credentials.clone()).into_server())
-            .add_service(
-                BridgeServiceImpl::new(self.credentials.clone(), self.bridge_registry.clone())
-                    .into_server(),
-            );
+            .add_service(health_service)
+            .add_service(cat_service.into_shared_server())
+            .add_service(InfoServiceImpl::new().into_server())
+            .add_service(bridge_service.into_shared_server());
 
         // Bridge Admin gRPC (optional)
         if self.config.bridge_admin_enabled {
@@ -134,24 +397,56 @@ impl Server {
         info!("[TRUST-PLANE]: Starting servers");
         info!("");
         info!("  HTTP Gateway: http://{}", http_addr);
-        info!("    GET  /.well-known/did.json");
-        info!("    GET  /.well-known/trustplane.json");
+        if self.config.http_compression {
+            info!("    Compression: gzip, br");
+        }
+        if self.config.serve_did_document {
+            for path in &did_document_paths {
+                info!("    GET  {}", path);
+            }
+        }
+        if self.config.serve_trustplane_metadata {
+            info!("    GET  /.well-known/trustplane.json");
+        }
         info!("    GET  /health");
         info!("    GET  /ready");
+        info!("    GET  /v1/version");
+        info!("    GET  /v1/capabilities");
         if self.config.metrics_enabled {
             info!("    GET  /metrics");
         }
+        if self.config.serve_openapi {
+            info!("    GET  /openapi.json");
+        }
         info!("    POST /v1/cat/transition");
         info!("    POST /v1/bridge/exchange");
         if self.config.bridge_admin_enabled {
             info!("    GET  /v1/bridge-admin/bridges");
+            info!("    GET  /v1/bridge-admin/bridge-types");
+            info!("    GET  /v1/bridge-admin/bridges/export");
+            info!("    POST /v1/bridge-admin/bridges/import");
             info!("    GET  /v1/bridge-admin/bridges/:id");
             info!("    DELETE /v1/bridge-admin/bridges/:id");
+            info!("    POST /v1/bridge-admin/bridges/:id/enable");
+            info!("    POST /v1/bridge-admin/bridges/:id/disable");
+            info!("    POST /v1/bridge-admin/bridges/:id/undelete");
+        }
+        if self.config.admin_enabled {
+            info!("    POST /v1/admin/revocations/:subject");
+            info!("    DELETE /v1/admin/revocations/:subject");
+            info!("    POST /v1/admin/keys/rotate");
+            info!("    POST /v1/admin/did-preview");
+            info!("    GET  /v1/admin/quotas/:subject");
+            info!("    PUT  /v1/admin/quotas/:subject");
+            info!("    DELETE /v1/admin/quotas/:subject");
         }
         info!("");
         info!("  gRPC Server: {}", grpc_addr);
+        info!("    grpc.health.v1.Health");
         info!("    CatService.Transition");
+        info!("    CatService.TransitionStream");
         info!("    BridgeService.Exchange");
+        info!("    InfoService.GetVersion");
         if self.config.bridge_admin_enabled {
             info!("    BridgeAdminService.*");
         } else {
@@ -162,10 +457,25 @@ impl Server {
         // ====================================================================
         // Start servers
         // ====================================================================
-        let http_listener = tokio::net::TcpListener::bind(http_addr).await?;
+        let activated = systemd::listeners()?;
+        let http_listener = listen("HTTP", activated.http, http_addr).await?;
         let http_server = axum::serve(http_listener, http_router);
 
-        let grpc_server = grpc_builder.serve(grpc_addr);
+        let grpc_listener = listen("gRPC", activated.grpc, grpc_addr).await?;
+        let grpc_incoming = TcpIncoming::from_listener(grpc_listener, false, None)
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        let grpc_server = grpc_builder.serve_with_incoming(grpc_incoming);
+
+        if !self.config.bridge_delete_grace.is_zero() {
+            tokio::spawn(purge_deleted_bridges(self.bridge_registry.clone()));
+        }
+        if let Some(bridge_dir) = self.bridge_dir.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = bridge_dir.watch().await {
+                    tracing::error!(error = %e, "Bridges directory no longer watched");
+                }
+            });
+        }
 
         // Run both servers concurrently
         tokio::select! {
@@ -179,18 +489,75 @@ impl Server {
                     tracing::error!(error = %e, "gRPC server error");
                 }
             }
-            _ = shutdown_signal() => {
+            _ = shutdown_signal(health_reporter, grpc_services, self.config.drain_delay) => {
                 info!("Received shutdown signal");
             }
         }
 
+        self.flush_state();
+
         info!("Server shutdown complete");
         Ok(())
     }
+
+    /// Durably write persisted state before exit
+    fn flush_state(&self) {
+        match self.revocations.flush() {
+            Ok(Some(path)) => info!(
+                path = %path.display(),
+                revoked = self.revocations.len(),
+                "Flushed revocation list"
+            ),
+            Ok(None) => {}
+            Err(e) => tracing::error!(error = %e, "Failed to flush revocation list"),
+        }
+        // The bridge registry is in-memory only; nothing to flush
+    }
 }
 
-/// Wait for shutdown signal
-async fn shutdown_signal() {
+/// Listen on the socket systemd passed in, or else bind `addr`
+async fn listen(
+    name: &str,
+    inherited: Option<std::net::TcpListener>,
+    addr: SocketAddr,
+) -> Result<TcpListener> {
+    let Some(inherited) = inherited else {
+        return Ok(TcpListener::bind(addr).await?);
+    };
+    let listener = TcpListener::from_std(inherited)?;
+    info!(
+        listener = name,
+        addr = %listener.local_addr()?,
+        "Using socket-activated listener instead of {}",
+        addr
+    );
+    Ok(listener)
+}
+
+/// Warn that a CAT setting is accepted but cannot take effect until
+/// transitions decode PCAs
+fn warn_not_enforced(flag: &str) {
+    warn!(flag, "Setting has no effect yet: CAT transitions do not decode PCAs");
+}
+
+/// Delete removed bridges once their deletion grace has passed
+async fn purge_deleted_bridges(registry: Arc<BridgeRegistry>) {
+    let mut interval = tokio::time::interval(BRIDGE_PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        registry.purge_deleted(chrono::Utc::now());
+    }
+}
+
+/// Wait for shutdown signal, then drain.
+///
+/// gRPC health flips to NOT_SERVING first and the servers keep running for
+/// `drain_delay`, so load balancers stop routing before we refuse traffic.
+async fn shutdown_signal(
+    mut health: HealthReporter,
+    services: Vec<&'static str>,
+    drain_delay: Duration,
+) {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -213,5 +580,16 @@ async fn shutdown_signal() {
         _ = terminate => info!("Received SIGTERM"),
     }
 
+    // "" is the overall server status
+    health.set_service_status("", ServingStatus::NotServing).await;
+    for service in services {
+        health.set_service_status(service, ServingStatus::NotServing).await;
+    }
+    info!(
+        drain_delay_secs = drain_delay.as_secs(),
+        "gRPC health set to NOT_SERVING, draining"
+    );
+    tokio::time::sleep(drain_delay).await;
+
     info!("Shutting down gracefully...");
 }
\ No newline at end of file
@@ -0,0 +1,158 @@
+/*
+ * Copyright Nitro Agility S.r.l.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! systemd socket activation.
+//!
+//! When systemd starts us from a socket unit, the listening sockets are
+//! inherited as fds 3 and up: `LISTEN_FDS` says how many, `LISTEN_PID`
+//! which process they are meant for. Sockets named `http` or `grpc` in
+//! `LISTEN_FDNAMES` (`FileDescriptorName=`) serve that API; unnamed ones
+//! are taken in order, HTTP first. Listeners without an inherited socket
+//! bind their configured address as usual.
+
+use crate::error::{Error, Result};
+use std::net::TcpListener;
+
+/// First inherited fd (`SD_LISTEN_FDS_START`)
+const LISTEN_FDS_START: i32 = 3;
+
+/// Sockets passed in by systemd
+#[derive(Debug, Default)]
+pub(crate) struct ActivatedListeners {
+    pub http: Option<TcpListener>,
+    pub grpc: Option<TcpListener>,
+}
+
+/// Take the sockets systemd passed to this process, if any
+pub(crate) fn listeners() -> Result<ActivatedListeners> {
+    let var = |name| std::env::var(name).ok();
+    let fds = activated_fds(
+        var("LISTEN_PID").as_deref(),
+        var("LISTEN_FDS").as_deref(),
+        var("LISTEN_FDNAMES").as_deref(),
+        std::process::id(),
+    )?;
+    Ok(ActivatedListeners {
+        http: fds.http.map(listener_from_fd).transpose()?,
+        grpc: fds.grpc.map(listener_from_fd).transpose()?,
+    })
+}
+
+#[cfg(unix)]
+fn listener_from_fd(fd: i32) -> Result<TcpListener> {
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: systemd hands us ownership of fds LISTEN_FDS_START.. for this
+    // PID, and each is taken exactly once, here.
+    let listener = unsafe { TcpListener::from_raw_fd(fd) };
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+#[cfg(not(unix))]
+fn listener_from_fd(_fd: i32) -> Result<TcpListener> {
+    Err(Error::Config("socket activation requires a Unix platform".into()))
+}
+
+/// Inherited fd of each listener
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ActivatedFds {
+    http: Option<i32>,
+    grpc: Option<i32>,
+}
+
+fn activated_fds(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    fd_names: Option<&str>,
+    pid: u32,
+) -> Result<ActivatedFds> {
+    let (Some(listen_pid), Some(listen_fds)) = (listen_pid, listen_fds) else {
+        return Ok(ActivatedFds::default());
+    };
+    // Sockets passed to our parent are not ours to take
+    if listen_pid.trim().parse::<u32>().ok() != Some(pid) {
+        return Ok(ActivatedFds::default());
+    }
+    let count: i32 = listen_fds
+        .trim()
+        .parse()
+        .map_err(|_| Error::Config(format!("Invalid LISTEN_FDS: {}", listen_fds)))?;
+    let names: Vec<&str> = fd_names.map(|n| n.split(':').collect()).unwrap_or_default();
+
+    let mut fds = ActivatedFds::default();
+    let mut unnamed = Vec::new();
+    for (i, fd) in (LISTEN_FDS_START..LISTEN_FDS_START.saturating_add(count)).enumerate() {
+        let slot = match names.get(i).copied() {
+            Some("http") => &mut fds.http,
+            Some("grpc") => &mut fds.grpc,
+            _ => {
+                unnamed.push(fd);
+                continue;
+            }
+        };
+        if slot.replace(fd).is_some() {
+            return Err(Error::Config(format!(
+                "LISTEN_FDNAMES names more than one {} socket",
+                names[i]
+            )));
+        }
+    }
+    for fd in unnamed {
+        match (&fds.http, &fds.grpc) {
+            (None, _) => fds.http = Some(fd),
+            (_, None) => fds.grpc = Some(fd),
+            _ => {
+                return Err(Error::Config(format!(
+                    "{} sockets passed by systemd, expected at most one HTTP and one gRPC",
+                    count
+                )));
+            }
+        }
+    }
+    Ok(fds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activated_fds() {
+        let fds = |pid, count, names| activated_fds(pid, count, names, 42);
+        let expect = |http, grpc| ActivatedFds { http, grpc };
+
+        // Not activated, or activated for another process
+        assert_eq!(fds(None, None, None).unwrap(), expect(None, None));
+        assert_eq!(fds(Some("7"), Some("2"), None).unwrap(), expect(None, None));
+
+        // Unnamed sockets go to HTTP, then gRPC
+        assert_eq!(fds(Some("42"), Some("1"), None).unwrap(), expect(Some(3), None));
+        assert_eq!(fds(Some("42"), Some("2"), None).unwrap(), expect(Some(3), Some(4)));
+
+        // Named sockets go where they say
+        let named = fds(Some("42"), Some("2"), Some("grpc:http")).unwrap();
+        assert_eq!(named, expect(Some(4), Some(3)));
+        let named = fds(Some("42"), Some("1"), Some("grpc")).unwrap();
+        assert_eq!(named, expect(None, Some(3)));
+        let mixed = fds(Some("42"), Some("2"), Some("unknown:http")).unwrap();
+        assert_eq!(mixed, expect(Some(4), Some(3)));
+
+        assert!(fds(Some("42"), Some("3"), None).is_err());
+        assert!(fds(Some("42"), Some("2"), Some("http:http")).is_err());
+        assert!(fds(Some("42"), Some("two"), None).is_err());
+    }
+}